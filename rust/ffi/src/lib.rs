@@ -0,0 +1,3 @@
+//! the c bindings of life-game built as a shared library. see `life_game::ffi`.
+
+pub use life_game::ffi::*;
@@ -0,0 +1,40 @@
+/* c interface of the life-game engine. build the library with
+ *
+ *     cargo build --release -p life-game-ffi
+ *
+ * and link against target/release/liblife_game_ffi.so (.dylib, .dll).
+ * boards wrap around their edges and follow conway's rule B3/S23.
+ */
+#ifndef LIFE_GAME_H
+#define LIFE_GAME_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* a board and its generation. only used through pointers. */
+typedef struct Life Life;
+
+/* an empty board of width x height, or NULL if it is too big. free it with life_free. */
+Life *life_new(uint16_t width, uint16_t height);
+
+/* advance one generation. */
+void life_step(Life *life);
+
+/* 1 if the cell at x, y is alive, 0 if dead, -1 if it is off the board. */
+int32_t life_get(const Life *life, uint16_t x, uint16_t y);
+
+/* make the cell at x, y alive if alive isn't 0, dead otherwise. returns 0, or -1 if it is off
+ * the board. */
+int32_t life_set(Life *life, uint16_t x, uint16_t y, int32_t alive);
+
+/* free a board from life_new. NULL is ignored. */
+void life_free(Life *life);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
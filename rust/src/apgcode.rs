@@ -0,0 +1,470 @@
+//! apgcodes, the names catagolue gives small objects: `xs4_33` is a block, `xp2_7` a blinker and
+//! `xq4_153` a glider.
+//!
+//! the prefix says what it is: `xs` and its population for a still life, `xp` and its period for
+//! an oscillator, and `xq` and its period for a spaceship. the rest are its cells in extended
+//! wechsler format: strips of 5 rows from the top, each column of a strip a base 32 digit with
+//! the top row as the lowest bit, `w`, `x` and `y` runs of empty columns, and `z` between strips.
+//! of every phase and orientation, the code is the shortest, and the first in order of those.
+//!
+//! [`objects`] splits a board into the objects to encode, and [`name`] knows the common ones.
+
+use crate::{
+    engine,
+    rule::{Neighbourhood, Rule},
+    Size,
+};
+use alloc::{format, string::String, vec, vec::Vec};
+use anyhow::{anyhow, bail, ensure, Result};
+
+/// the digits of columns.
+const DIGITS: &[u8; 32] = b"0123456789abcdefghijklmnopqrstuv";
+/// the digits after `y`, a run of 4 and up to 39 empty columns.
+const RUNS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+/// longest period [`encode`] looks for.
+pub const MAX_PERIOD: usize = 64;
+
+/// what an apgcode names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// a still life of this population.
+    Still(usize),
+    /// an oscillator of this period.
+    Oscillator(usize),
+    /// a spaceship of this period.
+    Ship(usize),
+}
+
+/// live cells in their bounding box.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Object {
+    /// size of the bounding box.
+    pub size: Size,
+    /// whether each cell of the bounding box is alive, in rows.
+    pub cells: Vec<bool>,
+}
+
+impl Object {
+    /// the live cells of `cells`, a board of `size` in rows, cut to their bounding box. none if
+    /// there are none.
+    pub fn crop(size: Size, cells: &[bool]) -> Option<(Self, (usize, usize))> {
+        let width = usize::from(size.width);
+        let (mut left, mut top, mut right, mut bottom) = (usize::MAX, usize::MAX, 0, 0);
+        for (i, _) in cells.iter().enumerate().filter(|(_, &alive)| alive) {
+            let (x, y) = (i % width, i / width);
+            (left, top) = (left.min(x), top.min(y));
+            (right, bottom) = (right.max(x), bottom.max(y));
+        }
+        if left == usize::MAX {
+            return None;
+        }
+        let (w, h) = (right - left + 1, bottom - top + 1);
+        let cells = (0..w * h)
+            .map(|i| cells[(top + i / w) * width + left + i % w])
+            .collect();
+        let size = Size {
+            width: w as u16,
+            height: h as u16,
+        };
+        Some((Self { size, cells }, (left, top)))
+    }
+
+    /// live cells of the object.
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|&&alive| alive).count()
+    }
+
+    /// board of `size` with the object placed on its center.
+    pub fn centered(&self, size: Size) -> Result<Vec<bool>> {
+        ensure!(
+            self.size.width <= size.width && self.size.height <= size.height,
+            "the object needs {} cells, but board is {}.",
+            self.size,
+            size
+        );
+        let (width, w) = (usize::from(size.width), usize::from(self.size.width));
+        let ox = usize::from((size.width - self.size.width) / 2);
+        let oy = usize::from((size.height - self.size.height) / 2);
+        let mut board = vec![false; width * usize::from(size.height)];
+        for (i, _) in self.cells.iter().enumerate().filter(|(_, &alive)| alive) {
+            board[(oy + i / w) * width + ox + i % w] = true;
+        }
+        Ok(board)
+    }
+
+    /// the next generation under `rule` on a board `margin` cells bigger on every side, and
+    /// where it is on that board. none if it dies out.
+    fn step(&self, rule: &Rule, margin: usize) -> Result<Option<(Self, (isize, isize))>> {
+        let (w, h) = (usize::from(self.size.width), usize::from(self.size.height));
+        let (width, height) = (w + 2 * margin, h + 2 * margin);
+        ensure!(
+            width <= usize::from(u16::MAX) && height <= usize::from(u16::MAX),
+            "it is too big."
+        );
+        let size = Size {
+            width: width as u16,
+            height: height as u16,
+        };
+        let mut cells = vec![false; width * height];
+        for (i, _) in self.cells.iter().enumerate().filter(|(_, &alive)| alive) {
+            cells[(margin + i / w) * width + margin + i % w] = true;
+        }
+        let next = engine::step(size, rule, &cells, &vec![0; cells.len()]).cells;
+        Ok(Self::crop(size, &next).map(|(next, (x, y))| (next, (x as isize, y as isize))))
+    }
+
+    /// one of the 8 rotations and reflections: `turn & 4` swaps the axes, then `turn & 1`
+    /// mirrors left and right and `turn & 2` top and bottom.
+    fn oriented(&self, turn: u8) -> Self {
+        let (w, h) = (usize::from(self.size.width), usize::from(self.size.height));
+        let (nw, nh) = if turn & 4 != 0 { (h, w) } else { (w, h) };
+        let cells = (0..nw * nh)
+            .map(|i| {
+                let (x, y) = (i % nw, i / nw);
+                let (x, y) = if turn & 4 != 0 { (y, x) } else { (x, y) };
+                let x = if turn & 1 != 0 { w - 1 - x } else { x };
+                let y = if turn & 2 != 0 { h - 1 - y } else { y };
+                self.cells[y * w + x]
+            })
+            .collect();
+        let size = Size {
+            width: nw as u16,
+            height: nh as u16,
+        };
+        Self { size, cells }
+    }
+
+    /// the cells in extended wechsler format, as they are.
+    fn wechsler(&self) -> String {
+        let (w, h) = (usize::from(self.size.width), usize::from(self.size.height));
+        let mut strips = Vec::new();
+        for top in (0..h).step_by(5) {
+            let mut columns: Vec<usize> = (0..w)
+                .map(|x| {
+                    (0..5)
+                        .filter(|row| top + row < h && self.cells[(top + row) * w + x])
+                        .map(|row| 1 << row)
+                        .sum()
+                })
+                .collect();
+            while columns.last() == Some(&0) {
+                columns.pop();
+            }
+            let (mut strip, mut empty) = (String::new(), 0);
+            for column in columns {
+                if column == 0 {
+                    empty += 1;
+                    continue;
+                }
+                push_empty(&mut strip, empty);
+                empty = 0;
+                strip.push(char::from(DIGITS[column]));
+            }
+            strips.push(strip);
+        }
+        strips.join("z")
+    }
+}
+
+/// write a run of `n` empty columns.
+fn push_empty(strip: &mut String, mut n: usize) {
+    while n > 39 {
+        strip.push_str("yz");
+        n -= 39;
+    }
+    match n {
+        0 => {}
+        1 => strip.push('0'),
+        2 => strip.push('w'),
+        3 => strip.push('x'),
+        n => {
+            strip.push('y');
+            strip.push(char::from(RUNS[n - 4]));
+        }
+    }
+}
+
+impl Kind {
+    fn prefix(&self) -> String {
+        match self {
+            Self::Still(population) => format!("xs{}", population),
+            Self::Oscillator(period) => format!("xp{}", period),
+            Self::Ship(period) => format!("xq{}", period),
+        }
+    }
+}
+
+/// what `code` names, and its cells.
+pub fn decode(code: &str) -> Result<(Kind, Object)> {
+    let invalid = || anyhow!("not an apgcode: {}", code);
+    let (prefix, body) = code.split_once('_').ok_or_else(invalid)?;
+    let (kind, n) = prefix.split_at_checked(2).ok_or_else(invalid)?;
+    let n: usize = n.parse().map_err(|_| invalid())?;
+    let kind = match kind {
+        "xs" => Kind::Still(n),
+        "xp" => Kind::Oscillator(n),
+        "xq" => Kind::Ship(n),
+        _ => return Err(invalid()),
+    };
+    let (mut live, mut x, mut top) = (Vec::new(), 0, 0);
+    let mut chars = body.bytes();
+    while let Some(c) = chars.next() {
+        match c {
+            b'w' => x += 2,
+            b'x' => x += 3,
+            b'y' => {
+                let run = chars.next().and_then(|c| RUNS.iter().position(|&d| d == c));
+                x += 4 + run.ok_or_else(invalid)?;
+            }
+            b'z' => (x, top) = (0, top + 5),
+            c => {
+                let column = DIGITS.iter().position(|&d| d == c).ok_or_else(invalid)?;
+                live.extend(
+                    (0..5)
+                        .filter(|row| column >> row & 1 == 1)
+                        .map(|row| (x, top + row)),
+                );
+                x += 1;
+            }
+        }
+    }
+    let width = live.iter().map(|&(x, _)| x + 1).max().ok_or_else(invalid)?;
+    let height = live.iter().map(|&(_, y)| y + 1).max().unwrap_or(0);
+    ensure!(
+        width <= usize::from(u16::MAX) && height <= usize::from(u16::MAX),
+        "{} is too big.",
+        code
+    );
+    let size = Size {
+        width: width as u16,
+        height: height as u16,
+    };
+    let mut cells = vec![false; width * height];
+    for &(x, y) in &live {
+        cells[y * width + x] = true;
+    }
+    let (object, _) = Object::crop(size, &cells).ok_or_else(invalid)?;
+    if let Kind::Still(population) = kind {
+        ensure!(
+            object.population() == population,
+            "{} has {} cells, not {}.",
+            code,
+            object.population(),
+            population
+        );
+    }
+    Ok((kind, object))
+}
+
+/// the apgcode of `object` under `rule`, which has to be a still life, an oscillator or a
+/// spaceship of a period up to [`MAX_PERIOD`].
+pub fn encode(object: &Object, rule: &Rule) -> Result<String> {
+    ensure!(
+        rule.states() == 2
+            && !rule.is_wireworld()
+            && rule.wolfram().is_none()
+            && rule.neighbourhood() != Neighbourhood::Hex,
+        "apgcodes are for rules of live and dead cells on a square grid."
+    );
+    // a cell is born at most this far from the live cells, and the board around them is twice
+    // as wide not to wrap into them.
+    let margin = 2 * rule.larger().map_or(1, |larger| usize::from(larger.radius));
+    let (mut phases, mut moved) = (vec![object.clone()], (0, 0));
+    for generation in 1..=MAX_PERIOD {
+        let last = phases.last().unwrap_or(object);
+        let Some((phase, (x, y))) = last.step(rule, margin)? else {
+            bail!("it dies out.");
+        };
+        moved = (moved.0 + x - margin as isize, moved.1 + y - margin as isize);
+        if phase == *object {
+            let kind = match (generation, moved == (0, 0)) {
+                (1, true) => Kind::Still(object.population()),
+                (period, true) => Kind::Oscillator(period),
+                (period, false) => Kind::Ship(period),
+            };
+            let body = phases
+                .iter()
+                .flat_map(|phase| (0..8).map(|turn| phase.oriented(turn).wechsler()))
+                .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+                .unwrap_or_default();
+            return Ok(format!("{}_{}", kind.prefix(), body));
+        }
+        phases.push(phase);
+    }
+    bail!(
+        "it doesn't repeat in {} generations: it isn't a still life, an oscillator or a spaceship.",
+        MAX_PERIOD
+    )
+}
+
+/// the groups of live cells which touch, across or along, on a board of `size` in rows which
+/// wraps around its edges, e.g. the ash of a soup.
+pub fn objects(size: Size, cells: &[bool]) -> Vec<Object> {
+    let (width, height) = (size.width as isize, size.height as isize);
+    let mut seen = vec![false; cells.len()];
+    let mut objects = Vec::new();
+    for start in 0..cells.len() {
+        if !cells[start] || seen[start] {
+            continue;
+        }
+        seen[start] = true;
+        let (sx, sy) = (start as isize % width, start as isize / width);
+        // positions relative to the first cell, which go on past the edges.
+        let (mut todo, mut group) = (vec![(0, 0)], Vec::new());
+        while let Some((x, y)) = todo.pop() {
+            group.push((x, y));
+            for (dx, dy) in (-1..=1).flat_map(|dy| (-1..=1).map(move |dx| (dx, dy))) {
+                let j = ((sy + y + dy).rem_euclid(height) * width + (sx + x + dx).rem_euclid(width))
+                    as usize;
+                if cells[j] && !seen[j] {
+                    seen[j] = true;
+                    todo.push((x + dx, y + dy));
+                }
+            }
+        }
+        let left = group.iter().map(|&(x, _)| x).min().unwrap_or(0);
+        let top = group.iter().map(|&(_, y)| y).min().unwrap_or(0);
+        let w = group.iter().map(|&(x, _)| x - left + 1).max().unwrap_or(0) as usize;
+        let h = group.iter().map(|&(_, y)| y - top + 1).max().unwrap_or(0) as usize;
+        let mut object = vec![false; w * h];
+        for (x, y) in group {
+            object[(y - top) as usize * w + (x - left) as usize] = true;
+        }
+        let size = Size {
+            width: w as u16,
+            height: h as u16,
+        };
+        objects.push(Object {
+            size,
+            cells: object,
+        });
+    }
+    objects
+}
+
+/// names of common objects, each with its plural.
+const NAMES: &[(&str, &str, &str)] = &[
+    ("xs4_33", "block", "blocks"),
+    ("xs6_696", "beehive", "beehives"),
+    ("xs7_2596", "loaf", "loaves"),
+    ("xs5_253", "boat", "boats"),
+    ("xs6_356", "ship", "ships"),
+    ("xs4_252", "tub", "tubs"),
+    ("xs8_6996", "pond", "ponds"),
+    ("xs6_25a4", "barge", "barges"),
+    ("xs7_25ac", "long boat", "long boats"),
+    ("xp2_7", "blinker", "blinkers"),
+    ("xp2_7e", "toad", "toads"),
+    ("xp2_318c", "beacon", "beacons"),
+    ("xq4_153", "glider", "gliders"),
+    (
+        "xq4_6frc",
+        "lightweight spaceship",
+        "lightweight spaceships",
+    ),
+];
+
+/// the name of the object of `code`, or its plural, if it is a common one.
+pub fn name(code: &str, plural: bool) -> Option<&'static str> {
+    NAMES
+        .iter()
+        .find(|&&(known, _, _)| known == code)
+        .map(|&(_, one, many)| if plural { many } else { one })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decode_a_glider() -> Result<()> {
+        let (kind, glider) = decode("xq4_153")?;
+        assert_eq!(kind, Kind::Ship(4));
+        assert_eq!(
+            glider.size,
+            Size {
+                width: 3,
+                height: 3
+            }
+        );
+        assert_eq!(
+            glider.cells,
+            [true, true, true, false, false, true, false, true, false]
+        );
+        // runs of empty columns and strips.
+        let (_, far) = decode("xs4_11y011")?;
+        assert_eq!(
+            far.size,
+            Size {
+                width: 8,
+                height: 1
+            }
+        );
+        let (_, tall) = decode("xs2_1z1")?;
+        assert_eq!(
+            tall.size,
+            Size {
+                width: 1,
+                height: 6
+            }
+        );
+        assert!(decode("xs5_33").is_err());
+        assert!(decode("xs4_3!").is_err());
+        assert!(decode("glider").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn encode_canonically() -> Result<()> {
+        let life = Rule::default();
+        for code in [
+            "xs4_33", "xs6_696", "xs7_2596", "xs6_25a4", "xs5_253", "xp2_7", "xp2_318c", "xq4_153",
+            "xq4_6frc",
+        ] {
+            let (_, object) = decode(code)?;
+            assert_eq!(encode(&object, &life)?, code);
+            // any orientation has the same code.
+            assert_eq!(encode(&object.oriented(5), &life)?, code);
+        }
+        // the r-pentomino is none of them.
+        let size = Size {
+            width: 3,
+            height: 3,
+        };
+        let (pentomino, _) = Object::crop(
+            size,
+            &[false, true, true, true, true, false, false, true, false],
+        )
+        .unwrap();
+        assert!(encode(&pentomino, &life).is_err());
+        let (_, glider) = decode("xq4_153")?;
+        assert!(encode(&glider, &"23/3/3".parse()?).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn name_the_objects() -> Result<()> {
+        // every name is under the code encode gives.
+        for &(code, _, _) in NAMES {
+            assert_eq!(encode(&decode(code)?.1, &Rule::default())?, code);
+        }
+        assert_eq!(name("xs7_2596", true), Some("loaves"));
+        assert_eq!(name("xs14_g88m952z121", false), None);
+        // a blinker across the top and bottom edges, and a block.
+        let size = Size {
+            width: 7,
+            height: 6,
+        };
+        let board = ".X.....\n.X.....\n....XX.\n....XX.\n.......\n.X.....";
+        let cells: Vec<bool> = board
+            .lines()
+            .flat_map(|l| l.bytes().map(|b| b == b'X'))
+            .collect();
+        let codes: Vec<String> = objects(size, &cells)
+            .iter()
+            .map(|object| encode(object, &Rule::default()))
+            .collect::<Result<_>>()?;
+        assert_eq!(codes, ["xp2_7", "xs4_33"]);
+        Ok(())
+    }
+}
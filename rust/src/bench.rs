@@ -0,0 +1,94 @@
+use anyhow::Result;
+use clap::Args;
+use life_game::{
+    engine::{self, Generation},
+    rule::Rule,
+    Size,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::time::{Duration, Instant};
+
+/// a way to compute the next generation, as `engine::step`.
+type Step = fn(Size, &Rule, &[bool], &[u8]) -> Generation;
+
+/// every engine the board can be stepped with, by name.
+const ENGINES: &[(&str, Step)] = &[
+    ("naive", engine::step),
+    ("parallel", engine::step_parallel),
+    ("sparse", engine::step_sparse),
+];
+
+#[derive(Args, Debug)]
+pub(crate) struct BenchArgs {
+    #[arg(long, default_value = "1024:1024")]
+    size: Size,
+    #[arg(long, default_value = "1000")]
+    generations: usize,
+    #[arg(long, default_value = "B3/S23")]
+    rule: Rule,
+    #[arg(
+        long,
+        default_value = "0",
+        help = "seed of the random board every engine starts from."
+    )]
+    seed: u64,
+}
+
+/// time `step` over `generations` generations from `cells`.
+fn time(step: Step, args: &BenchArgs, cells: &[bool]) -> Duration {
+    let (mut cells, mut dying) = (cells.to_vec(), vec![0; cells.len()]);
+    let start = Instant::now();
+    for _ in 0..args.generations {
+        let next = step(args.size, &args.rule, &cells, &dying);
+        (cells, dying) = (next.cells, next.dying);
+    }
+    start.elapsed()
+}
+
+fn report(name: &str, generations: usize, elapsed: Duration) -> String {
+    format!(
+        "{}\t{} generations in {:.2?}\t{:.1} generations/s",
+        name,
+        generations,
+        elapsed,
+        generations as f64 / elapsed.as_secs_f64().max(f64::EPSILON)
+    )
+}
+
+pub(crate) fn bench(args: &BenchArgs) -> Result<()> {
+    let mut rng = StdRng::seed_from_u64(args.seed);
+    let mut cells = vec![false; args.size.width as usize * args.size.height as usize];
+    rng.fill(&mut cells[..]);
+    for &(name, step) in ENGINES {
+        println!(
+            "{}",
+            report(name, args.generations, time(step, args, &cells))
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn time_every_engine() {
+        let args = BenchArgs {
+            size: Size {
+                width: 16,
+                height: 16,
+            },
+            generations: 3,
+            rule: Rule::default(),
+            seed: 0,
+        };
+        for &(_, step) in ENGINES {
+            assert!(time(step, &args, &[true; 256]) > Duration::ZERO);
+        }
+        assert_eq!(
+            report("naive", 10, Duration::from_secs(2)),
+            "naive\t10 generations in 2.00s\t5.0 generations/s"
+        );
+    }
+}
@@ -0,0 +1,292 @@
+use anyhow::{anyhow, Result};
+use clap::Args;
+use life_game::{apgcode, generate::Symmetry, rule::Rule, Game, Size};
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    thread::{available_parallelism, scope},
+};
+
+/// generations the population has to repeat for a board with moving objects to count as settled.
+const SETTLED: usize = 100;
+/// longest period of the population looked for.
+const MAX_PERIOD: usize = 30;
+
+#[derive(Args, Debug)]
+pub(crate) struct CensusArgs {
+    #[arg(
+        long,
+        default_value = "128:128",
+        help = "the board, big enough that ships don't wrap around into the ash soon."
+    )]
+    size: Size,
+    #[arg(long, default_value = "16:16", help = "the random soup in the middle.")]
+    soup_size: Size,
+    #[arg(long, default_value = "100", help = "soups, seeded 0..N.")]
+    soups: u64,
+    #[arg(long, default_value = "2000", help = "max generations of each soup.")]
+    generations: usize,
+    #[arg(long, default_value = "B3/S23")]
+    rule: Rule,
+    #[arg(long, value_enum, ignore_case = true, default_value = "C1")]
+    symmetry: Symmetry,
+    #[arg(long, help = "worker threads. default: available cores.")]
+    threads: Option<usize>,
+}
+
+/// how a soup ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Outcome {
+    Extinct,
+    StillLife,
+    /// the whole board repeats with this period.
+    Oscillator(usize),
+    /// the board never repeats, but its population does, i.e. ships fly away from the ash.
+    Ships,
+    /// still active after the last generation.
+    Unsettled,
+}
+
+#[derive(Debug)]
+struct Soup {
+    seed: u64,
+    outcome: Outcome,
+    generations: usize,
+    population: usize,
+    /// apgcodes of the objects it left once it settled.
+    objects: Vec<String>,
+    /// objects left which are no still life, oscillator or spaceship on their own, e.g. ones
+    /// which a spaceship flies into.
+    unidentified: usize,
+}
+
+/// period of the population over the last `SETTLED` generations, if it has one.
+fn population_period(game: &Game) -> Option<usize> {
+    let history = &game.state.history;
+    let recent = &history[history.len().checked_sub(SETTLED + MAX_PERIOD)?..];
+    (1..=MAX_PERIOD).find(|&p| {
+        recent[MAX_PERIOD..]
+            .iter()
+            .zip(&recent[MAX_PERIOD - p..])
+            .all(|(a, b)| a.population == b.population)
+    })
+}
+
+/// step `game` until it settles, for up to `generations` generations.
+fn settle(game: &mut Game, generations: usize) -> Result<Outcome> {
+    for _ in 0..generations {
+        game.step()?;
+        if game.state.population == 0 {
+            return Ok(Outcome::Extinct);
+        }
+        match game.state.cycle {
+            Some((_, 1)) => return Ok(Outcome::StillLife),
+            Some((_, period)) => return Ok(Outcome::Oscillator(period)),
+            None => {}
+        }
+        if population_period(game).is_some() {
+            return Ok(Outcome::Ships);
+        }
+    }
+    Ok(Outcome::Unsettled)
+}
+
+fn run_one(args: &CensusArgs, seed: u64) -> Result<Soup> {
+    let mut game = Game::blank(args.size)?;
+    game.state.rule = args.rule;
+    game.state.soup = Some(args.soup_size);
+    game.state.symmetry = args.symmetry;
+    game.random_from(seed)?;
+    let outcome = settle(&mut game, args.generations)?;
+    let (mut objects, mut unidentified) = (Vec::new(), 0);
+    if outcome != Outcome::Unsettled {
+        for object in apgcode::objects(game.state.size, &game.game) {
+            match apgcode::encode(&object, &args.rule) {
+                Ok(code) => objects.push(code),
+                Err(_) => unidentified += 1,
+            }
+        }
+    }
+    Ok(Soup {
+        seed,
+        outcome,
+        generations: game.state.time,
+        population: game.state.population,
+        objects,
+        unidentified,
+    })
+}
+
+/// the objects the soups left, most common first, e.g. "12 blocks, 5 blinkers, 3 gliders
+/// escaped". objects without a name go by their apgcode.
+fn objects(soups: &[Soup]) -> String {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for code in soups.iter().flat_map(|s| &s.objects) {
+        *counts.entry(code).or_default() += 1;
+    }
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let mut objects: Vec<String> = counts
+        .into_iter()
+        .map(|(code, count)| {
+            let name = apgcode::name(code, count != 1).unwrap_or(code);
+            let escaped = if code.starts_with("xq") {
+                " escaped"
+            } else {
+                ""
+            };
+            format!("{} {}{}", count, name, escaped)
+        })
+        .collect();
+    let unidentified: usize = soups.iter().map(|s| s.unidentified).sum();
+    if unidentified > 0 {
+        objects.push(format!("{} unidentified", unidentified));
+    }
+    if objects.is_empty() {
+        return "none".to_string();
+    }
+    objects.join(", ")
+}
+
+fn summary(soups: &[Soup]) -> String {
+    let mut outcomes: BTreeMap<Outcome, usize> = BTreeMap::new();
+    for soup in soups {
+        *outcomes.entry(soup.outcome).or_default() += 1;
+    }
+    let n = soups.len().max(1) as f64;
+    let mut data = format!("soups\t{}\n", soups.len());
+    for (outcome, count) in outcomes {
+        let name = match outcome {
+            Outcome::Extinct => "extinct".to_string(),
+            Outcome::StillLife => "still lifes".to_string(),
+            Outcome::Oscillator(period) => format!("oscillators p{}", period),
+            Outcome::Ships => "ships escaped".to_string(),
+            Outcome::Unsettled => "unsettled".to_string(),
+        };
+        let _ = writeln!(
+            data,
+            "{}\t{} ({:.1}%)",
+            name,
+            count,
+            count as f64 * 100.0 / n
+        );
+    }
+    let _ = writeln!(data, "objects\t{}", objects(soups));
+    let _ = writeln!(
+        data,
+        "mean generations\t{:.1}\nmean final population\t{:.1}",
+        soups.iter().map(|s| s.generations).sum::<usize>() as f64 / n,
+        soups.iter().map(|s| s.population).sum::<usize>() as f64 / n,
+    );
+    data
+}
+
+/// run every soup headlessly on worker threads.
+fn run_all(args: &CensusArgs) -> Result<Vec<Soup>> {
+    let threads = args
+        .threads
+        .unwrap_or_else(|| available_parallelism().map_or(1, |n| n.get()))
+        .clamp(1, args.soups.max(1) as usize);
+
+    let next = AtomicU64::new(0);
+    let soups = Mutex::new(Vec::with_capacity(args.soups as usize));
+    scope(|s| -> Result<()> {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                s.spawn(|| -> Result<()> {
+                    loop {
+                        let seed = next.fetch_add(1, Ordering::Relaxed);
+                        if seed >= args.soups {
+                            return Ok(());
+                        }
+                        let soup = run_one(args, seed)?;
+                        soups.lock().map_err(|_| anyhow!("poisoned"))?.push(soup);
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().map_err(|_| anyhow!("worker panicked"))??;
+        }
+        Ok(())
+    })?;
+
+    let mut soups = soups.into_inner().map_err(|_| anyhow!("poisoned"))?;
+    soups.sort_by_key(|s| s.seed);
+    Ok(soups)
+}
+
+pub(crate) fn census(args: &CensusArgs) -> Result<()> {
+    print!("{}", summary(&run_all(args)?));
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use life_game::patterns::Pattern;
+
+    #[test]
+    fn classify_outcomes() -> Result<()> {
+        let outcome = |pattern: &str| -> Result<Outcome> {
+            let size = "40:40".parse()?;
+            let mut game = Game::from_board(size, pattern.parse::<Pattern>()?.centered(size)?)?;
+            settle(&mut game, 300)
+        };
+        assert_eq!(outcome("block")?, Outcome::StillLife);
+        assert_eq!(outcome("blinker")?, Outcome::Oscillator(2));
+        assert_eq!(outcome("glider")?, Outcome::Ships);
+        let mut game = Game::blank("4:4".parse()?)?;
+        game.set_pos((1, 1))?;
+        assert_eq!(settle(&mut game, 10)?, Outcome::Extinct);
+        Ok(())
+    }
+
+    #[test]
+    fn census_runs_every_soup() -> Result<()> {
+        let args = CensusArgs {
+            size: "32:32".parse()?,
+            soup_size: "8:8".parse()?,
+            soups: 5,
+            generations: 200,
+            rule: Rule::default(),
+            symmetry: Symmetry::C1,
+            threads: Some(2),
+        };
+        let soups = run_all(&args)?;
+        assert_eq!(
+            soups.iter().map(|s| s.seed).collect::<Vec<_>>(),
+            [0, 1, 2, 3, 4]
+        );
+        // seeded soups are reproducible.
+        assert_eq!(run_one(&args, 3)?.outcome, soups[3].outcome);
+        assert!(summary(&soups).starts_with("soups\t5\n"));
+        assert!(summary(&soups).contains("\nobjects\t"));
+        Ok(())
+    }
+
+    #[test]
+    fn count_objects() {
+        let soup = |objects: &[&str], unidentified| Soup {
+            seed: 0,
+            outcome: Outcome::Ships,
+            generations: 0,
+            population: 0,
+            objects: objects.iter().map(|code| code.to_string()).collect(),
+            unidentified,
+        };
+        assert_eq!(objects(&[]), "none");
+        let soups = [
+            soup(&["xs4_33", "xp2_7", "xq4_153", "xs4_33"], 0),
+            soup(&["xs7_2596", "xs4_33", "xs14_g88m952z121"], 1),
+        ];
+        assert_eq!(
+            objects(&soups),
+            "3 blocks, 1 blinker, 1 glider escaped, 1 xs14_g88m952z121, 1 loaf, 1 unidentified"
+        );
+    }
+}
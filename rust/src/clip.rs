@@ -0,0 +1,128 @@
+//! pieces of a board and the shapes the editor selects: lines and rectangles.
+
+/// rectangular piece of a board, used by the editor's yank/cut/paste.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clip {
+    /// columns of the piece.
+    pub width: u16,
+    /// rows of the piece.
+    pub height: u16,
+    /// row-major, `width * height` long.
+    pub cells: Vec<bool>,
+}
+
+/// cells of the straight line from `a` to `b`, both included, by Bresenham's algorithm.
+pub fn line(a: (u16, u16), b: (u16, u16)) -> Vec<(u16, u16)> {
+    let (mut x, mut y) = (i32::from(a.0), i32::from(a.1));
+    let (x1, y1) = (i32::from(b.0), i32::from(b.1));
+    let (dx, dy) = ((x1 - x).abs(), -(y1 - y).abs());
+    let (sx, sy) = ((x1 - x).signum(), (y1 - y).signum());
+    let mut err = dx + dy;
+    let mut cells = Vec::new();
+    loop {
+        cells.push((x as u16, y as u16));
+        if (x, y) == (x1, y1) {
+            return cells;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+    }
+}
+
+/// normalize two corners into (top-left, bottom-right), both inclusive.
+pub fn rect(a: (u16, u16), b: (u16, u16)) -> ((u16, u16), (u16, u16)) {
+    ((a.0.min(b.0), a.1.min(b.1)), (a.0.max(b.0), a.1.max(b.1)))
+}
+
+/// whether `pos` is inside the rectangle spanned by `a` and `b`.
+pub fn contains(a: (u16, u16), b: (u16, u16), pos: (u16, u16)) -> bool {
+    let ((x0, y0), (x1, y1)) = rect(a, b);
+    (x0..=x1).contains(&pos.0) && (y0..=y1).contains(&pos.1)
+}
+
+impl Clip {
+    fn get(&self, x: u16, y: u16) -> bool {
+        self.cells[y as usize * self.width as usize + x as usize]
+    }
+
+    /// rotate 90° clockwise.
+    pub fn rotate(&self) -> Self {
+        let (width, height) = (self.height, self.width);
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| self.get(y, self.height - 1 - x))
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// mirror left and right.
+    pub fn flip_horizontal(&self) -> Self {
+        let cells = self
+            .cells
+            .chunks(self.width as usize)
+            .flat_map(|row| row.iter().rev().copied())
+            .collect();
+        Self {
+            cells,
+            ..self.clone()
+        }
+    }
+
+    /// mirror top and bottom.
+    pub fn flip_vertical(&self) -> Self {
+        let cells = self
+            .cells
+            .chunks(self.width as usize)
+            .rev()
+            .flatten()
+            .copied()
+            .collect();
+        Self {
+            cells,
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn clip(width: u16, rows: &[&str]) -> Clip {
+        Clip {
+            width,
+            height: rows.len() as u16,
+            cells: rows.concat().chars().map(|c| c == '@').collect(),
+        }
+    }
+
+    #[test]
+    fn transforms() {
+        // @@@
+        // @--
+        let l = clip(3, &["@@@", "@--"]);
+        assert_eq!(l.rotate(), clip(2, &["@@", "-@", "-@"]));
+        assert_eq!(l.flip_horizontal(), clip(3, &["@@@", "--@"]));
+        assert_eq!(l.flip_vertical(), clip(3, &["@--", "@@@"]));
+        assert_eq!(l.rotate().rotate().rotate().rotate(), l);
+    }
+
+    #[test]
+    fn lines() {
+        assert_eq!(line((1, 1), (1, 1)), [(1, 1)]);
+        assert_eq!(line((0, 0), (3, 1)), [(0, 0), (1, 0), (2, 1), (3, 1)]);
+        assert_eq!(line((2, 3), (0, 0)), [(2, 3), (1, 2), (1, 1), (0, 0)]);
+        assert_eq!(line((0, 2), (2, 0)), [(0, 2), (1, 1), (2, 0)]);
+    }
+}
@@ -0,0 +1,158 @@
+use anyhow::{anyhow, bail, ensure, Error, Result};
+use life_game::{point_from_str, rule::Rule, Anchor, Size, Transform};
+use std::{path::PathBuf, str::FromStr};
+
+/// a command typed on the `:` command line.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Cmd {
+    /// write the board to a file. `.rle` files are written as rle.
+    Save(PathBuf),
+    /// replace the board with a built-in pattern, an apgcode or a file.
+    Load(String),
+    /// stamp a built-in pattern, an apgcode or a file at the cursor, anchored at its top left or
+    /// middle, wrapping around the edges or not.
+    Read(String, Anchor, bool),
+    /// put a built-in pattern, an apgcode or a file in the clipboard, to place it at the cursor.
+    Yank(String),
+    /// switch the rule, keeping the board.
+    Rule(Rule),
+    /// grow or crop the board, keeping the cells where the anchor says.
+    Resize(Size, Anchor),
+    /// cut the board down to its live cells and this many dead cells around them.
+    Trim(u16),
+    /// shift, rotate or mirror the whole board.
+    Transform(Transform),
+    /// move the cursor and the view to a cell.
+    Goto((u16, u16)),
+    /// kill every cell, and count generations from 0 again if true.
+    Clear(bool),
+    /// set the name written into saves.
+    Name(String),
+    /// write a picture of the board, as a png or an svg.
+    Export(PathBuf),
+}
+
+impl FromStr for Cmd {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (name, arg) = s
+            .split_once(char::is_whitespace)
+            .map_or((s, ""), |(name, arg)| (name, arg.trim()));
+        Ok(match (name, arg) {
+            ("save" | "w", path) if !path.is_empty() => Self::Save(path.into()),
+            ("load" | "e", what) if !what.is_empty() => Self::Load(what.to_string()),
+            ("read" | "r", arg) => {
+                // options come after the file, which may have spaces.
+                let (mut what, mut anchor, mut wrap) = (arg, Anchor::TopLeft, false);
+                while let Some((rest, option)) = what.rsplit_once(char::is_whitespace) {
+                    match option {
+                        "center" => anchor = Anchor::Center,
+                        "corner" => anchor = Anchor::TopLeft,
+                        "wrap" => wrap = true,
+                        _ => break,
+                    }
+                    what = rest.trim_end();
+                }
+                ensure!(!what.is_empty(), "read what? give a pattern or a file.");
+                Self::Read(what.to_string(), anchor, wrap)
+            }
+            ("yank", what) if !what.is_empty() => Self::Yank(what.to_string()),
+            ("rule", rule) => Self::Rule(rule.parse()?),
+            ("resize", arg) => {
+                let (size, anchor) = arg.split_once(char::is_whitespace).unwrap_or((arg, ""));
+                let anchor = match anchor.trim() {
+                    "" | "center" => Anchor::Center,
+                    "corner" => Anchor::TopLeft,
+                    a => bail!("unknown anchor: {}. use center or corner.", a),
+                };
+                Self::Resize(size.parse()?, anchor)
+            }
+            ("shift", amount) => {
+                let (dx, dy) = amount
+                    .split_once(',')
+                    .ok_or_else(|| anyhow!(r#"note:: you must use a "shift <dx>,<dy>" format."#))?;
+                Self::Transform(Transform::Shift(dx.trim().parse()?, dy.trim().parse()?))
+            }
+            ("trim", "") => Self::Trim(0),
+            ("trim", padding) => Self::Trim(padding.parse()?),
+            ("rotate", "") => Self::Transform(Transform::Rotate),
+            ("flip", "h") => Self::Transform(Transform::FlipHorizontal),
+            ("flip", "v") => Self::Transform(Transform::FlipVertical),
+            ("flip", _) => bail!("flip needs h or v."),
+            ("goto", pos) => Self::Goto(point_from_str(&pos.replace(',', ":"))?),
+            ("clear", "") => Self::Clear(false),
+            ("clear", "reset") => Self::Clear(true),
+            ("clear", _) => bail!("clear takes only reset."),
+            ("name", name) if !name.is_empty() => Self::Name(name.to_string()),
+            ("export", path) if !path.is_empty() => Self::Export(path.into()),
+            ("save" | "w" | "load" | "e" | "name" | "export", _) => {
+                bail!("{} needs an argument.", name)
+            }
+            _ => bail!("unknown command: {}", s),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_commands() -> Result<()> {
+        assert_eq!("save foo.rle".parse::<Cmd>()?, Cmd::Save("foo.rle".into()));
+        assert_eq!(" load glider ".parse::<Cmd>()?, Cmd::Load("glider".into()));
+        assert_eq!(
+            "read my glider.rle center wrap".parse::<Cmd>()?,
+            Cmd::Read("my glider.rle".into(), Anchor::Center, true)
+        );
+        assert_eq!(
+            "read glider".parse::<Cmd>()?,
+            Cmd::Read("glider".into(), Anchor::TopLeft, false)
+        );
+        assert!("read".parse::<Cmd>().is_err());
+        assert_eq!("yank glider".parse::<Cmd>()?, Cmd::Yank("glider".into()));
+        assert_eq!(
+            "rule B36/S23".parse::<Cmd>()?,
+            Cmd::Rule("B36/S23".parse()?)
+        );
+        assert_eq!(
+            "resize 80:20".parse::<Cmd>()?,
+            Cmd::Resize("80:20".parse()?, Anchor::Center)
+        );
+        assert_eq!(
+            "resize 80:20 corner".parse::<Cmd>()?,
+            Cmd::Resize("80:20".parse()?, Anchor::TopLeft)
+        );
+        assert!("resize 80:20 left".parse::<Cmd>().is_err());
+        assert_eq!(
+            "shift -3, 2".parse::<Cmd>()?,
+            Cmd::Transform(Transform::Shift(-3, 2))
+        );
+        assert_eq!(
+            "flip v".parse::<Cmd>()?,
+            Cmd::Transform(Transform::FlipVertical)
+        );
+        assert!("shift 3".parse::<Cmd>().is_err());
+        assert_eq!("trim".parse::<Cmd>()?, Cmd::Trim(0));
+        assert_eq!("trim 4".parse::<Cmd>()?, Cmd::Trim(4));
+        assert_eq!("goto 40,10".parse::<Cmd>()?, Cmd::Goto((40, 10)));
+        assert_eq!("goto 40:10".parse::<Cmd>()?, Cmd::Goto((40, 10)));
+        assert_eq!("clear".parse::<Cmd>()?, Cmd::Clear(false));
+        assert_eq!("clear reset".parse::<Cmd>()?, Cmd::Clear(true));
+        assert_eq!(
+            "export board.svg".parse::<Cmd>()?,
+            Cmd::Export("board.svg".into())
+        );
+        assert!("export".parse::<Cmd>().is_err());
+        assert!("clear all".parse::<Cmd>().is_err());
+        assert_eq!(
+            "name two gliders".parse::<Cmd>()?,
+            Cmd::Name("two gliders".into())
+        );
+        assert!("save".parse::<Cmd>().is_err());
+        assert!("fly".parse::<Cmd>().is_err());
+        Ok(())
+    }
+}
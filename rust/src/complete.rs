@@ -0,0 +1,84 @@
+use std::{fs::read_dir, path::Path};
+
+/// complete the last word of `line` as a path, as far as every match agrees.
+/// also returns the matches when there is more than one.
+pub(crate) fn complete(line: &str) -> (String, Vec<String>) {
+    let start = line.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+    let (head, word) = line.split_at(start);
+    let (dir, prefix) = word
+        .rsplit_once('/')
+        .map_or(("", word), |(dir, prefix)| (&word[..dir.len() + 1], prefix));
+    let mut names = matches(if dir.is_empty() { "." } else { dir }, prefix);
+    let Some(first) = names.first() else {
+        return (line.to_string(), names);
+    };
+    let common = names.iter().fold(first.clone(), |common, name| {
+        common
+            .chars()
+            .zip(name.chars())
+            .take_while(|(a, b)| a == b)
+            .map(|(a, _)| a)
+            .collect()
+    });
+    let completed = format!("{}{}{}", head, dir, common);
+    if names.len() == 1 {
+        names.clear();
+    }
+    (completed, names)
+}
+
+/// sorted entries of `dir` starting with `prefix`. directories end with `/`.
+/// hidden entries only show up if `prefix` starts with a dot.
+fn matches(dir: &str, prefix: &str) -> Vec<String> {
+    let Ok(entries) = read_dir(Path::new(dir)) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            Some(if entry.path().is_dir() {
+                name + "/"
+            } else {
+                name
+            })
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs::{create_dir_all, write};
+
+    #[test]
+    fn complete_paths() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        create_dir_all(dir.join("saves"))?;
+        write(dir.join("glider.rle"), "")?;
+        write(dir.join("gun.txt"), "")?;
+        write(dir.join(".hidden"), "")?;
+        let base = format!("{}/", dir.display());
+
+        let (line, names) = complete(&format!(":e {}gl", base));
+        assert_eq!(line, format!(":e {}glider.rle", base));
+        assert!(names.is_empty());
+        let (line, names) = complete(&format!("{}g", base));
+        assert_eq!(line, format!("{}g", base));
+        assert_eq!(names, ["glider.rle", "gun.txt"]);
+        assert_eq!(complete(&format!("{}s", base)).0, format!("{}saves/", base));
+        assert_eq!(complete(&base).1, ["glider.rle", "gun.txt", "saves/"]);
+        assert_eq!(
+            complete(&format!("{}x", base)),
+            (format!("{}x", base), vec![])
+        );
+
+        Ok(())
+    }
+}
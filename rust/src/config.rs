@@ -0,0 +1,97 @@
+use crate::keys::{Action, Key};
+use anyhow::{Context, Result};
+use crossterm::style::Color;
+use life_game::{render::Style, rule::Rule, Glyphs, Size};
+use serde::Deserialize;
+use std::{collections::HashMap, env::var_os, fs::read_to_string, path::PathBuf};
+
+/// settings read from `config.toml`. everything is optional, and the command line wins.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Config {
+    pub(crate) size: Option<Size>,
+    /// same format as `--duration`, e.g. `"250ms"`.
+    pub(crate) duration: Option<String>,
+    pub(crate) rule: Option<Rule>,
+    pub(crate) glyphs: Glyphs,
+    pub(crate) colors: Colors,
+    /// pictures of the board, e.g. `scale = 4`, `alive = "#20ff0a"` and `grid = "#404040"`.
+    pub(crate) picture: Style,
+    /// where saves are written. default: the current directory.
+    pub(crate) save_dir: Option<PathBuf>,
+    /// action -> key, e.g. `left = "a"`.
+    pub(crate) keys: HashMap<Action, Key>,
+}
+
+/// colors of the whole screen, e.g. `"green"`, `"dark_grey"`, `"rgb_(0,64,0)"` or `"ansi_(22)"`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Colors {
+    pub(crate) foreground: Option<Color>,
+    pub(crate) background: Option<Color>,
+}
+
+/// `$XDG_CONFIG_HOME/life-game/config.toml`, or `~/.config/life-game/config.toml`.
+fn default_path() -> Option<PathBuf> {
+    var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("life-game").join("config.toml"))
+}
+
+impl Config {
+    /// read `path`, or the default config file if there is one.
+    pub(crate) fn load(path: Option<PathBuf>) -> Result<Self> {
+        let Some(path) = path.or_else(|| default_path().filter(|p| p.exists())) else {
+            return Ok(Self::default());
+        };
+        let text = read_to_string(&path).with_context(|| format!("{}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("{}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_config() -> Result<()> {
+        let config: Config = toml::from_str(
+            r##"
+            size = "40:20"
+            duration = "50ms"
+            rule = "B36/S23"
+            save_dir = "saves"
+
+            [glyphs]
+            alive = "#"
+            dead = " "
+
+            [colors]
+            foreground = "green"
+
+            [picture]
+            scale = 4
+            alive = "#20ff0a"
+            grid = "#404040"
+
+            [keys]
+            quit = "Q"
+            step = "space"
+            "##,
+        )?;
+        assert_eq!(config.size.map(|s| (s.width, s.height)), Some((40, 20)));
+        assert_eq!(config.rule, Some("B36/S23".parse()?));
+        assert_eq!((config.glyphs.alive, config.glyphs.dead), ('#', ' '));
+        assert_eq!(config.glyphs.alive_selected, '#');
+        assert_eq!(config.colors.foreground, Some(Color::Green));
+        assert_eq!(config.picture.scale, 4);
+        assert_eq!(config.picture.alive, "#20ff0a".parse()?);
+        assert_eq!(config.picture.dead, Style::default().dead);
+        assert_eq!(config.picture.grid, Some("#404040".parse()?));
+        assert_eq!(config.keys[&Action::Quit], "Q".parse()?);
+        assert!(toml::from_str::<Config>("[keys]\nfly = \"f\"\n").is_err());
+        assert!(toml::from_str::<Config>("colour = 1\n").is_err());
+        Ok(())
+    }
+}
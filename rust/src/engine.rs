@@ -0,0 +1,644 @@
+//! the stepping logic on its own. it only needs `alloc`, so it builds with the `std` feature off,
+//! e.g. for an led matrix driven by a microcontroller.
+
+use crate::{
+    rule::{Neighbourhood, Rule, CONDUCTOR},
+    script::Script,
+    Size,
+};
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// indices of the 8 neighbours of `idx` on a board of `size` which wraps around the edges.
+pub fn neighbours(size: Size, idx: usize) -> [usize; 8] {
+    // cu ru rm rd cd ld lm lu
+    let width = size.width as usize;
+    let len = width * size.height as usize;
+
+    let left_weight = if idx.is_multiple_of(width) { width } else { 0 };
+    let right_weight = if idx % width == width - 1 { width } else { 0 };
+
+    let cu = (len + idx - width) % len;
+    let cd = (len + idx + width) % len;
+
+    let ru = (len + idx + 1 - right_weight - width) % len;
+    let rm = (len + idx + 1 - right_weight) % len;
+    let rd = (len + idx + 1 - right_weight + width) % len;
+
+    let lu = (len + idx - 1 + left_weight - width) % len;
+    let lm = (len + idx - 1 + left_weight) % len;
+    let ld = (len + idx - 1 + left_weight + width) % len;
+
+    [cu, ru, rm, rd, cd, ld, lm, lu]
+}
+
+/// indices of the neighbours of `idx` in `neighbourhood` on a board of `size` which wraps around
+/// the edges.
+pub fn neighbours_in(
+    size: Size,
+    neighbourhood: Neighbourhood,
+    idx: usize,
+) -> impl Iterator<Item = usize> {
+    let [cu, ru, rm, rd, cd, ld, lm, lu] = neighbours(size, idx);
+    let odd = (idx / size.width as usize) % 2 == 1;
+    let (picked, len) = match neighbourhood {
+        Neighbourhood::Moore => ([cu, ru, rm, rd, cd, ld, lm, lu], 8),
+        Neighbourhood::VonNeumann => ([cu, rm, cd, lm, 0, 0, 0, 0], 4),
+        // odd rows are half a cell to the right, so they touch the cells above and below them and
+        // the ones to the right of those.
+        Neighbourhood::Hex if odd => ([cu, ru, rm, rd, cd, lm, 0, 0], 6),
+        Neighbourhood::Hex => ([lu, cu, rm, cd, ld, lm, 0, 0], 6),
+    };
+    picked.into_iter().take(len)
+}
+
+/// state of every cell: 0 dead, 1 alive, 2 and up dying, or the colour of a live cell.
+pub fn states(cells: &[bool], dying: &[u8]) -> Vec<u8> {
+    cells
+        .iter()
+        .zip(dying)
+        .map(|(&alive, &state)| if alive { state.max(1) } else { state })
+        .collect()
+}
+
+/// `states` under `rule` back into the live cells and the dying states or colours.
+pub fn split(states: &[u8], rule: &Rule) -> (Vec<bool>, Vec<u8>) {
+    states
+        .iter()
+        .map(|&state| (rule.alive(state), if state > 1 { state } else { 0 }))
+        .unzip()
+}
+
+/// a board after one step.
+#[derive(Debug)]
+pub struct Generation {
+    /// whether each cell is alive, in rows.
+    pub cells: Vec<bool>,
+    /// state of every dying cell, 2 and up, or the colour of a live cell under a coloured rule,
+    /// and 0 for the rest. all 0 under a life-like rule.
+    pub dying: Vec<u8>,
+    /// cells which became alive.
+    pub births: usize,
+    /// cells which stopped being alive, dying or dead.
+    pub deaths: usize,
+}
+
+impl Generation {
+    /// flip every cell `flip` picks, in order, e.g. at random to add noise. flipped cells are
+    /// plain live or dead cells, which lose their dying state or colour.
+    pub fn perturb(&mut self, mut flip: impl FnMut() -> bool) {
+        for (alive, state) in self.cells.iter_mut().zip(&mut self.dying) {
+            if !flip() {
+                continue;
+            }
+            *alive = !*alive;
+            *state = 0;
+            if *alive {
+                self.births += 1;
+            } else {
+                self.deaths += 1;
+            }
+        }
+    }
+}
+
+/// the generation after `cells` and their `dying` states, a board of `size` in rows, under `rule`.
+pub fn step(size: Size, rule: &Rule, cells: &[bool], dying: &[u8]) -> Generation {
+    if let Some(code) = rule.wolfram() {
+        return elementary(size, code, cells);
+    }
+    let counts = neighbour_counts(size, rule, cells);
+    step_range(size, rule, cells, dying, &counts, 0..cells.len())
+}
+
+/// [`step`] with the board cut into bands of rows, one per core, each stepped on its own thread.
+#[cfg(feature = "std")]
+pub fn step_parallel(size: Size, rule: &Rule, cells: &[bool], dying: &[u8]) -> Generation {
+    let threads = std::thread::available_parallelism().map_or(1, usize::from);
+    step_bands(size, rule, cells, dying, threads)
+}
+
+/// [`step_parallel`] on `threads` threads.
+#[cfg(feature = "std")]
+fn step_bands(size: Size, rule: &Rule, cells: &[bool], dying: &[u8], threads: usize) -> Generation {
+    if rule.wolfram().is_some() {
+        return step(size, rule, cells, dying);
+    }
+    let width = size.width as usize;
+    let band = (size.height as usize).div_ceil(threads).max(1) * width;
+    // the summed-area table of a Larger than Life rule is built once for the whole board.
+    let larger = rule
+        .larger()
+        .map(|larger| window_counts(size, cells, larger.radius.into(), larger.middle));
+    let bands: Vec<Generation> = std::thread::scope(|s| {
+        let workers: Vec<_> = (0..cells.len())
+            .step_by(band)
+            .map(|start| {
+                let (range, larger) = (start..(start + band).min(cells.len()), &larger);
+                s.spawn(move || {
+                    let counts = match larger {
+                        Some(counts) => counts[range.clone()].to_vec(),
+                        None => range.clone().map(|i| count(size, rule, cells, i)).collect(),
+                    };
+                    step_range(size, rule, cells, dying, &counts, range)
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e))
+            })
+            .collect()
+    });
+    let mut next = Generation {
+        cells: Vec::with_capacity(cells.len()),
+        dying: Vec::with_capacity(cells.len()),
+        births: 0,
+        deaths: 0,
+    };
+    for band in bands {
+        next.cells.extend(band.cells);
+        next.dying.extend(band.dying);
+        next.births += band.births;
+        next.deaths += band.deaths;
+    }
+    next
+}
+
+/// [`step`] for sparse boards: every live cell adds itself to the counts of its neighbours, so
+/// empty space costs next to nothing to count.
+pub fn step_sparse(size: Size, rule: &Rule, cells: &[bool], dying: &[u8]) -> Generation {
+    // the hexagonal neighbourhood isn't symmetric across the seam of a board of odd height, and a
+    // Larger than Life rule has its own way to count.
+    let symmetric = matches!(
+        rule.neighbourhood(),
+        Neighbourhood::Moore | Neighbourhood::VonNeumann
+    );
+    if rule.wolfram().is_some() || rule.larger().is_some() || !symmetric {
+        return step(size, rule, cells, dying);
+    }
+    let mut counts = alloc::vec![0; cells.len()];
+    for (i, _) in cells.iter().enumerate().filter(|(_, &v)| v) {
+        for j in neighbours_in(size, rule.neighbourhood(), i) {
+            counts[j] += 1;
+        }
+    }
+    step_range(size, rule, cells, dying, &counts, 0..cells.len())
+}
+
+/// the cells of `range` of the board after one step, with the live neighbours of each of them in
+/// `counts`.
+fn step_range(
+    size: Size,
+    rule: &Rule,
+    cells: &[bool],
+    dying: &[u8],
+    counts: &[usize],
+    range: Range<usize>,
+) -> Generation {
+    let mut next = Generation {
+        cells: Vec::with_capacity(range.len()),
+        dying: Vec::with_capacity(range.len()),
+        births: 0,
+        deaths: 0,
+    };
+    let (live, states) = (&cells[range.clone()], &dying[range.clone()]);
+    for (i, ((&v, &state), &alive)) in range.zip(live.iter().zip(states).zip(counts)) {
+        let (alive, state) = if rule.is_wireworld() && state == CONDUCTOR {
+            // a conductor next to one or two electron heads carries the signal on.
+            if (1..=2).contains(&alive) {
+                next.births += 1;
+                (true, 0)
+            } else {
+                (false, CONDUCTOR)
+            }
+        } else if !v && state > 0 {
+            // dying cells don't count, and can't be born.
+            (false, rule.decay(state))
+        } else {
+            match (v, rule.next(v, alive)) {
+                (false, true) => {
+                    next.births += 1;
+                    (true, born_colour(size, rule, cells, dying, i))
+                }
+                // rule.decay(1) is 0, a dead cell, under a life-like rule.
+                (true, false) => {
+                    next.deaths += 1;
+                    (false, rule.decay(1))
+                }
+                // live cells keep their colour.
+                (v, _) => (v, state),
+            }
+        };
+        next.cells.push(alive);
+        next.dying.push(state);
+    }
+    next
+}
+
+/// the generation after `cells` and their `dying` states under `script`, which counts the
+/// neighbours in the neighbourhood of `rule` and has its states.
+pub fn step_script(
+    size: Size,
+    rule: &Rule,
+    script: &Script,
+    cells: &[bool],
+    dying: &[u8],
+) -> Generation {
+    let counts = neighbour_counts(size, rule, cells);
+    let mut next = Generation {
+        cells: Vec::with_capacity(cells.len()),
+        dying: Vec::with_capacity(cells.len()),
+        births: 0,
+        deaths: 0,
+    };
+    for ((state, count), &v) in states(cells, dying).into_iter().zip(counts).zip(cells) {
+        let state = script.next(state, count, rule.states());
+        let alive = rule.alive(state);
+        match (v, alive) {
+            (false, true) => next.births += 1,
+            (true, false) => next.deaths += 1,
+            _ => {}
+        }
+        next.cells.push(alive);
+        next.dying.push(if state > 1 { state } else { 0 });
+    }
+    next
+}
+
+/// the generation after `cells` under the elementary automaton with Wolfram's rule number `code`:
+/// every row moves up one, and the bottom row is followed by its next generation, which wraps
+/// around.
+fn elementary(size: Size, code: u8, cells: &[bool]) -> Generation {
+    let width = size.width as usize;
+    let last = &cells[cells.len() - width..];
+    let mut next = Generation {
+        cells: Vec::with_capacity(cells.len()),
+        dying: alloc::vec![0; cells.len()],
+        births: 0,
+        deaths: 0,
+    };
+    for (i, &v) in cells.iter().enumerate() {
+        let alive = match cells.get(i + width) {
+            Some(&below) => below,
+            None => {
+                let x = i % width;
+                let (l, c, r) = (
+                    last[(x + width - 1) % width],
+                    last[x],
+                    last[(x + 1) % width],
+                );
+                let n = usize::from(l) << 2 | usize::from(c) << 1 | usize::from(r);
+                code >> n & 1 == 1
+            }
+        };
+        match (v, alive) {
+            (false, true) => next.births += 1,
+            (true, false) => next.deaths += 1,
+            _ => {}
+        }
+        next.cells.push(alive);
+    }
+    next
+}
+
+/// colour of a cell born at `idx` under a coloured `rule`, 0 under the others: the one most of its
+/// parents have. three parents of different colours give the fourth one.
+fn born_colour(size: Size, rule: &Rule, cells: &[bool], colours: &[u8], idx: usize) -> u8 {
+    let n = rule.colours();
+    if n == 0 {
+        return 0;
+    }
+    // live cells without a colour have the first one.
+    let mut parents = [0; 256];
+    for j in neighbours_in(size, rule.neighbourhood(), idx).filter(|&j| cells[j]) {
+        parents[usize::from(colours[j].clamp(1, n))] += 1;
+    }
+    let parents = &parents[1..=usize::from(n)];
+    match parents.iter().max() {
+        Some(1) if n == 4 && parents.iter().sum::<u8>() == 3 => parents
+            .iter()
+            .position(|&p| p == 0)
+            .map_or(1, |c| c as u8 + 1),
+        _ => (1..=n)
+            .max_by_key(|&c| parents[usize::from(c - 1)])
+            .unwrap_or(1),
+    }
+}
+
+/// live neighbours of every cell of `cells` under `rule`.
+fn neighbour_counts(size: Size, rule: &Rule, cells: &[bool]) -> Vec<usize> {
+    match rule.larger() {
+        Some(larger) => window_counts(size, cells, larger.radius.into(), larger.middle),
+        None => (0..cells.len())
+            .map(|i| count(size, rule, cells, i))
+            .collect(),
+    }
+}
+
+/// live neighbours of the cell at `idx` under a rule which isn't Larger than Life.
+fn count(size: Size, rule: &Rule, cells: &[bool], idx: usize) -> usize {
+    neighbours_in(size, rule.neighbourhood(), idx)
+        .filter(|&j| cells[j])
+        .count()
+}
+
+/// live cells in the square `radius` cells around every cell, which includes the cell itself if
+/// `middle`. the board wraps around the edges, even when the square is bigger than the board.
+///
+/// a summed-area table of the board, extended by `radius` on every side, gives every square in
+/// four lookups however big the radius is.
+fn window_counts(size: Size, cells: &[bool], radius: usize, middle: bool) -> Vec<usize> {
+    let (width, height) = (size.width as usize, size.height as usize);
+    let side = 2 * radius + 1;
+    // one more column and row of zeros, above and to the left.
+    let stride = width + 2 * radius + 1;
+    let mut table = alloc::vec![0; stride * (height + 2 * radius + 1)];
+    for y in 0..height + 2 * radius {
+        let by = (y + height * side - radius) % height;
+        let mut row = 0;
+        for x in 0..width + 2 * radius {
+            let bx = (x + width * side - radius) % width;
+            row += usize::from(cells[by * width + bx]);
+            table[(y + 1) * stride + x + 1] = table[y * stride + x + 1] + row;
+        }
+    }
+    (0..width * height)
+        .map(|i| {
+            // the square from (x, y) to (x + side, y + side) of the extended board.
+            let (x, y) = (i % width, i / width);
+            let sum = table[(y + side) * stride + x + side] + table[y * stride + x]
+                - table[y * stride + x + side]
+                - table[(y + side) * stride + x];
+            if middle {
+                sum
+            } else {
+                sum - usize::from(cells[i])
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn neighbour_indices() {
+        let size = Size {
+            width: 3,
+            height: 3,
+        };
+        // 0 1 2 0 1 2
+        // 3 4 5 3 4 5
+        // 6 7 8 6 7 8
+        // 0 1 2 0 1 2
+        // 3 4 5 3 4 5
+        // 6 7 8 6 7 8
+
+        //                             cu ru rm rd cd ld lm lu
+        assert_eq!(neighbours(size, 4), [1, 2, 5, 8, 7, 6, 3, 0,]);
+        assert_eq!(neighbours(size, 0), [6, 7, 1, 4, 3, 5, 2, 8,]);
+        assert_eq!(neighbours(size, 1), [7, 8, 2, 5, 4, 3, 0, 6,]);
+        assert_eq!(neighbours(size, 2), [8, 6, 0, 3, 5, 4, 1, 7,]);
+        assert_eq!(neighbours(size, 3), [0, 1, 4, 7, 6, 8, 5, 2,]);
+        assert_eq!(neighbours(size, 5), [2, 0, 3, 6, 8, 7, 4, 1,]);
+        assert_eq!(neighbours(size, 6), [3, 4, 7, 1, 0, 2, 8, 5,]);
+        assert_eq!(neighbours(size, 7), [4, 5, 8, 2, 1, 0, 6, 3,]);
+        assert_eq!(neighbours(size, 8), [5, 3, 6, 0, 2, 1, 7, 4,]);
+    }
+
+    #[test]
+    fn step_blinker() {
+        let size = Size {
+            width: 5,
+            height: 5,
+        };
+        let mut cells = alloc::vec![false; 25];
+        for i in [11, 12, 13] {
+            cells[i] = true;
+        }
+        let none = alloc::vec![0; 25];
+        let next = step(size, &Rule::default(), &cells, &none);
+        assert_eq!((next.births, next.deaths), (2, 2));
+        assert!([7, 12, 17].iter().all(|&i| next.cells[i]));
+        assert_eq!(next.dying, none);
+        assert_eq!(
+            step(size, &Rule::default(), &next.cells, &none).cells,
+            cells
+        );
+    }
+
+    #[test]
+    fn perturb_cells() {
+        let size = Size {
+            width: 3,
+            height: 1,
+        };
+        let mut next = step(size, &Rule::default(), &[false; 3], &[0; 3]);
+        let mut picks = [true, false, true].into_iter();
+        next.perturb(|| picks.next().unwrap_or(false));
+        assert_eq!(next.cells, [true, false, true]);
+        assert_eq!((next.births, next.deaths), (2, 0));
+        next.perturb(|| true);
+        assert_eq!(next.cells, [false, true, false]);
+        assert_eq!((next.births, next.deaths), (3, 2));
+    }
+
+    #[test]
+    fn step_with_script() -> anyhow::Result<()> {
+        let size = Size {
+            width: 5,
+            height: 5,
+        };
+        let mut cells = alloc::vec![false; 25];
+        for i in [11, 12, 13] {
+            cells[i] = true;
+        }
+        let none = alloc::vec![0; 25];
+        let life = "count == 3 || state == 1 && count == 2".parse()?;
+        let next = step_script(size, &Rule::default(), &life, &cells, &none);
+        let expected = step(size, &Rule::default(), &cells, &none);
+        assert_eq!(next.cells, expected.cells);
+        assert_eq!((next.births, next.deaths), (2, 2));
+        // a script of the Generations rule B2/S/C3, i.e. Brian's Brain.
+        let brain = "state == 1 ? 2 : state == 0 && count == 2".parse()?;
+        let rule = Rule::brians_brain();
+        let next = step_script(size, &rule, &brain, &cells, &none);
+        let expected = step(size, &rule, &cells, &none);
+        assert_eq!((next.cells, next.dying), (expected.cells, expected.dying));
+        Ok(())
+    }
+
+    #[test]
+    fn step_brians_brain() -> anyhow::Result<()> {
+        let size = Size {
+            width: 4,
+            height: 4,
+        };
+        let rule: Rule = "/2/3".parse()?;
+        // . A . .
+        // . A . .
+        let mut cells = alloc::vec![false; 16];
+        (cells[1], cells[5]) = (true, true);
+        let next = step(size, &rule, &cells, &[0; 16]);
+        // both die, and the cells next to the pair are born.
+        assert_eq!((next.births, next.deaths), (4, 2));
+        assert_eq!((next.dying[1], next.dying[5]), (2, 2));
+        assert!([0, 2, 4, 6].iter().all(|&i| next.cells[i]));
+        let after = step(size, &rule, &next.cells, &next.dying);
+        assert_eq!((after.dying[1], after.dying[5]), (0, 0));
+        assert!(!after.cells[1] && !after.cells[5]);
+        Ok(())
+    }
+
+    #[test]
+    fn other_neighbourhoods() -> anyhow::Result<()> {
+        let size = Size {
+            width: 4,
+            height: 4,
+        };
+        let hex = |idx| neighbours_in(size, Neighbourhood::Hex, idx).collect::<Vec<_>>();
+        // the odd row 1 and the even row 2.
+        assert_eq!(hex(5), [1, 2, 6, 10, 9, 4]);
+        assert_eq!(hex(9), [4, 5, 10, 13, 12, 8]);
+        // a lone cell grows into a plus under B1/SV.
+        let mut cells = alloc::vec![false; 16];
+        cells[5] = true;
+        let next = step(size, &"B1/SV".parse()?, &cells, &[0; 16]);
+        let alive: Vec<usize> = (0..16).filter(|&i| next.cells[i]).collect();
+        assert_eq!(alive, [1, 4, 6, 9]);
+        Ok(())
+    }
+
+    #[test]
+    fn step_wireworld() {
+        let size = Size {
+            width: 5,
+            height: 3,
+        };
+        // a signal running right along a wire: tail, head, conductor, conductor, conductor.
+        let mut cells = alloc::vec![false; 15];
+        let mut dying = alloc::vec![0; 15];
+        cells[6] = true;
+        (dying[5], dying[7], dying[8], dying[9]) = (2, CONDUCTOR, CONDUCTOR, CONDUCTOR);
+        let next = step(size, &Rule::wireworld(), &cells, &dying);
+        assert_eq!((next.births, next.deaths), (1, 1));
+        assert!(next.cells[7]);
+        assert_eq!(next.dying[5..10], [CONDUCTOR, 2, 0, CONDUCTOR, CONDUCTOR]);
+        // the rows above and below stay empty.
+        assert!(next.dying[..5]
+            .iter()
+            .chain(&next.dying[10..])
+            .all(|&s| s == 0));
+    }
+
+    #[test]
+    fn step_quadlife() {
+        let size = Size {
+            width: 5,
+            height: 5,
+        };
+        // three parents of colours 1, 2 and 3 around the cell 12.
+        let mut cells = alloc::vec![false; 25];
+        let mut colours = alloc::vec![0; 25];
+        for (i, colour) in [(6, 1), (8, 2), (16, 3)] {
+            (cells[i], colours[i]) = (true, colour);
+        }
+        let next = step(size, &Rule::quadlife(), &cells, &colours);
+        assert!(next.cells[12]);
+        assert_eq!(next.dying[12], 4);
+        // the parents with one neighbour die, and the survivors keep their colour.
+        assert_eq!(
+            split(&states(&next.cells, &next.dying), &Rule::quadlife()).1,
+            next.dying
+        );
+        let mut cells = alloc::vec![false; 25];
+        // one parent of colour 1 and two of colour 2 around the cell 13.
+        for (i, colour) in [(7, 1), (9, 2), (18, 2)] {
+            (cells[i], colours[i]) = (true, colour);
+        }
+        assert_eq!(
+            step(size, &Rule::immigration(), &cells, &colours).dying[13],
+            2
+        );
+    }
+
+    #[test]
+    fn step_elementary() {
+        let size = Size {
+            width: 5,
+            height: 2,
+        };
+        let mut cells = alloc::vec![false; 10];
+        cells[7] = true;
+        // rule 90: a cell is the xor of its neighbours.
+        let next = step(size, &Rule::elementary(90), &cells, &[0; 10]);
+        let alive: Vec<usize> = (0..10).filter(|&i| next.cells[i]).collect();
+        assert_eq!(alive, [2, 6, 8]);
+        assert_eq!((next.births, next.deaths), (3, 1));
+    }
+
+    #[test]
+    fn window_counts_wrap() {
+        let size = Size {
+            width: 7,
+            height: 5,
+        };
+        let cells: Vec<bool> = (0..35).map(|i: usize| (i * 7 + i / 3) % 5 < 2).collect();
+        for radius in [1, 2, 3] {
+            let counts = window_counts(size, &cells, radius, false);
+            for (i, &count) in counts.iter().enumerate() {
+                let (x, y) = ((i % 7) as isize, (i / 7) as isize);
+                let r = radius as isize;
+                let mut expected = 0;
+                for dy in -r..=r {
+                    for dx in -r..=r {
+                        let j = (y + dy).rem_euclid(5) * 7 + (x + dx).rem_euclid(7);
+                        expected += usize::from(cells[j as usize] && (dx, dy) != (0, 0));
+                    }
+                }
+                assert_eq!(count, expected, "radius {} cell {}", radius, i);
+            }
+        }
+        // radius 1 agrees with the 8 neighbours.
+        let rule: Rule = "R1,C0,M0,S2..3,B3..3,NM".parse().unwrap();
+        let life = step(size, &Rule::default(), &cells, &[0; 35]);
+        assert_eq!(step(size, &rule, &cells, &[0; 35]).cells, life.cells);
+    }
+
+    #[test]
+    fn engines_agree() -> anyhow::Result<()> {
+        let size = Size {
+            width: 17,
+            height: 13,
+        };
+        let start: Vec<bool> = (0..221)
+            .map(|i: usize| (i * 7 + i / 5).is_multiple_of(3))
+            .collect();
+        for rule in [
+            "B3/S23",
+            "B2/S345/C4",
+            "B1/SV",
+            "B2/S34H",
+            "R2,C3,M1,S3..5,B3..4,NM",
+        ] {
+            let rule: Rule = rule.parse()?;
+            let (mut cells, mut dying) = (start.clone(), alloc::vec![0; 221]);
+            for _ in 0..4 {
+                let next = step(size, &rule, &cells, &dying);
+                let bands = (1..5).map(|threads| step_bands(size, &rule, &cells, &dying, threads));
+                for other in bands.chain([step_sparse(size, &rule, &cells, &dying)]) {
+                    assert_eq!(other.cells, next.cells, "{}", rule);
+                    assert_eq!(other.dying, next.dying, "{}", rule);
+                    assert_eq!((other.births, other.deaths), (next.births, next.deaths));
+                }
+                (cells, dying) = (next.cells, next.dying);
+            }
+        }
+        Ok(())
+    }
+}
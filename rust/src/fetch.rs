@@ -0,0 +1,171 @@
+//! the `fetch` subcommand, which downloads a pattern by its name from LifeWiki, or by its
+//! apgcode from catagolue, and keeps it in the pattern directory so it is fetched only once.
+
+use anyhow::{bail, ensure, Context, Result};
+use clap::Args;
+use life_game::rle;
+use std::{
+    env::var_os,
+    fs::{create_dir_all, write},
+    path::{Path, PathBuf},
+};
+
+#[derive(Args, Debug)]
+pub(crate) struct FetchArgs {
+    #[arg(help = "a LifeWiki pattern, e.g. gosperglidergun, or an apgcode, e.g. xq4_153.")]
+    name: String,
+    #[arg(
+        long,
+        default_value = "b3s23",
+        help = "rulestring catagolue files apgcodes under."
+    )]
+    rule: String,
+    #[arg(
+        long,
+        help = "download it again even if it is in the pattern directory."
+    )]
+    refresh: bool,
+    #[arg(long, help = "start from it, as --file with the other options.")]
+    pub(crate) run: bool,
+}
+
+/// `$XDG_DATA_HOME/life-game/patterns`, or `~/.local/share/life-game/patterns`.
+fn pattern_dir() -> Result<PathBuf> {
+    var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .map(|dir| dir.join("life-game").join("patterns"))
+        .context("no pattern directory: neither XDG_DATA_HOME nor HOME is set.")
+}
+
+/// whether `name` is an apgcode of a still life, an oscillator or a spaceship.
+fn is_apgcode(name: &str) -> bool {
+    name.split_once('_').is_some_and(|(prefix, code)| {
+        let period = prefix
+            .strip_prefix("xs")
+            .or_else(|| prefix.strip_prefix("xp"))
+            .or_else(|| prefix.strip_prefix("xq"));
+        period.is_some_and(|p| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit()))
+            && !code.is_empty()
+            && code.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_')
+    })
+}
+
+/// (url, file name) of `name`. LifeWiki names its files in lower case without spaces.
+fn source(name: &str, rule: &str) -> Result<(String, String)> {
+    if is_apgcode(name) {
+        return Ok((
+            format!("https://catagolue.hatsya.com/rle/{}/{}", rule, name),
+            format!("{}.rle", name),
+        ));
+    }
+    let name: String = name
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    ensure!(
+        !name.is_empty()
+            && name
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-'),
+        "not a pattern name: {}",
+        name
+    );
+    Ok((
+        format!("https://conwaylife.com/patterns/{}.rle", name),
+        format!("{}.rle", name),
+    ))
+}
+
+fn download(url: &str) -> Result<String> {
+    match ureq::get(url).call() {
+        Ok(response) => Ok(response.into_string()?),
+        Err(ureq::Error::Status(404, _)) => bail!("there is no {}", url),
+        Err(e) => Err(e).context(url.to_string()),
+    }
+}
+
+/// the file of `args.name` in `dir`, downloaded with `download` unless it is there already.
+fn fetch_into(
+    dir: &Path,
+    args: &FetchArgs,
+    download: impl FnOnce(&str) -> Result<String>,
+) -> Result<(PathBuf, bool)> {
+    let (url, file) = source(&args.name, &args.rule)?;
+    let path = dir.join(file);
+    if path.exists() && !args.refresh {
+        return Ok((path, false));
+    }
+    let text = download(&url)?;
+    // an error page isn't kept.
+    rle::decode(&text).with_context(|| format!("{} is no rle", url))?;
+    create_dir_all(dir).with_context(|| format!("{}", dir.display()))?;
+    write(&path, text).with_context(|| format!("{}", path.display()))?;
+    Ok((path, true))
+}
+
+/// fetch the pattern of `args`, returning where it is.
+pub(crate) fn fetch(args: &FetchArgs) -> Result<PathBuf> {
+    let (path, fetched) = fetch_into(&pattern_dir()?, args, download)?;
+    if fetched {
+        println!("fetched {} to {}", args.name, path.display());
+    } else {
+        println!("{} is in {}", args.name, path.display());
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs::read_to_string;
+
+    fn args(name: &str) -> FetchArgs {
+        FetchArgs {
+            name: name.to_string(),
+            rule: "b3s23".to_string(),
+            refresh: false,
+            run: false,
+        }
+    }
+
+    #[test]
+    fn find_the_source() -> Result<()> {
+        assert!(is_apgcode("xs4_33") && is_apgcode("xq4_153") && is_apgcode("xp2_7"));
+        assert!(!is_apgcode("glider") && !is_apgcode("xs_33") && !is_apgcode("xs4_"));
+        assert_eq!(
+            source("xq4_153", "b3s23")?.0,
+            "https://catagolue.hatsya.com/rle/b3s23/xq4_153"
+        );
+        assert_eq!(
+            source("Gosper glider gun", "b3s23")?,
+            (
+                "https://conwaylife.com/patterns/gosperglidergun.rle".to_string(),
+                "gosperglidergun.rle".to_string()
+            )
+        );
+        assert!(source("../etc/passwd", "b3s23").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_once() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        let glider = "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n";
+        let (path, fetched) = fetch_into(dir, &args("glider"), |url| {
+            assert_eq!(url, "https://conwaylife.com/patterns/glider.rle");
+            Ok(glider.to_string())
+        })?;
+        assert!(fetched);
+        assert_eq!(read_to_string(&path)?, glider);
+        // it is in the directory now.
+        let (again, fetched) = fetch_into(dir, &args("glider"), |_| bail!("no network in tests"))?;
+        assert_eq!((again, fetched), (path, false));
+        let page = |_: &str| Ok("<html>not found</html>".to_string());
+        assert!(fetch_into(dir, &args("xs4_33"), page).is_err());
+        assert!(!dir.join("xs4_33.rle").exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+//! c bindings, declared in `include/life_game.h`. the `life-game-ffi` crate of the workspace
+//! builds them as a `cdylib`:
+//!
+//! ```sh
+//! cargo build --release -p life-game-ffi
+//! ```
+//!
+//! nothing here panics on bad input: out of range positions are reported by the return value.
+
+use crate::{Game, Size};
+use std::ptr::null_mut;
+
+/// an empty board of `width` x `height`, or null if it is too big. free it with `life_free`.
+#[no_mangle]
+pub extern "C" fn life_new(width: u16, height: u16) -> *mut Game {
+    match Game::blank(Size { width, height }) {
+        Ok(game) => Box::into_raw(Box::new(game)),
+        Err(_) => null_mut(),
+    }
+}
+
+/// advance one generation.
+///
+/// # Safety
+/// `life` must come from `life_new` and not be freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn life_step(life: *mut Game) {
+    let game = &mut *life;
+    // stepping a board of the right length can't fail.
+    let _ = game.step();
+}
+
+/// 1 if the cell at `x`, `y` is alive, 0 if dead, -1 if it is off the board.
+///
+/// # Safety
+/// `life` must come from `life_new` and not be freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn life_get(life: *const Game, x: u16, y: u16) -> i32 {
+    let game = &*life;
+    match game.index((x, y)) {
+        Ok(i) => game.game[i] as i32,
+        Err(_) => -1,
+    }
+}
+
+/// make the cell at `x`, `y` alive if `alive` isn't 0, dead otherwise.
+/// returns 0, or -1 if it is off the board.
+///
+/// # Safety
+/// `life` must come from `life_new` and not be freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn life_set(life: *mut Game, x: u16, y: u16, alive: i32) -> i32 {
+    let game = &mut *life;
+    match game.set_cell((x, y), alive != 0) {
+        Ok(()) => 0,
+        Err(_) => -1,
+    }
+}
+
+/// free a board from `life_new`. null is ignored.
+///
+/// # Safety
+/// `life` must be null, or come from `life_new` and not be freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn life_free(life: *mut Game) {
+    if !life.is_null() {
+        drop(Box::from_raw(life));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn drive_blinker() {
+        let life = life_new(5, 5);
+        assert!(!life.is_null());
+        unsafe {
+            for x in 1..4 {
+                assert_eq!(life_set(life, x, 2, 1), 0);
+            }
+            assert_eq!(life_set(life, 5, 0, 1), -1);
+            life_step(life);
+            assert_eq!(life_get(life, 2, 1), 1);
+            assert_eq!(life_get(life, 1, 2), 0);
+            assert_eq!(life_get(life, 0, 5), -1);
+            life_free(life);
+            life_free(null_mut());
+        }
+    }
+}
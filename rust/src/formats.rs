@@ -0,0 +1,513 @@
+//! reading boards from files in the formats of [`Format`], and placing them on a board.
+
+use crate::{engine, meta::Metadata, point_from_str, rle, rule::MAX_STATES, Size};
+use anyhow::{anyhow, bail, ensure, Context, Error, Result};
+use clap::ValueEnum;
+use std::{
+    fmt::Display,
+    fs::read_to_string,
+    io::{stdin, Read},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// file formats boards can be read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// `W:H` and rows of `0`/`1`, or a delta against another file.
+    /// dying states of Generations rules and colours of live cells are `2` and up, in base 36.
+    Native,
+    /// run length encoded, as golly and catagolue write them.
+    Rle,
+    /// `.cells`: rows of `.` and `O`.
+    Plaintext,
+    /// `#Life 1.06` and one `x y` per live cell.
+    Life106,
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "{}",
+            match self {
+                Format::Native => "native",
+                Format::Rle => "rle",
+                Format::Plaintext => "plaintext",
+                Format::Life106 => "life 1.06",
+            }
+        )
+    }
+}
+
+impl Format {
+    /// guess the format from the content. `#` lines are comments of rle, unless a native size
+    /// follows them.
+    pub fn detect(text: &str) -> Result<Self> {
+        let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+        let first = lines.clone().next().unwrap_or("");
+        let after_comments = lines.find(|l| !l.starts_with('#')).unwrap_or("");
+        Ok(if first.starts_with("#Life 1.06") {
+            Format::Life106
+        } else if first.starts_with("#life-game")
+            || first.starts_with("#delta ")
+            || point_from_str(after_comments).is_ok()
+        {
+            Format::Native
+        } else if first.starts_with('!') || first.chars().all(|c| matches!(c, '.' | 'O' | '*')) {
+            Format::Plaintext
+        } else if first.starts_with('#') || first.starts_with('x') && first.contains('=') {
+            Format::Rle
+        } else {
+            bail!("unknown file format.")
+        })
+    }
+}
+
+/// a board read from a file.
+#[derive(Debug)]
+pub struct Board {
+    /// size of the board.
+    pub size: Size,
+    /// whether each cell is alive, in rows.
+    pub cells: Vec<bool>,
+    /// state of every dying cell, 2 and up, and 0 for the rest.
+    pub dying: Vec<u8>,
+    /// rule, generation and the rest the file says about the board.
+    pub meta: Metadata,
+    /// the format the file was read as.
+    pub format: Format,
+}
+
+/// a cell state as written in native saves: one base 36 digit.
+pub(crate) fn state_char(state: u8) -> char {
+    char::from_digit(state as u32, 36).unwrap_or('0')
+}
+
+/// the cell state `c` stands for in native saves.
+pub(crate) fn char_state(c: char) -> Option<u8> {
+    c.to_digit(36).map(|d| d as u8).filter(|&d| d < MAX_STATES)
+}
+
+/// a file to place on the board, written `<path>@<x>,<y>` or just `<path>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placement {
+    /// the file to read.
+    pub path: PathBuf,
+    /// where its top left goes. `None` centers it.
+    pub offset: Option<(u16, u16)>,
+}
+
+impl FromStr for Placement {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s.rsplit_once('@') {
+            Some((path, pos)) if pos.contains([',', ':']) => Self {
+                path: path.into(),
+                offset: Some(point_from_str(&pos.replace(',', ":"))?),
+            },
+            _ => Self {
+                path: s.into(),
+                offset: None,
+            },
+        })
+    }
+}
+
+impl Board {
+    /// live cells onto `board` of `size`, with the top left at `offset`, or centered.
+    pub fn paste_onto(
+        &self,
+        board: &mut [bool],
+        size: Size,
+        offset: Option<(u16, u16)>,
+    ) -> Result<()> {
+        let (ox, oy) = offset.unwrap_or((
+            size.width.saturating_sub(self.size.width) / 2,
+            size.height.saturating_sub(self.size.height) / 2,
+        ));
+        ensure!(
+            ox as usize + self.size.width as usize <= size.width as usize
+                && oy as usize + self.size.height as usize <= size.height as usize,
+            "{} at {}:{} doesn't fit on a board of {}.",
+            self.size,
+            ox,
+            oy,
+            size
+        );
+        let (width, inner) = (size.width as usize, self.size.width as usize);
+        for (y, row) in self.cells.chunks(inner).enumerate() {
+            let start = (oy as usize + y) * width + ox as usize;
+            for (cell, &alive) in board[start..start + inner].iter_mut().zip(row) {
+                *cell |= alive;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// read a board from `path`, or stdin if it is `-`, in `format` or whatever its content looks
+/// like.
+pub fn load(path: &Path, format: Option<Format>) -> Result<Board> {
+    load_from(path, format, &[])
+}
+
+/// [`load`] as the base of the delta files in `deltas`, canonicalized, which it may not be one
+/// of.
+fn load_from(path: &Path, format: Option<Format>, deltas: &[PathBuf]) -> Result<Board> {
+    let mut deltas = deltas.to_vec();
+    deltas.extend(path.canonicalize());
+    let text = if path == Path::new("-") {
+        let mut text = String::new();
+        stdin().read_to_string(&mut text)?;
+        text
+    } else {
+        ensure!(path.is_file(), "{} is not a file.", path.display());
+        read_to_string(path)?
+    };
+    let format = match format {
+        Some(format) => format,
+        None => Format::detect(&text).with_context(|| format!("{}", path.display()))?,
+    };
+    let alive = |(size, cells, meta): (Size, Vec<bool>, Metadata)| {
+        (size, cells.into_iter().map(u8::from).collect(), meta)
+    };
+    let (size, states, meta) = match format {
+        Format::Native => native(path, &text, &deltas),
+        Format::Rle => rle::decode(&text),
+        Format::Plaintext => plaintext(&text).map(alive),
+        Format::Life106 => life106(&text).map(alive),
+    }
+    .with_context(|| format!("{} as {}", path.display(), format))?;
+    let expected = size.width as usize * size.height as usize;
+    ensure!(
+        states.len() == expected,
+        "{} as {}: a {} board has {} cells, but {} were read.",
+        path.display(),
+        format,
+        size,
+        expected,
+        states.len()
+    );
+    let (cells, dying) = engine::split(&states, &meta.rule.unwrap_or_default());
+    Ok(Board {
+        size,
+        cells,
+        dying,
+        meta,
+        format,
+    })
+}
+
+/// a native or delta save, with the metadata above it if there is any. blank lines, `\r` and
+/// other `#` lines are skipped, and errors point at the line and column. `deltas` are the files
+/// it is the base of, and itself.
+fn native(path: &Path, text: &str, deltas: &[PathBuf]) -> Result<(Size, Vec<u8>, Metadata)> {
+    let text = text.replace("\r\n", "\n");
+    let (meta, rest) = Metadata::split(&text)?;
+    // number the lines of the whole file, metadata included.
+    let skipped = text[..text.len() - rest.len()].matches('\n').count();
+    let mut lines = rest
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + skipped + 1, line.trim_end()))
+        .filter(|(_, line)| !line.is_empty());
+    let mut base = None;
+    let size = loop {
+        let Some((n, line)) = lines.next() else {
+            bail!("no `<width>:<height>` line.");
+        };
+        if let Some(path) = line.strip_prefix("#delta base=") {
+            base = Some(path);
+        } else if !line.starts_with('#') {
+            let (width, height) = point_from_str(line).with_context(|| {
+                format!("line {}: expected <width>:<height>, found {:?}", n, line)
+            })?;
+            break Size { width, height };
+        }
+    };
+    let lines = lines.filter(|(_, line)| !line.starts_with('#'));
+    if let Some(base) = base {
+        return Ok((size, delta(path, base, size, lines, deltas)?, meta));
+    }
+
+    let width = size.width as usize;
+    let mut game = Vec::with_capacity(width * size.height as usize);
+    let mut rows = 0;
+    for (n, line) in lines {
+        ensure!(
+            rows < size.height,
+            "line {}: the board has only {} rows.",
+            n,
+            size.height
+        );
+        for (column, c) in line.chars().enumerate() {
+            game.push(char_state(c).ok_or_else(|| {
+                anyhow!(
+                    "line {}, column {}: expected 0, 1 or a dying state, found {:?}",
+                    n,
+                    column + 1,
+                    c
+                )
+            })?);
+        }
+        let len = line.chars().count();
+        ensure!(
+            len == width,
+            "line {}: row has {} cells, expected {}.",
+            n,
+            len,
+            width
+        );
+        rows += 1;
+    }
+    ensure!(
+        rows == size.height,
+        "expected {} rows, found {}.",
+        size.height,
+        rows
+    );
+    Ok((size, game, meta))
+}
+
+/// load the base file, then reverse every `x:y` of `lines`, killing dying cells. the base path is
+/// relative to the delta file, and may not be one of `deltas`, which would never end.
+fn delta<'a>(
+    path: &Path,
+    base: &str,
+    size: Size,
+    lines: impl Iterator<Item = (usize, &'a str)>,
+    deltas: &[PathBuf],
+) -> Result<Vec<u8>> {
+    let base: PathBuf = path.parent().unwrap_or(Path::new(".")).join(base);
+    ensure!(
+        !base.canonicalize().is_ok_and(|base| deltas.contains(&base)),
+        "{} is a base of itself.",
+        base.display()
+    );
+    let Board {
+        size: base_size,
+        cells,
+        dying,
+        ..
+    } = load_from(&base, None, deltas)?;
+    let mut game = engine::states(&cells, &dying);
+    ensure!(
+        base_size == size,
+        "base is {}, but delta is {}.",
+        base_size,
+        size
+    );
+
+    for (n, line) in lines {
+        let (x, y) = point_from_str(line)
+            .with_context(|| format!("line {}: expected <x>:<y>, found {:?}", n, line))?;
+        ensure!(
+            x < size.width && y < size.height,
+            "line {}: {} is out of the board.",
+            n,
+            line
+        );
+        let idx = y as usize * size.width as usize + x as usize;
+        game[idx] = u8::from(game[idx] != 1);
+    }
+    Ok(game)
+}
+
+/// rows of `.` and `O` (or `*`). `!Name:` gives the name, other `!` lines are comments.
+fn plaintext(text: &str) -> Result<(Size, Vec<bool>, Metadata)> {
+    let mut meta = Metadata::default();
+    let mut rows = Vec::new();
+    for line in text.lines() {
+        if let Some(comment) = line.strip_prefix('!') {
+            match comment.strip_prefix("Name:") {
+                Some(name) => meta.name = Some(name.trim().to_string()),
+                None if !comment.trim().is_empty() => {
+                    meta.comments.push(comment.trim().to_string())
+                }
+                None => {}
+            }
+            continue;
+        }
+        let row = line
+            .trim_end()
+            .chars()
+            .map(|c| match c {
+                '.' => Ok(false),
+                'O' | '*' => Ok(true),
+                c => Err(anyhow!("unexpected {:?} in a plaintext row.", c)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        rows.push(row);
+    }
+    // trailing blank lines are no rows.
+    while rows.last().is_some_and(|r| r.is_empty()) {
+        rows.pop();
+    }
+    let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    ensure!(width > 0, "no cells.");
+    let size = Size {
+        width: width.try_into()?,
+        height: rows.len().try_into()?,
+    };
+    let cells = rows
+        .into_iter()
+        .flat_map(|mut r| {
+            r.resize(width, false);
+            r
+        })
+        .collect();
+    Ok((size, cells, meta))
+}
+
+/// cells of the largest board a Life 1.06 file may span, 16384x16384.
+const MAX_LIFE106_CELLS: i64 = 1 << 28;
+
+/// `#Life 1.06` and one `x y` per live cell. the board is the bounding box of the cells.
+fn life106(text: &str) -> Result<(Size, Vec<bool>, Metadata)> {
+    let mut points = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (x, y) = line
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| anyhow!("expected `x y`: {}", line))?;
+        points.push((x.trim().parse::<i64>()?, y.trim().parse::<i64>()?));
+    }
+    ensure!(!points.is_empty(), "no cells.");
+    let min_x = points.iter().map(|p| p.0).min().unwrap_or(0);
+    let min_y = points.iter().map(|p| p.1).min().unwrap_or(0);
+    let span = |d: i64, min: i64| d.saturating_sub(min).saturating_add(1);
+    let width = points.iter().map(|p| span(p.0, min_x)).max().unwrap_or(1);
+    let height = points.iter().map(|p| span(p.1, min_y)).max().unwrap_or(1);
+    ensure!(
+        width.saturating_mul(height) <= MAX_LIFE106_CELLS,
+        "the cells span {}x{}, more than {} cells.",
+        width,
+        height,
+        MAX_LIFE106_CELLS
+    );
+    let size = Size {
+        width: width.try_into()?,
+        height: height.try_into()?,
+    };
+    let mut cells = vec![false; size.width as usize * size.height as usize];
+    for (x, y) in points {
+        cells[(y - min_y) as usize * size.width as usize + (x - min_x) as usize] = true;
+    }
+    Ok((size, cells, Metadata::default()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn detect_formats() -> Result<()> {
+        assert_eq!(Format::detect("3:1\n010")?, Format::Native);
+        assert_eq!(
+            Format::detect("#life-game v2\n#generation=3\n3:1\n010")?,
+            Format::Native
+        );
+        assert_eq!(
+            Format::detect("#N Glider\nx = 3, y = 3\nbo$2bo$3o!")?,
+            Format::Rle
+        );
+        assert_eq!(Format::detect("x = 3, y = 3\nbo$2bo$3o!")?, Format::Rle);
+        assert_eq!(
+            Format::detect("# hand made\n\n#C two rows\n3:2\n010\n111")?,
+            Format::Native
+        );
+        assert_eq!(
+            Format::detect("!Name: Glider\n.O.\n..O\nOOO")?,
+            Format::Plaintext
+        );
+        assert_eq!(
+            Format::detect("#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1")?,
+            Format::Life106
+        );
+        assert!(Format::detect("hello").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_placements() -> Result<()> {
+        let placed: Placement = "gun.rle@10,4".parse()?;
+        assert_eq!(placed.path, PathBuf::from("gun.rle"));
+        assert_eq!(placed.offset, Some((10, 4)));
+        assert_eq!("a@b.rle@3:5".parse::<Placement>()?.offset, Some((3, 5)));
+        assert_eq!("me@home.rle".parse::<Placement>()?.offset, None);
+        assert!("gun.rle@x,4".parse::<Placement>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn delta_cycles() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        std::fs::write(dir.join("self.txt"), "#delta base=self.txt\n2:2\n")?;
+        std::fs::write(dir.join("a.txt"), "#delta base=b.txt\n2:2\n0:0\n")?;
+        std::fs::write(dir.join("b.txt"), "#delta base=./a.txt\n2:2\n")?;
+        for file in ["self.txt", "a.txt"] {
+            let err = format!("{:#}", load(&dir.join(file), None).unwrap_err());
+            assert!(err.contains("is a base of itself"), "{}", err);
+        }
+        // a chain of deltas is no cycle.
+        std::fs::write(dir.join("base.txt"), "2:2\n00\n00\n")?;
+        std::fs::write(dir.join("c.txt"), "#delta base=base.txt\n2:2\n0:0\n")?;
+        std::fs::write(dir.join("d.txt"), "#delta base=c.txt\n2:2\n1:1\n")?;
+        assert_eq!(
+            load(&dir.join("d.txt"), None)?.cells,
+            [true, false, false, true]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn tolerant_native() -> Result<()> {
+        let path = Path::new("board.txt");
+        let (size, cells, _) = native(
+            path,
+            "# hand made\r\n3:2\r\n\r\n010\r\n# middle\r\n111\r\n\r\n\r\n",
+            &[],
+        )?;
+        assert_eq!((size.width, size.height), (3, 2));
+        assert_eq!(cells, [0, 1, 0, 1, 1, 1]);
+        let (_, cells, _) = native(path, "3:1\n12o\n", &[])?;
+        assert_eq!(cells, [1, 2, 24]);
+        let err = |text: &str| format!("{:#}", native(path, text, &[]).unwrap_err());
+        assert_eq!(
+            err("3:2\n010\n1x1\n"),
+            "line 3, column 2: expected 0, 1 or a dying state, found 'x'"
+        );
+        assert_eq!(
+            err("3:2\n010\n11\n"),
+            "line 3: row has 2 cells, expected 3."
+        );
+        assert_eq!(err("3:2\n010\n"), "expected 2 rows, found 1.");
+        assert_eq!(err("3:1\n010\n010\n"), "line 3: the board has only 1 rows.");
+        assert!(err("# nothing\n\nthree:two\n").starts_with("line 3: expected <width>:<height>"));
+        Ok(())
+    }
+
+    #[test]
+    fn read_glider_everywhere() -> Result<()> {
+        let glider = [false, true, false, false, false, true, true, true, true];
+        let (size, cells, meta) = plaintext("!Name: Glider\n!\n.O.\n..O\nOOO\n")?;
+        assert_eq!((size.width, size.height), (3, 3));
+        assert_eq!(cells, glider);
+        assert_eq!(meta.name.as_deref(), Some("Glider"));
+        let (_, cells, _) = life106("#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n")?;
+        assert_eq!(cells, glider);
+        // two far apart cells would need a board too big to allocate.
+        assert!(life106("#Life 1.06\n0 0\n60000 60000\n").is_err());
+        assert!(life106(&format!("0 0\n{} {}\n", i64::MIN, i64::MAX)).is_err());
+        let (size, cells, _) = plaintext(".O\nOOO\n\n")?;
+        assert_eq!((size.width, size.height), (3, 2));
+        assert_eq!(cells, [false, true, false, true, true, true]);
+        assert!(plaintext(".x.").is_err());
+        Ok(())
+    }
+}
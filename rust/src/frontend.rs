@@ -0,0 +1,305 @@
+//! how the tui shows a game and where it saves it, kept apart from the game itself.
+
+use life_game::{Bounds, Game, Glyphs, Size, State};
+use std::{ops::Range, path::PathBuf};
+
+/// window of a board which is bigger than the terminal.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Viewport {
+    pub(crate) x: u16,
+    pub(crate) y: u16,
+    pub(crate) width: u16,
+    pub(crate) height: u16,
+}
+
+/// the settings of the tui, which the game doesn't need to run.
+#[derive(Debug, Clone)]
+pub(crate) struct Frontend {
+    pub(crate) glyphs: Glyphs,
+    /// the viewport of a board too big to show whole, with the size of the board it was made for.
+    /// a board of another size is shown whole again.
+    pub(crate) view: Option<(Size, Viewport)>,
+    /// where `s` and `C` write their files.
+    pub(crate) save_dir: PathBuf,
+    /// show the bounding box of the live cells, outlined on the board and in the summary.
+    pub(crate) outline: bool,
+    /// mark the edges the board wraps around at, and draw this many cells of the opposite edges
+    /// around a board shown whole.
+    pub(crate) seam: Option<u16>,
+    /// number the columns above the board and the rows left of it.
+    pub(crate) rulers: bool,
+    /// edits of the game when it was last loaded or saved.
+    pub(crate) saved: usize,
+}
+
+impl Default for Frontend {
+    fn default() -> Self {
+        Self {
+            glyphs: Glyphs::default(),
+            view: None,
+            save_dir: PathBuf::from("."),
+            outline: false,
+            seam: None,
+            rulers: false,
+            saved: 0,
+        }
+    }
+}
+
+impl Frontend {
+    /// show the part `view` of a board of `size` from now on.
+    pub(crate) fn set_view(&mut self, size: Size, view: Viewport) {
+        self.view = Some((size, view));
+    }
+
+    /// the viewport, if the board is still the one it was made for.
+    pub(crate) fn viewport(&self, state: &State) -> Option<Viewport> {
+        self.view
+            .filter(|(size, _)| *size == state.size)
+            .map(|(_, view)| view)
+    }
+
+    /// the board was edited since it was loaded or saved.
+    pub(crate) fn edited(&self, game: &Game) -> bool {
+        game.state.edits != self.saved
+    }
+
+    /// the board was just loaded or saved.
+    pub(crate) fn mark_saved(&mut self, game: &Game) {
+        self.saved = game.state.edits;
+    }
+
+    /// columns and rows of the board shown on the terminal.
+    pub(crate) fn visible(&self, state: &State, zoom: u16) -> (Range<u16>, Range<u16>) {
+        match self.viewport(state) {
+            Some(view) => (
+                view.x
+                    ..(view.x + (view.width / (zoom * state.cell_columns())).max(1))
+                        .min(state.size.width),
+                view.y..(view.y + (view.height / zoom).max(1)).min(state.size.height),
+            ),
+            None => (0..state.size.width, 0..state.size.height),
+        }
+    }
+
+    /// move the viewport just enough to show `pos`.
+    pub(crate) fn scroll_to(&mut self, state: &State, pos: (u16, u16), zoom: u16) {
+        let columns = state.cell_columns();
+        let Some((_, view)) = self.view.as_mut().filter(|(size, _)| *size == state.size) else {
+            return;
+        };
+        let (width, height) = (
+            (view.width / (zoom * columns)).max(1),
+            (view.height / zoom).max(1),
+        );
+        if pos.0 < view.x {
+            view.x = pos.0;
+        } else if pos.0 >= view.x + width {
+            view.x = pos.0 + 1 - width;
+        }
+        if pos.1 < view.y {
+            view.y = pos.1;
+        } else if pos.1 >= view.y + height {
+            view.y = pos.1 + 1 - height;
+        }
+    }
+
+    /// move the viewport by `amount`, staying on the board.
+    pub(crate) fn scroll(&mut self, state: &State, amount: (i32, i32)) {
+        let Some((size, view)) = self.view.as_mut().filter(|(size, _)| *size == state.size) else {
+            return;
+        };
+        view.x = (view.x as i32 + amount.0).clamp(0, (size.width - view.width) as i32) as u16;
+        view.y = (view.y as i32 + amount.1).clamp(0, (size.height - view.height) as i32) as u16;
+    }
+
+    /// board position drawn at a terminal cell, if any.
+    pub(crate) fn screen_to_board(
+        &self,
+        state: &State,
+        screen: (u16, u16),
+        zoom: u16,
+    ) -> Option<(u16, u16)> {
+        let (xs, ys) = self.visible(state, zoom);
+        let ghosts = self.ghosts(state);
+        let (rx, ry) = self.ruler_size(state);
+        let columns = zoom.saturating_mul(state.cell_columns());
+        let y = ys.start
+            + screen
+                .1
+                .checked_sub(ry.saturating_add(ghosts.saturating_mul(zoom)))?
+                / zoom;
+        let x = xs.start
+            + screen.0.checked_sub(
+                rx.saturating_add(state.row_offset(y, zoom))
+                    .saturating_add(ghosts.saturating_mul(columns)),
+            )? / columns;
+        (xs.contains(&x) && ys.contains(&y)).then_some((x, y))
+    }
+
+    /// cells of the opposite edges drawn around the board. only a board shown whole has them, as
+    /// a scrolled view would mix them up with the cells next to it.
+    pub(crate) fn ghosts(&self, state: &State) -> u16 {
+        match (self.seam, self.viewport(state)) {
+            (Some(ghosts), None) => ghosts,
+            _ => 0,
+        }
+    }
+
+    /// columns left of the board and rows above it the rulers take, a row number and a space, and
+    /// a row of column numbers.
+    pub(crate) fn ruler_size(&self, state: &State) -> (u16, u16) {
+        if self.rulers {
+            ((state.size.height - 1).to_string().len() as u16 + 1, 1)
+        } else {
+            (0, 0)
+        }
+    }
+
+    /// the bounding box to draw around the live cells, if it is shown.
+    pub(crate) fn outline(&self, game: &Game) -> Option<Bounds> {
+        self.outline.then(|| game.bounding_box()).flatten()
+    }
+
+    /// the visible part of the board as text, each cell a `zoom`x`zoom` block.
+    pub(crate) fn show(
+        &self,
+        game: &Game,
+        zoom: u16,
+        highlight: impl Fn((u16, u16)) -> bool,
+    ) -> String {
+        let visible = self.visible(&game.state, zoom);
+        game.show_cells(&self.glyphs, visible, zoom, highlight)
+    }
+
+    /// the lines under the board: the summary of the game, where the view is, and the bounding
+    /// box if it is outlined.
+    pub(crate) fn summary(&self, game: &Game) -> String {
+        let mut summary = game.summary();
+        if let Some(view) = self.viewport(&game.state) {
+            summary += &format!(", view from {}:{}", view.x, view.y);
+        }
+        if let (Some(((l, t), (r, b))), Some((x, y))) = (self.outline(game), game.center_of_mass())
+        {
+            summary += &format!(
+                "\nbox {}:{} to {}:{} ({}x{}), center {:.1}:{:.1}",
+                l,
+                t,
+                r,
+                b,
+                r - l + 1,
+                b - t + 1,
+                x,
+                y
+            );
+        }
+        summary
+    }
+
+    /// the settings of the game, and those of the tui.
+    pub(crate) fn settings(&self, state: &State) -> String {
+        let mut settings = state.settings();
+        if let Some(ghosts) = self.seam {
+            settings.push_str(&format!("\nseam marked, {} ghost cells around", ghosts));
+        }
+        settings
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use anyhow::Result;
+
+    #[test]
+    fn crop_and_scroll() -> Result<()> {
+        let game = Game::blank("10:10".parse()?)?;
+        let mut frontend = Frontend::default();
+        let view = Viewport {
+            x: 0,
+            y: 0,
+            width: 4,
+            height: 3,
+        };
+        frontend.set_view(game.state.size, view);
+        frontend.scroll_to(&game.state, (6, 1), 1);
+        assert_eq!(frontend.visible(&game.state, 1), (3..7, 0..3));
+        frontend.scroll_to(&game.state, (6, 1), 2);
+        assert_eq!(frontend.visible(&game.state, 2), (5..7, 1..2));
+        frontend.scroll(&game.state, (100, 100));
+        assert_eq!(frontend.visible(&game.state, 1), (6..10, 7..10));
+        assert!(frontend.summary(&game).ends_with(", view from 6:7"));
+        // a board of another size is shown whole.
+        let game = Game::blank("5:5".parse()?)?;
+        assert_eq!(frontend.visible(&game.state, 1), (0..5, 0..5));
+        Ok(())
+    }
+
+    #[test]
+    fn screen_to_board() -> Result<()> {
+        let game = Game::blank("10:10".parse()?)?;
+        let mut frontend = Frontend::default();
+        let at =
+            |frontend: &Frontend, screen, zoom| frontend.screen_to_board(&game.state, screen, zoom);
+        assert_eq!(at(&frontend, (3, 4), 1), Some((3, 4)));
+        assert_eq!(at(&frontend, (7, 5), 2), Some((3, 2)));
+        assert_eq!(at(&frontend, (3, 12), 1), None);
+        let view = Viewport {
+            x: 5,
+            y: 2,
+            width: 4,
+            height: 4,
+        };
+        frontend.set_view(game.state.size, view);
+        assert_eq!(at(&frontend, (1, 1), 1), Some((6, 3)));
+        assert_eq!(at(&frontend, (4, 1), 1), None);
+        Ok(())
+    }
+
+    #[test]
+    fn hex_screen_to_board() -> Result<()> {
+        let mut game = Game::blank("3:2".parse()?)?;
+        game.state.rule = "B2/S34H".parse()?;
+        let mut frontend = Frontend::default();
+        let at = |frontend: &Frontend, screen| frontend.screen_to_board(&game.state, screen, 1);
+        assert_eq!(at(&frontend, (1, 1)), Some((0, 1)));
+        assert_eq!(at(&frontend, (0, 1)), None);
+        assert_eq!(at(&frontend, (4, 0)), Some((2, 0)));
+        // cells of the other edges are drawn around the board.
+        frontend.seam = Some(1);
+        assert_eq!(at(&frontend, (6, 1)), Some((2, 0)));
+        assert_eq!(at(&frontend, (1, 0)), None);
+        // and the rulers left of and above that.
+        frontend.rulers = true;
+        assert_eq!(at(&frontend, (8, 2)), Some((2, 0)));
+        Ok(())
+    }
+
+    #[test]
+    fn outlined_summary() -> Result<()> {
+        let mut game = Game::blank("6:5".parse()?)?;
+        for pos in [(1, 1), (3, 1), (2, 4)] {
+            game.set_pos(pos)?;
+        }
+        let mut frontend = Frontend::default();
+        assert!(!frontend.summary(&game).contains("box"));
+        frontend.outline = true;
+        assert!(frontend
+            .summary(&game)
+            .ends_with("\nbox 1:1 to 3:4 (3x4), center 2.0:2.0"));
+        Ok(())
+    }
+
+    #[test]
+    fn track_edits() -> Result<()> {
+        let mut game = Game::blank("6:5".parse()?)?;
+        let mut frontend = Frontend::default();
+        game.step()?;
+        assert!(!frontend.edited(&game));
+        game.set_pos((2, 1))?;
+        assert!(frontend.edited(&game));
+        frontend.mark_saved(&game);
+        assert!(!frontend.edited(&game));
+        Ok(())
+    }
+}
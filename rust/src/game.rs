@@ -0,0 +1,1824 @@
+use crate::{
+    apgcode,
+    clip::{self, Clip},
+    engine, formats,
+    generate::{RandomKind, Symmetry},
+    meta::Metadata,
+    patterns::Pattern,
+    render::{self, Style},
+    replay::Recorder,
+    rle,
+    rule::{Neighbourhood, Rule, CONDUCTOR},
+    script::Script,
+    stats::{population_csv, sparkline, CycleDetector, PopulationRecord, RunStats},
+    Size,
+};
+use anyhow::{anyhow, bail, ensure, Result};
+use chrono::prelude::Local;
+use clap::ValueEnum;
+use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
+use serde::Deserialize;
+use std::{
+    fmt::Display,
+    fs::write,
+    ops::Range,
+    path::{Component, Path, PathBuf},
+    time::Duration,
+};
+
+/// generations shown by the population sparkline.
+const SPARKLINE_WIDTH: usize = 60;
+
+/// `path` as seen from `dir`, both canonical, or `path` itself if they share no root.
+fn relative_to(path: &Path, dir: &Path) -> PathBuf {
+    let common = path
+        .components()
+        .zip(dir.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+    if common == 0 {
+        return path.to_path_buf();
+    }
+    dir.components()
+        .skip(common)
+        .map(|_| Component::ParentDir)
+        .chain(path.components().skip(common))
+        .collect()
+}
+
+/// positions of cells and their states.
+type States = Vec<((u16, u16), u8)>;
+
+/// the live cells of `what`, a built-in pattern, an apgcode or a file, from the top left of
+/// their bounding box, whose size comes first.
+fn live_cells(what: &str) -> Result<(Size, States)> {
+    let cells: States = match what.parse::<Pattern>() {
+        Ok(pattern) => pattern.cells().map(|pos| (pos, 1)).collect(),
+        Err(_) => {
+            let (width, states) = match apgcode::decode(what) {
+                Ok((_, object)) => (
+                    object.size.width as usize,
+                    object.cells.iter().map(|&v| u8::from(v)).collect(),
+                ),
+                Err(_) => {
+                    let board = formats::load(Path::new(what), None)?;
+                    let width = board.size.width as usize;
+                    (width, engine::states(&board.cells, &board.dying))
+                }
+            };
+            states
+                .into_iter()
+                .enumerate()
+                .filter(|&(_, state)| state > 0)
+                .map(|(i, state)| (((i % width) as u16, (i / width) as u16), state))
+                .collect()
+        }
+    };
+
+    let (left, top) = cells
+        .iter()
+        .fold((u16::MAX, u16::MAX), |(l, t), &((x, y), _)| {
+            (l.min(x), t.min(y))
+        });
+    let (right, bottom) = cells
+        .iter()
+        .fold((0, 0), |(r, b), &((x, y), _)| (r.max(x), b.max(y)));
+    ensure!(!cells.is_empty(), "{} has no live cells.", what);
+    let size = Size {
+        width: right - left + 1,
+        height: bottom - top + 1,
+    };
+    let cells = cells
+        .into_iter()
+        .map(|((x, y), state)| ((x - left, y - top), state))
+        .collect();
+    Ok((size, cells))
+}
+
+/// a board of `size` in the native format, without metadata.
+fn native(size: Size, states: &[u8]) -> String {
+    let mut data = format!("{}:{}", size.width, size.height);
+    for (i, &state) in states.iter().enumerate() {
+        if i % size.width as usize == 0 {
+            data.push('\n')
+        }
+        data.push(formats::state_char(state));
+    }
+    data
+}
+
+/// `duration` rounded to milliseconds, for the status line.
+pub fn show_duration(duration: Duration) -> humantime::FormattedDuration {
+    humantime::format_duration(Duration::from_millis(duration.as_millis() as u64))
+}
+
+/// (top left, bottom right) corners of a box of cells, inclusive.
+pub type Bounds = ((u16, u16), (u16, u16));
+
+/// where the old board lands on a resized one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Anchor {
+    /// the middles of both boards line up.
+    #[default]
+    Center,
+    /// the top left corners line up.
+    TopLeft,
+}
+
+/// a change of the whole board, see [`Game::transform`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    /// move every cell right and down by the amounts, wrapping around the edges.
+    Shift(i32, i32),
+    /// rotate 90° clockwise.
+    Rotate,
+    /// mirror left and right.
+    FlipHorizontal,
+    /// mirror top and bottom.
+    FlipVertical,
+}
+
+/// file format of dumped frames.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum DumpFormat {
+    /// the native format, as `s` saves.
+    Native,
+    /// run length encoded.
+    Rle,
+    /// a picture, drawn like `--screenshot`.
+    Png,
+}
+
+impl DumpFormat {
+    /// extension of the dumped files.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Native => "txt",
+            Self::Rle => "rle",
+            Self::Png => "png",
+        }
+    }
+}
+
+/// where and how often frames are written while running.
+#[derive(Debug)]
+pub struct FrameDump {
+    /// directory the frames are written into, named after their generation.
+    pub dir: PathBuf,
+    /// generations between two frames.
+    pub every: u64,
+    /// how the frames are written.
+    pub format: DumpFormat,
+}
+
+/// random flips of cells after every generation.
+#[derive(Debug)]
+pub struct Noise {
+    /// chance of every cell to flip.
+    pub probability: f64,
+    /// seed of the flips, to repeat them.
+    pub seed: u64,
+    rng: StdRng,
+}
+
+impl Noise {
+    /// flip every cell with `probability`, drawn from `seed`.
+    pub fn new(probability: f64, seed: u64) -> Self {
+        Self {
+            probability,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+/// everything about a run but the cells themselves.
+#[derive(Debug)]
+pub struct State {
+    /// size of the board.
+    pub size: Size,
+    /// generation of the board.
+    pub time: usize,
+    /// time between two generations while auto running.
+    pub duration: Duration,
+    /// cells on the board, `width * height`.
+    pub len: usize,
+    /// the rule cells live and die by.
+    pub rule: Rule,
+    /// replaces the rule, which still gives the states and the neighbourhood.
+    pub script: Option<Script>,
+    /// live cells on the board.
+    pub population: usize,
+    /// stop auto running once the board died out or stabilized.
+    pub auto_stop: bool,
+    /// stop auto running at this generation.
+    pub limit: Option<usize>,
+    /// generations computed per drawn frame while auto running.
+    pub render_every: u64,
+    /// recent boards, to notice the board repeating.
+    pub cycles: CycleDetector,
+    /// (generation the cycle started, period) if the board repeats an earlier one.
+    pub cycle: Option<(usize, usize)>,
+    /// population of every generation so far.
+    pub history: Vec<PopulationRecord>,
+    /// where frames are written while running, if they are.
+    pub dump: Option<FrameDump>,
+    /// random flips after every generation, if any.
+    pub noise: Option<Noise>,
+    /// statistics of the run, if they are tracked.
+    pub stats: Option<RunStats>,
+    /// (path, board) of the base file saves are made against.
+    pub delta_base: Option<(PathBuf, Vec<bool>)>,
+    /// how pictures of the board look.
+    pub picture: Style,
+    /// seed of the random board the run started from.
+    pub seed: Option<u64>,
+    /// saves are cut down to the live cells and this many dead cells around them, except deltas.
+    pub trim: Option<u16>,
+    /// symmetry of random boards.
+    pub symmetry: Symmetry,
+    /// how random boards are filled.
+    pub random_kind: RandomKind,
+    /// size of the random soup in the middle of random boards, if not the whole board.
+    pub soup: Option<Size>,
+    /// written into saves.
+    pub name: Option<String>,
+    /// changes made to the board other than steps, counted up by every edit. compare it with the
+    /// count at the last save to know whether there is anything new to save.
+    pub edits: usize,
+}
+
+/// characters cells are drawn with. `*_selected` are used under the cursor and the selection.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Glyphs {
+    /// live cells, and every colour of a coloured rule.
+    pub alive: char,
+    /// dead cells.
+    pub dead: char,
+    /// every dying state of a Generations rule, and electron tails of Wireworld.
+    pub dying: char,
+    /// conductors of Wireworld. electron heads are `alive`.
+    pub conductor: char,
+    /// live cells under the cursor and the selection.
+    pub alive_selected: char,
+    /// dead cells under the cursor and the selection.
+    pub dead_selected: char,
+}
+
+impl Glyphs {
+    /// glyph of a cell in `state` under `rule`, 0 dead, 1 alive and 2 and up dying, which is
+    /// `selected` or not. every colour of a live cell is `alive`.
+    pub fn pick(&self, rule: &Rule, state: u8, selected: bool) -> char {
+        match (state, selected) {
+            (s, false) if rule.alive(s) => self.alive,
+            (0, false) => self.dead,
+            (CONDUCTOR, false) if rule.is_wireworld() => self.conductor,
+            (_, false) => self.dying,
+            (s, true) if rule.alive(s) => self.alive_selected,
+            (_, true) => self.dead_selected,
+        }
+    }
+}
+
+impl Default for Glyphs {
+    fn default() -> Self {
+        Self {
+            alive: '@',
+            dead: '-',
+            dying: 'o',
+            conductor: '=',
+            alive_selected: '#',
+            dead_selected: '+',
+        }
+    }
+}
+
+impl Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "{}, {}times, population {}",
+            self.size, self.time, self.population
+        )?;
+        match self.cycle {
+            Some((_, 1)) => write!(f, ", still life")?,
+            Some((_, period)) => write!(f, ", period {}", period)?,
+            None => {}
+        }
+        Ok(())
+    }
+}
+
+impl State {
+    /// every setting of the run, for the help screen.
+    pub fn settings(&self) -> String {
+        let on = |b: bool| if b { "on" } else { "off" };
+        let mut settings = format!(
+            "rule: {}\nsize: {}\nboundary: wrap around (torus)\nduration: {}\ndraw every {} generations\nauto stop: {}\nstatistics: {}",
+            self.rule,
+            self.size,
+            show_duration(self.duration),
+            self.render_every,
+            on(self.auto_stop),
+            on(self.stats.is_some()),
+        );
+        if let Some(script) = &self.script {
+            settings.push_str(&format!("\nrule script: {}", script));
+        }
+        if let Some(limit) = self.limit {
+            settings.push_str(&format!("\ngeneration limit: {}", limit));
+        }
+        if let Some(dump) = &self.dump {
+            settings.push_str(&format!(
+                "\ndump every {} generations to {}",
+                dump.every,
+                dump.dir.display()
+            ));
+        }
+        if let Some((path, _)) = &self.delta_base {
+            settings.push_str(&format!("\ndelta base: {}", path.display()));
+        }
+        if let Some(noise) = &self.noise {
+            settings.push_str(&format!(
+                "\nnoise: {} (seed {})",
+                noise.probability, noise.seed
+            ));
+        }
+        if let Some(seed) = self.seed {
+            settings.push_str(&format!("\nseed: {}", seed));
+        }
+        if let Some(padding) = self.trim {
+            settings.push_str(&format!("\nsaves trimmed, padding {}", padding));
+        }
+        if self.random_kind != RandomKind::Uniform {
+            settings.push_str(&format!("\nrandom boards: {:?}", self.random_kind));
+        }
+        if self.symmetry != Symmetry::C1 {
+            settings.push_str(&format!("\nsymmetry: {:?}", self.symmetry));
+        }
+        if let Some(soup) = self.soup {
+            settings.push_str(&format!("\nsoup: {}", soup));
+        }
+        if let Some(name) = &self.name {
+            settings.push_str(&format!("\nname: {}", name));
+        }
+        settings
+    }
+
+    /// default settings for an empty board of `size`.
+    pub fn blank(size: Size) -> Result<Self> {
+        Ok(Self {
+            size,
+            time: 0,
+            duration: Duration::from_millis(100),
+            len: usize::checked_mul(size.width.into(), size.height.into())
+                .ok_or_else(|| anyhow!("overflow"))?,
+            rule: Rule::default(),
+            script: None,
+            population: 0,
+            auto_stop: true,
+            limit: None,
+            render_every: 1,
+            cycles: CycleDetector::default(),
+            cycle: None,
+            history: Vec::new(),
+            dump: None,
+            noise: None,
+            stats: None,
+            delta_base: None,
+            picture: Style::default(),
+            seed: None,
+            trim: None,
+            symmetry: Symmetry::default(),
+            random_kind: RandomKind::default(),
+            soup: None,
+            name: None,
+            edits: 0,
+        })
+    }
+
+    /// take over how a loaded board was produced. the rule is kept if it isn't known.
+    pub fn apply_metadata(&mut self, meta: Metadata) {
+        self.time = meta.generation.unwrap_or(0);
+        if let Some(rule) = meta.rule {
+            self.rule = rule;
+        }
+        self.seed = meta.seed;
+        self.name = meta.name;
+    }
+
+    /// terminal columns a cell takes at zoom 1. cells of a hexagonal board have a gap after them,
+    /// so odd rows can be drawn half a cell to the right.
+    pub fn cell_columns(&self) -> u16 {
+        match self.rule.neighbourhood() {
+            Neighbourhood::Hex => 2,
+            _ => 1,
+        }
+    }
+
+    /// columns row `y` is drawn to the right at `zoom`: half a cell for odd rows of a hexagonal
+    /// board.
+    pub fn row_offset(&self, y: u16, zoom: u16) -> u16 {
+        if self.cell_columns() > 1 && y % 2 == 1 {
+            zoom
+        } else {
+            0
+        }
+    }
+
+    /// `pos` moved by `amount`, wrapping around the edges.
+    pub fn move_to(&self, pos: (u16, u16), amount: (i16, i16)) -> Result<(u16, u16)> {
+        let (px, py) = pos;
+        let (ax, ay) = amount;
+        Ok((
+            ((self.size.width as i32 + px as i32 + ax as i32) % self.size.width as i32) as u16,
+            ((self.size.height as i32 + py as i32 + ay as i32) % self.size.height as i32) as u16,
+        ))
+    }
+}
+
+/// a board and the state of its run.
+#[derive(Debug)]
+pub struct Game {
+    /// whether each cell is alive, in rows.
+    pub game: Vec<bool>,
+    /// state of every dying cell, 2 and up, or the colour of a live cell under a coloured rule,
+    /// and 0 for the rest. all 0 under a life-like rule.
+    pub dying: Vec<u8>,
+    /// everything about the run but the cells.
+    pub state: State,
+    /// where the run is recorded, if it is.
+    recorder: Option<Recorder>,
+}
+
+/// a copy of the board at one generation. kept by `m1`..`m9` and yielded by [`Game::generations`].
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    /// size of the board.
+    pub size: Size,
+    /// generation of the board.
+    pub time: usize,
+    /// whether each cell is alive, in rows.
+    pub game: Vec<bool>,
+    /// state of every dying cell, as in [`Game::dying`].
+    pub dying: Vec<u8>,
+}
+
+impl Display for Game {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "{}\n{}", self.show_board(), self.summary())
+    }
+}
+
+impl Game {
+    /// a game on `game`, whose cells are in rows of `size.width`.
+    pub fn from_board(size: Size, game: Vec<bool>) -> Result<Self> {
+        let state = State::blank(size)?;
+        ensure!(
+            game.len() == state.len,
+            "board doesn't match its size {}.",
+            size
+        );
+        let mut game = Self {
+            dying: vec![0; game.len()],
+            game,
+            state,
+            recorder: None,
+        };
+        game.recount();
+        game.record_population(0, 0);
+        Ok(game)
+    }
+
+    /// an empty board of `size`.
+    pub fn blank(size: Size) -> Result<Self> {
+        let state = State::blank(size)?;
+        Self::from_board(size, vec![false; state.len])
+    }
+
+    /// statistics of the run, if they are collected.
+    pub fn stats(&self) -> Option<&RunStats> {
+        self.state.stats.as_ref()
+    }
+
+    /// start the statistics over from the current board.
+    pub fn reset_stats(&mut self) {
+        self.state.stats = Some(RunStats::new(self.state.size, &self.game, self.state.time));
+    }
+
+    /// count generations from 0 again, starting the population history and statistics over.
+    pub fn reset_time(&mut self) {
+        self.state.time = 0;
+        self.state.history.clear();
+        self.record_population(0, 0);
+        if self.state.stats.is_some() {
+            self.reset_stats();
+        }
+    }
+
+    /// count the population from scratch, after replacing the whole board.
+    pub fn recount(&mut self) {
+        self.state.population = self.game.iter().filter(|&&v| v).count();
+        self.update_record();
+        self.forget_history();
+    }
+
+    /// keep the record of the current generation up with the board, after it was edited.
+    fn update_record(&mut self) {
+        let time = self.state.time;
+        if let Some(record) = self.state.history.last_mut() {
+            if record.generation == time {
+                record.population = self.state.population;
+            }
+        }
+    }
+
+    /// append the current population to the history.
+    pub fn record_population(&mut self, births: usize, deaths: usize) {
+        self.state.history.push(PopulationRecord {
+            generation: self.state.time,
+            population: self.state.population,
+            births,
+            deaths,
+        });
+    }
+
+    /// write the population history as csv into `dir`.
+    pub fn save_population(&self, dir: &Path) -> Result<String> {
+        let path = dir.join(
+            Local::now()
+                .format("%F_%H.%M.%ST%z_population.csv")
+                .to_string(),
+        );
+        write(&path, population_csv(&self.state.history))?;
+
+        Ok(format!("success save population to {}", path.display()))
+    }
+
+    /// start cycle detection over, after the board was edited.
+    pub fn forget_history(&mut self) {
+        self.state.cycles = CycleDetector::default();
+        self.state.cycle = None;
+    }
+
+    /// switch to `rule`, keeping the board. cells in a state `rule` doesn't have are alive if they
+    /// were, and dead otherwise. a rule script is dropped.
+    pub fn set_rule(&mut self, rule: Rule) {
+        let old = self.state.rule;
+        let states: Vec<u8> = self
+            .states()
+            .into_iter()
+            .map(|state| match state {
+                s if rule.alive(s) || (s > 1 && s < rule.states()) => s,
+                s => u8::from(old.alive(s)),
+            })
+            .collect();
+        (self.game, self.dying) = engine::split(&states, &rule);
+        self.state.rule = rule;
+        self.state.script = None;
+        self.recount();
+    }
+
+    /// kill every cell.
+    pub fn clear(&mut self) {
+        self.game = vec![false; self.state.len];
+        self.dying = vec![0; self.state.len];
+        self.state.edits += 1;
+        self.recount();
+    }
+
+    /// start a new random board with a new seed, from generation 0. returns the seed.
+    pub fn random(&mut self) -> Result<u64> {
+        let seed = thread_rng().gen();
+        self.random_from(seed)?;
+        self.reset_time();
+        Ok(seed)
+    }
+
+    /// fill the board randomly from `seed`, with the symmetry of the state. only the soup in the
+    /// middle is filled if the state has one, and the rest of the board is dead.
+    pub fn random_from(&mut self, seed: u64) -> Result<()> {
+        let size = self.state.size;
+        let soup = self.state.soup.unwrap_or(size);
+        ensure!(
+            soup.width <= size.width && soup.height <= size.height,
+            "soup {} is bigger than the board {}.",
+            soup,
+            size
+        );
+        ensure!(
+            soup.width > 0 && soup.height > 0,
+            "soup {} has no cells to fill.",
+            soup
+        );
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut cells = self.state.random_kind.fill(&mut rng, soup);
+        self.state.symmetry.apply(soup, &mut cells)?;
+        // under a coloured rule, live cells get random colours.
+        let mut colours = vec![0; cells.len()];
+        let n = self.state.rule.colours();
+        if n > 0 {
+            for (&alive, colour) in cells.iter().zip(&mut colours) {
+                if alive {
+                    *colour = rng.gen_range(1..=n);
+                }
+            }
+        }
+        self.state.symmetry.apply(soup, &mut colours)?;
+
+        self.state.seed = Some(seed);
+        self.state.edits += 1;
+        self.game = vec![false; self.state.len];
+        self.dying = vec![0; self.state.len];
+        let (width, soup_width) = (size.width as usize, soup.width as usize);
+        let left = (width - soup_width) / 2;
+        let top = usize::from((size.height - soup.height) / 2);
+        let rows = cells.chunks(soup_width).zip(colours.chunks(soup_width));
+        for (y, (row, colour_row)) in rows.enumerate() {
+            let start = (top + y) * width + left;
+            self.game[start..][..soup_width].copy_from_slice(row);
+            self.dying[start..][..soup_width].copy_from_slice(colour_row);
+        }
+        self.recount();
+        if self.state.stats.is_some() {
+            self.reset_stats();
+        }
+        Ok(())
+    }
+
+    /// the lines under the board: a sparkline of the population and the state.
+    pub fn summary(&self) -> String {
+        format!(
+            "{}\n{}",
+            sparkline(&self.state.history, SPARKLINE_WIDTH),
+            self.state
+        )
+    }
+
+    /// the whole board as text, drawn with the default glyphs.
+    pub fn show_board(&self) -> String {
+        let whole = (0..self.state.size.width, 0..self.state.size.height);
+        self.show_cells(&Glyphs::default(), whole, 1, |_| false)
+    }
+
+    /// the columns `xs` of the rows `ys` of the board as text, each cell drawn with `glyphs` as a
+    /// `zoom`x`zoom` block. cells picked by `highlight` use the selected glyphs. cells of a
+    /// hexagonal board are spaced out, and odd rows moved half a cell to the right.
+    pub fn show_cells(
+        &self,
+        glyphs: &Glyphs,
+        (xs, ys): (Range<u16>, Range<u16>),
+        zoom: u16,
+        highlight: impl Fn((u16, u16)) -> bool,
+    ) -> String {
+        let width = self.state.size.width as usize;
+        let gap = usize::from((self.state.cell_columns() - 1) * zoom);
+        let mut formatted = String::new();
+        for y in ys {
+            let mut line = " ".repeat(self.state.row_offset(y, zoom).into());
+            for x in xs.clone() {
+                if x > xs.start {
+                    line.extend(std::iter::repeat_n(' ', gap));
+                }
+                let c = glyphs.pick(
+                    &self.state.rule,
+                    self.state_at(y as usize * width + x as usize),
+                    highlight((x, y)),
+                );
+                line.extend(std::iter::repeat_n(c, zoom.into()));
+            }
+            for _ in 0..zoom {
+                formatted.push_str(&line);
+                formatted.push('\n');
+            }
+        }
+        formatted
+    }
+
+    /// advance one generation.
+    pub fn step(&mut self) -> Result<()> {
+        if let Some(recorder) = &mut self.recorder {
+            let states = engine::states(&self.game, &self.dying);
+            recorder.catch_up(self.state.size, self.state.rule, states)?;
+        }
+        if self.state.cycles.is_empty() {
+            self.state.cycles.check(&self.game, self.state.time);
+        }
+        self.state.time += 1;
+        let (size, rule) = (self.state.size, &self.state.rule);
+        let mut next = match &self.state.script {
+            Some(script) => engine::step_script(size, rule, script, &self.game, &self.dying),
+            None => engine::step(size, rule, &self.game, &self.dying),
+        };
+        if let Some(noise) = &mut self.state.noise {
+            next.perturb(|| noise.rng.gen_bool(noise.probability));
+        }
+        self.state.population = self.state.population + next.births - next.deaths;
+        self.game = next.cells;
+        self.dying = next.dying;
+        self.record_population(next.births, next.deaths);
+        self.state.cycle = self.state.cycles.check(&self.game, self.state.time);
+        if let Some(stats) = &mut self.state.stats {
+            stats.record(&self.game, self.state.time);
+        }
+        if let Some(recorder) = &mut self.recorder {
+            recorder.stepped(engine::states(&self.game, &self.dying));
+        }
+        Ok(())
+    }
+
+    /// record every step and edit of the run from now on to `path`, to be played back with
+    /// [`replay`](crate::replay). a rule script can't be recorded.
+    pub fn record(&mut self, path: &Path) -> Result<()> {
+        self.recorder = Some(Recorder::create(self, path)?);
+        Ok(())
+    }
+
+    /// write the end of the recording started with [`record`](Self::record), if there is one.
+    pub fn finish_recording(&mut self) -> Result<()> {
+        match self.recorder.take() {
+            Some(recorder) => recorder.finish(self),
+            None => Ok(()),
+        }
+    }
+
+    /// step `n` generations without drawing, stopping early if the board died out.
+    pub fn skip(&mut self, n: usize) -> Result<usize> {
+        for done in 0..n {
+            if self.state.population == 0 {
+                return Ok(done);
+            }
+            self.step()?;
+        }
+        Ok(n)
+    }
+
+    /// step lazily, yielding a copy of each new generation. never ends by itself.
+    ///
+    /// ```
+    /// # use life_game::{patterns::Pattern, Game};
+    /// let size = "5:5".parse()?;
+    /// let mut game = Game::from_board(size, "blinker".parse::<Pattern>()?.centered(size)?)?;
+    /// let start = game.game.clone();
+    /// let later: Vec<_> = game.generations().take(2).collect::<anyhow::Result<_>>()?;
+    /// assert_ne!(later[0].game, start);
+    /// assert_eq!(later[1].game, start);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn generations(&mut self) -> impl Iterator<Item = Result<Checkpoint>> + '_ {
+        std::iter::repeat_with(move || {
+            self.step()?;
+            Ok(self.checkpoint())
+        })
+    }
+
+    /// fail unless `pos` is on the board.
+    pub fn check_pos(&self, pos: (u16, u16)) -> Result<()> {
+        let (x, y) = pos;
+        ensure!(
+            x < self.state.size.width,
+            "x:{} is bigger than width:{}",
+            x,
+            self.state.size.width
+        );
+        ensure!(
+            y < self.state.size.height,
+            "y:{} is bigger than height:{}",
+            y,
+            self.state.size.height
+        );
+        Ok(())
+    }
+
+    /// names of the cell states, indexed by state. used by the editor's palette.
+    pub fn state_names(&self) -> Vec<String> {
+        if self.state.rule.is_wireworld() {
+            return ["empty", "electron head", "electron tail", "conductor"]
+                .map(String::from)
+                .to_vec();
+        }
+        if self.state.rule.colours() > 0 {
+            let mut names = vec!["dead".to_string()];
+            names.extend((1..=self.state.rule.colours()).map(|c| format!("colour {}", c)));
+            return names;
+        }
+        let mut names = vec!["dead".to_string(), "alive".to_string()];
+        names.extend((2..self.state.rule.states()).map(|s| format!("dying {}", s - 1)));
+        names
+    }
+
+    /// state of the cell at `idx`: 0 dead, 1 alive, 2 and up dying, or the colour of a live cell.
+    pub fn state_at(&self, idx: usize) -> u8 {
+        if self.game[idx] {
+            self.dying[idx].max(1)
+        } else {
+            self.dying[idx]
+        }
+    }
+
+    /// state of every cell, as [`state_at`](Self::state_at).
+    pub fn states(&self) -> Vec<u8> {
+        engine::states(&self.game, &self.dying)
+    }
+
+    /// index of `pos` into the cells.
+    pub fn index(&self, pos: (u16, u16)) -> Result<usize> {
+        self.check_pos(pos)?;
+        let (x, y) = pos;
+        Ok(y as usize * self.state.size.width as usize + x as usize)
+    }
+
+    /// toggle the cell at `pos`. a dying cell becomes alive.
+    pub fn set_pos(&mut self, pos: (u16, u16)) -> Result<()> {
+        let idx = self.index(pos)?;
+        self.dying[idx] = 0;
+        self.game[idx] = !self.game[idx];
+        if self.game[idx] {
+            self.state.population += 1;
+        } else {
+            self.state.population -= 1;
+        }
+        self.state.edits += 1;
+        self.update_record();
+        self.forget_history();
+        Ok(())
+    }
+
+    /// make the cell at `pos` alive or dead.
+    pub fn set_cell(&mut self, pos: (u16, u16), alive: bool) -> Result<()> {
+        let idx = self.index(pos)?;
+        self.dying[idx] = 0;
+        if self.game[idx] != alive {
+            self.set_pos(pos)?;
+        }
+        Ok(())
+    }
+
+    /// put the cell at `pos` into `state`, one of [`state_names`](Self::state_names).
+    pub fn set_state(&mut self, pos: (u16, u16), state: u8) -> Result<()> {
+        ensure!(
+            state < self.state.rule.states(),
+            "{} has no state {}.",
+            self.state.rule,
+            state
+        );
+        self.set_cell(pos, self.state.rule.alive(state))?;
+        if state > 1 {
+            let idx = self.index(pos)?;
+            self.dying[idx] = state;
+        }
+        Ok(())
+    }
+
+    /// cells of the rectangle with corners `a` and `b`.
+    pub fn copy_rect(&self, a: (u16, u16), b: (u16, u16)) -> Result<Clip> {
+        let ((x0, y0), (x1, y1)) = clip::rect(a, b);
+        let mut cells = Vec::new();
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                cells.push(self.game[self.index((x, y))?]);
+            }
+        }
+        Ok(Clip {
+            width: x1 - x0 + 1,
+            height: y1 - y0 + 1,
+            cells,
+        })
+    }
+
+    /// the live cells of `what`, a built-in pattern, an apgcode or a file, cut down to their
+    /// bounding box, e.g. to place them with the editor.
+    pub fn clip_of(what: &str) -> Result<Clip> {
+        let (size, cells) = live_cells(what)?;
+        let mut cells_of_clip = vec![false; size.width as usize * size.height as usize];
+        for ((x, y), _) in cells {
+            cells_of_clip[y as usize * size.width as usize + x as usize] = true;
+        }
+        Ok(Clip {
+            width: size.width,
+            height: size.height,
+            cells: cells_of_clip,
+        })
+    }
+
+    /// put every cell of the rectangle with corners `a` and `b` into `state`.
+    pub fn fill_rect(&mut self, a: (u16, u16), b: (u16, u16), state: u8) -> Result<()> {
+        let ((x0, y0), (x1, y1)) = clip::rect(a, b);
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                self.set_state((x, y), state)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// live cells of the rectangle with corners `a` and `b`, and their bounding box.
+    pub fn live_in(&self, a: (u16, u16), b: (u16, u16)) -> (usize, Option<Bounds>) {
+        let ((x0, y0), (x1, y1)) = clip::rect(a, b);
+        let width = self.state.size.width as usize;
+        let mut live = 0;
+        let mut bbox: Option<Bounds> = None;
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                if !self.game[y as usize * width + x as usize] {
+                    continue;
+                }
+                live += 1;
+                bbox = Some(match bbox {
+                    None => ((x, y), (x, y)),
+                    Some(((l, t), (r, b))) => ((l.min(x), t.min(y)), (r.max(x), b.max(y))),
+                });
+            }
+        }
+        (live, bbox)
+    }
+
+    /// make every cell of the rectangle with corners `a` and `b` alive with probability
+    /// `density`, and dead otherwise.
+    pub fn fill_random(
+        &mut self,
+        a: (u16, u16),
+        b: (u16, u16),
+        density: f64,
+        rng: &mut impl Rng,
+    ) -> Result<()> {
+        let ((x0, y0), (x1, y1)) = clip::rect(a, b);
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                self.set_cell((x, y), rng.gen_bool(density))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// overwrite the board with `clip`, its top-left at `pos`. wraps around the edges.
+    pub fn paste(&mut self, pos: (u16, u16), clip: &Clip) -> Result<()> {
+        let width = clip.width as usize;
+        for (i, &v) in clip.cells.iter().enumerate() {
+            let to = self.move_to(pos, ((i % width) as i16, (i / width) as i16))?;
+            self.set_cell(to, v)?;
+        }
+        Ok(())
+    }
+
+    /// stamp the live cells of `what`, a built-in pattern, an apgcode or a file, onto the board
+    /// with the top left or the middle of their bounding box at `pos`. cells which run off the
+    /// board wrap around if `wrap`, or are an error. gives the size of the stamp.
+    pub fn stamp(
+        &mut self,
+        what: &str,
+        pos: (u16, u16),
+        anchor: Anchor,
+        wrap: bool,
+    ) -> Result<Size> {
+        let (size, cells) = live_cells(what)?;
+        let states = self.state.rule.states();
+        if let Some(&(_, state)) = cells.iter().find(|&&(_, state)| state >= states) {
+            bail!("{} has no state {}.", self.state.rule, state);
+        }
+        let (x0, y0) = match anchor {
+            Anchor::TopLeft => (i32::from(pos.0), i32::from(pos.1)),
+            Anchor::Center => (
+                i32::from(pos.0) - i32::from(size.width / 2),
+                i32::from(pos.1) - i32::from(size.height / 2),
+            ),
+        };
+        let (width, height) = (
+            i32::from(self.state.size.width),
+            i32::from(self.state.size.height),
+        );
+        ensure!(
+            wrap || (x0 >= 0
+                && y0 >= 0
+                && x0 + i32::from(size.width) <= width
+                && y0 + i32::from(size.height) <= height),
+            "{} ({}) at {}:{} runs off the board. add wrap to wrap it around.",
+            what,
+            size,
+            pos.0,
+            pos.1
+        );
+        for ((x, y), state) in cells {
+            let to = (
+                (x0 + i32::from(x)).rem_euclid(width) as u16,
+                (y0 + i32::from(y)).rem_euclid(height) as u16,
+            );
+            self.set_state(to, state)?;
+        }
+        Ok(size)
+    }
+
+    /// `pos` moved by `amount`, wrapping around the edges.
+    pub fn move_to(&self, pos: (u16, u16), amount: (i16, i16)) -> Result<(u16, u16)> {
+        self.state.move_to(pos, amount)
+    }
+
+    /// (top left, bottom right) corners of the cells which aren't dead, inclusive.
+    pub fn bounding_box(&self) -> Option<Bounds> {
+        let width = self.state.size.width as usize;
+        self.states()
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, state)| state > 0)
+            .map(|(i, _)| ((i % width) as u16, (i / width) as u16))
+            .fold(None, |bbox, (x, y)| {
+                Some(match bbox {
+                    None => ((x, y), (x, y)),
+                    Some(((l, t), (r, b))) => ((l.min(x), t.min(y)), (r.max(x), b.max(y))),
+                })
+            })
+    }
+
+    /// mean position of the cells which aren't dead.
+    pub fn center_of_mass(&self) -> Option<(f64, f64)> {
+        let width = self.state.size.width as usize;
+        let (mut n, mut sx, mut sy) = (0usize, 0usize, 0usize);
+        for (i, state) in self.states().into_iter().enumerate() {
+            if state > 0 {
+                n += 1;
+                sx += i % width;
+                sy += i / width;
+            }
+        }
+        (n > 0).then(|| (sx as f64 / n as f64, sy as f64 / n as f64))
+    }
+
+    /// size and states of the board cut down to its bounding box and `padding` dead cells around
+    /// it, which may be more than the board has.
+    pub fn trimmed(&self, padding: u16) -> Result<(Size, Vec<u8>)> {
+        let ((left, top), (right, bottom)) = self
+            .bounding_box()
+            .ok_or_else(|| anyhow!("the board is empty."))?;
+        let grow = |n: u16| {
+            n.checked_add(padding.saturating_mul(2))
+                .ok_or_else(|| anyhow!("padding {} is too big.", padding))
+        };
+        let size = Size {
+            width: grow(right - left + 1)?,
+            height: grow(bottom - top + 1)?,
+        };
+        let states = self.states();
+        let (width, height) = (self.state.size.width as i32, self.state.size.height as i32);
+        let (x0, y0) = (
+            i32::from(left) - i32::from(padding),
+            i32::from(top) - i32::from(padding),
+        );
+        let cells = (0..i32::from(size.height))
+            .flat_map(|y| (0..i32::from(size.width)).map(move |x| (x0 + x, y0 + y)))
+            .map(|(x, y)| {
+                if (0..width).contains(&x) && (0..height).contains(&y) {
+                    states[(y * width + x) as usize]
+                } else {
+                    0
+                }
+            })
+            .collect();
+        Ok((size, cells))
+    }
+
+    /// cut the board down to its bounding box and `padding` dead cells around it.
+    pub fn trim(&mut self, padding: u16) -> Result<()> {
+        let (size, states) = self.trimmed(padding)?;
+        (self.game, self.dying) = engine::split(&states, &self.state.rule);
+        self.state.size = size;
+        self.state.len = states.len();
+        self.state.edits += 1;
+        self.replaced();
+        Ok(())
+    }
+
+    /// shift, rotate or mirror the whole board with its dying states. nothing is lost.
+    pub fn transform(&mut self, transform: Transform) {
+        let Size { width, height } = self.state.size;
+        let (w, h) = (i32::from(width), i32::from(height));
+        let size = match transform {
+            Transform::Rotate => Size {
+                width: height,
+                height: width,
+            },
+            _ => self.state.size,
+        };
+        // the cell of the old board every cell of the new one comes from.
+        let from = |x: i32, y: i32| -> (i32, i32) {
+            match transform {
+                Transform::Shift(dx, dy) => ((x - dx).rem_euclid(w), (y - dy).rem_euclid(h)),
+                Transform::Rotate => (y, h - 1 - x),
+                Transform::FlipHorizontal => (w - 1 - x, y),
+                Transform::FlipVertical => (x, h - 1 - y),
+            }
+        };
+        let indices: Vec<usize> = (0..i32::from(size.height))
+            .flat_map(|y| (0..i32::from(size.width)).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let (x, y) = from(x, y);
+                (y * w + x) as usize
+            })
+            .collect();
+        self.game = indices.iter().map(|&i| self.game[i]).collect();
+        self.dying = indices.iter().map(|&i| self.dying[i]).collect();
+        self.state.size = size;
+        self.state.edits += 1;
+        self.replaced();
+    }
+
+    /// cut the board down to `size`, keeping its center.
+    pub fn crop(&mut self, size: Size) -> Result<()> {
+        let size = Size {
+            width: size.width.min(self.state.size.width),
+            height: size.height.min(self.state.size.height),
+        };
+        self.resize(size, Anchor::Center)
+    }
+
+    /// change the size of the board to `size`. a bigger board gets dead cells around the old one,
+    /// a smaller one loses the cells outside of it. the old board lies at `anchor` of the new.
+    pub fn resize(&mut self, size: Size, anchor: Anchor) -> Result<()> {
+        ensure!(
+            size.width > 0 && size.height > 0,
+            "a board of {} has no cells.",
+            size
+        );
+        let old = self.state.size;
+        let len = State::blank(size)?.len;
+        // position of the new board on the old one.
+        let (ox, oy) = match anchor {
+            Anchor::Center => (
+                (i32::from(old.width) - i32::from(size.width)) / 2,
+                (i32::from(old.height) - i32::from(size.height)) / 2,
+            ),
+            Anchor::TopLeft => (0, 0),
+        };
+        let index = |size: Size, x: u16, y: u16| y as usize * size.width as usize + x as usize;
+        let mut game = vec![false; len];
+        let mut dying = vec![0; len];
+        for y in 0..size.height {
+            for x in 0..size.width {
+                let from = (i32::from(x) + ox, i32::from(y) + oy);
+                let (Ok(fx), Ok(fy)) = (u16::try_from(from.0), u16::try_from(from.1)) else {
+                    continue;
+                };
+                if fx < old.width && fy < old.height {
+                    let (to, from) = (index(size, x, y), index(old, fx, fy));
+                    game[to] = self.game[from];
+                    dying[to] = self.dying[from];
+                }
+            }
+        }
+        self.state.size = size;
+        self.state.len = len;
+        self.game = game;
+        self.dying = dying;
+        self.state.edits += 1;
+        self.replaced();
+        Ok(())
+    }
+
+    /// the board in the native format: `W:H`, then a line of 0 and 1 per row, and dying states.
+    pub fn to_native(&self) -> String {
+        native(self.state.size, &self.states())
+    }
+
+    /// the whole board with its metadata, as read back by `--file`.
+    pub fn to_text(&self) -> String {
+        format!("{}{}\n", self.metadata(), self.to_native())
+    }
+
+    /// cells which differ from `base`, one `x:y` per line, for a file in `dir`. the base path is
+    /// written relative to `dir`, where it is read from.
+    pub fn to_delta(&self, dir: &Path, base_path: &Path, base: &[bool]) -> String {
+        let width = self.state.size.width as usize;
+        let dir = if dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            dir
+        };
+        let base_path = match (base_path.canonicalize(), dir.canonicalize()) {
+            (Ok(base_path), Ok(dir)) => relative_to(&base_path, &dir),
+            _ => base_path.to_path_buf(),
+        };
+        let mut data = format!(
+            "#delta base={}\n{}:{}\n",
+            base_path.display(),
+            self.state.size.width,
+            self.state.size.height
+        );
+        for (i, _) in self
+            .game
+            .iter()
+            .zip(base)
+            .enumerate()
+            .filter(|(_, (a, b))| a != b)
+        {
+            data.push_str(&format!("{}:{}\n", i % width, i / width));
+        }
+        data
+    }
+
+    /// default name of a save: the current time.
+    pub fn save_name() -> String {
+        Local::now().format("%F_%H.%M.%ST%z.txt").to_string()
+    }
+
+    /// how the board was produced, as written into saves.
+    pub fn metadata(&self) -> Metadata {
+        Metadata {
+            rule: Some(self.state.rule),
+            generation: Some(self.state.time),
+            seed: self.state.seed,
+            name: self.state.name.clone(),
+            ..Metadata::default()
+        }
+    }
+
+    /// write the board to `path`, as rle if it ends with `.rle`. an empty board is saved whole
+    /// even with `trim`, and so is a delta, which has the size of its base.
+    pub fn save_to(&mut self, path: &Path) -> Result<String> {
+        let (size, states) = match self.state.trim {
+            Some(padding) if self.bounding_box().is_some() => self.trimmed(padding)?,
+            _ => (self.state.size, self.states()),
+        };
+        let data = if path.extension().is_some_and(|e| e == "rle") {
+            rle::encode(size, &states, &self.state.rule)
+        } else {
+            let board = match &self.state.delta_base {
+                Some((base_path, base)) => {
+                    self.to_delta(path.parent().unwrap_or(Path::new(".")), base_path, base)
+                }
+                None => native(size, &states),
+            };
+            format!("{}{}", self.metadata(), board)
+        };
+        write(path, data)?;
+
+        Ok(format!("success save to {}", path.display()))
+    }
+
+    /// replace the board with a built-in pattern or an apgcode, or a file with its size and
+    /// metadata.
+    pub fn load(&mut self, what: &str) -> Result<()> {
+        self.state.history.clear();
+        if let Ok(pattern) = what.parse::<Pattern>() {
+            self.game = pattern.centered(self.state.size)?;
+            self.dying = vec![0; self.state.len];
+            self.state.apply_metadata(Metadata::default());
+        } else if let Ok((_, object)) = apgcode::decode(what) {
+            self.game = object.centered(self.state.size)?;
+            self.dying = vec![0; self.state.len];
+            self.state.apply_metadata(Metadata::default());
+        } else {
+            let board = formats::load(Path::new(what), None)?;
+            self.state.size = board.size;
+            self.state.len = board.cells.len();
+            self.game = board.cells;
+            self.dying = board.dying;
+            self.state.apply_metadata(board.meta);
+        }
+        self.replaced();
+        self.record_population(0, 0);
+        Ok(())
+    }
+
+    /// a copy of the board to `restore` later.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            size: self.state.size,
+            time: self.state.time,
+            game: self.game.clone(),
+            dying: self.dying.clone(),
+        }
+    }
+
+    /// go back to `checkpoint`, dropping the population history recorded after it.
+    pub fn restore(&mut self, checkpoint: &Checkpoint) {
+        if checkpoint.size != self.state.size {
+            self.state.size = checkpoint.size;
+            self.state.len = checkpoint.game.len();
+        }
+        self.game = checkpoint.game.clone();
+        self.dying = checkpoint.dying.clone();
+        self.state.time = checkpoint.time;
+        self.state.edits += 1;
+        self.state
+            .history
+            .retain(|r| r.generation < checkpoint.time);
+        self.replaced();
+        self.record_population(0, 0);
+    }
+
+    /// catch up with a board replaced as a whole.
+    pub fn replaced(&mut self) {
+        if self
+            .state
+            .delta_base
+            .as_ref()
+            .is_some_and(|(_, base)| base.len() != self.state.len)
+        {
+            self.state.delta_base = None;
+        }
+        self.recount();
+        if self.state.stats.is_some() {
+            self.reset_stats();
+        }
+    }
+
+    /// write the board if `state.dump` asks for this generation.
+    pub fn dump_frame(&self) -> Result<()> {
+        let Some(dump) = &self.state.dump else {
+            return Ok(());
+        };
+        if !(self.state.time as u64).is_multiple_of(dump.every) {
+            return Ok(());
+        }
+        let path = dump.dir.join(format!(
+            "{:08}.{}",
+            self.state.time,
+            dump.format.extension()
+        ));
+        let data = match dump.format {
+            DumpFormat::Native => self.to_native(),
+            DumpFormat::Rle => rle::encode(self.state.size, &self.states(), &self.state.rule),
+            DumpFormat::Png => return render::save(self, &path).map(drop),
+        };
+        write(path, data)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs::create_dir_all;
+
+    #[test]
+    fn test_of_test() -> Result<()> {
+        let mut game = Game::blank("160:32".parse()?)?;
+        println!("{}", game);
+        game.set_pos((15, 20))?;
+        println!("{}", game);
+        Ok(())
+    }
+
+    macro_rules! inner {
+        ( $v:ident) => {};
+        ( $v:ident, $e:literal) => {{$v.push($e != 0);}};
+        ( $v:ident, $e:literal, $($a:literal),* ) => {{
+            $v.push($e != 0);
+            inner!($v, $($a),* );
+        }};
+    }
+
+    macro_rules! board_init {
+        ($($e:literal),*) => {{
+            let mut v = Vec::new();
+            inner!(v, $($e),*);
+            v
+        }};
+    }
+
+    #[test]
+    fn blinker_test() -> Result<()> {
+        let mut game = Game::blank("5:5".parse()?)?;
+        game.set_pos((1, 2))?;
+        game.set_pos((2, 2))?;
+        game.set_pos((3, 2))?;
+        println!("{}", game);
+        game.step()?;
+        println!("{}", game);
+        assert_eq!(game.state.population, 3);
+        game.step()?;
+        println!("{}", game);
+        assert_eq!(game.state.population, 3);
+        assert_eq!(
+            game.state.history[1],
+            PopulationRecord {
+                generation: 1,
+                population: 3,
+                births: 2,
+                deaths: 2
+            }
+        );
+        assert_eq!(
+            population_csv(&game.state.history),
+            "generation,population,births,deaths\n0,3,0,0\n1,3,2,2\n2,3,2,2\n"
+        );
+        assert_eq!(
+            game.game,
+            board_init!(0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn skip_ahead() -> Result<()> {
+        let mut game = Game::blank("5:5".parse()?)?;
+        for pos in [(1, 2), (2, 2), (3, 2)] {
+            game.set_pos(pos)?;
+        }
+        assert_eq!(game.skip(101)?, 101);
+        assert_eq!(game.state.time, 101);
+        assert!(game.game[game.index((2, 1))?]);
+        game.set_pos((2, 1))?;
+        // two cells die out on the first step.
+        assert_eq!(game.skip(10)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn iterate_generations() -> Result<()> {
+        let size = "6:6".parse()?;
+        let mut game = Game::from_board(size, "glider".parse::<Pattern>()?.centered(size)?)?;
+        let start = game.checkpoint();
+        let times: Vec<_> = game
+            .generations()
+            .take(4)
+            .map(|g| g.map(|g| (g.time, g.game.iter().filter(|&&v| v).count())))
+            .collect::<Result<_>>()?;
+        assert_eq!(times, [(1, 5), (2, 5), (3, 5), (4, 5)]);
+        // a glider moves one cell down and right every 4 generations.
+        let width = size.width as usize;
+        let moved: Vec<_> = (0..start.game.len())
+            .map(|i| start.game[(i + start.game.len() - width - 1) % start.game.len()])
+            .collect();
+        assert_eq!(game.game, moved);
+        assert_eq!(game.state.time, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn restore_checkpoint() -> Result<()> {
+        let mut game = Game::blank("5:5".parse()?)?;
+        for pos in [(1, 2), (2, 2), (3, 2)] {
+            game.set_pos(pos)?;
+        }
+        game.step()?;
+        let checkpoint = game.checkpoint();
+        game.skip(3)?;
+        game.load("glider")?;
+        game.restore(&checkpoint);
+        assert_eq!(game.state.time, 1);
+        assert_eq!(game.state.population, 3);
+        assert!(game.game[game.index((2, 1))?]);
+        assert!(game.state.history.iter().all(|r| r.generation <= 1));
+        Ok(())
+    }
+
+    #[test]
+    fn load_an_apgcode() -> Result<()> {
+        let mut game = Game::blank("6:6".parse()?)?;
+        game.load("xp2_318c")?;
+        assert_eq!(game.state.population, 6);
+        assert!(game.game[game.index((1, 1))?] && game.game[game.index((4, 4))?]);
+        assert_eq!(Game::clip_of("xq4_153")?.cells.len(), 9);
+        assert!(game.load("xs5_33").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn oscillator_period() -> Result<()> {
+        let mut game = Game::blank("6:6".parse()?)?;
+        for pos in [(1, 1), (2, 1), (1, 2), (4, 3), (3, 4), (4, 4)] {
+            game.set_pos(pos)?;
+        }
+        // beacon
+        game.step()?;
+        assert_eq!(game.state.cycle, None);
+        game.step()?;
+        assert_eq!(game.state.cycle, Some((0, 2)));
+        game.set_pos((0, 5))?;
+        assert_eq!(game.state.cycle, None);
+        Ok(())
+    }
+
+    #[test]
+    fn copy_and_paste() -> Result<()> {
+        let mut game = Game::blank("4:3".parse()?)?;
+        game.set_pos((0, 0))?;
+        game.set_pos((1, 1))?;
+        let clip = game.copy_rect((1, 1), (0, 0))?;
+        assert_eq!(clip.cells, [true, false, false, true]);
+        game.fill_rect((0, 0), (1, 1), 0)?;
+        assert!(game.game.iter().all(|&v| !v));
+        // wraps around the right and bottom edges.
+        game.paste((3, 2), &clip)?;
+        assert!(game.game[game.index((3, 2))?]);
+        assert!(game.game[game.index((0, 0))?]);
+        assert_eq!(game.game.iter().filter(|&&v| v).count(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn generations_rule() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        let mut game = Game::blank("4:3".parse()?)?;
+        game.state.rule = "345/2/4".parse()?;
+        assert_eq!(game.state_names(), ["dead", "alive", "dying 1", "dying 2"]);
+        game.set_pos((0, 0))?;
+        game.step()?;
+        // a lone cell doesn't survive, and fades out over two generations.
+        assert_eq!((game.state_at(0), game.state.population), (2, 0));
+        assert_eq!(game.show_board(), "o---\n----\n----\n");
+        game.set_state((3, 2), 3)?;
+        assert!(game.set_state((3, 2), 4).is_err());
+        assert_eq!(game.to_native(), "4:3\n2000\n0000\n0003");
+
+        // saves keep the dying cells.
+        for name in ["gens.txt", "gens.rle"] {
+            game.save_to(&dir.join(name))?;
+            let mut loaded = Game::blank("1:1".parse()?)?;
+            loaded.load(dir.join(name).to_str().unwrap_or_default())?;
+            assert_eq!(loaded.states(), game.states());
+            assert_eq!(loaded.state.rule, game.state.rule);
+        }
+        game.step()?;
+        assert_eq!(game.states(), [3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        Ok(())
+    }
+
+    #[test]
+    fn quadlife_colours() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        let mut game = Game::blank("4:4".parse()?)?;
+        game.state.rule = Rule::quadlife();
+        assert_eq!(game.state_names()[4], "colour 4");
+        // a block of four colours.
+        for (pos, colour) in [((1, 1), 1), ((2, 1), 2), ((1, 2), 3), ((2, 2), 4)] {
+            game.set_state(pos, colour)?;
+        }
+        assert_eq!(game.state.population, 4);
+        assert_eq!(game.show_board(), "----\n-@@-\n-@@-\n----\n");
+        game.step()?;
+        assert_eq!(game.to_native(), "4:4\n0000\n0120\n0340\n0000");
+        for name in ["quad.txt", "quad.rle"] {
+            game.save_to(&dir.join(name))?;
+            let mut loaded = Game::blank("1:1".parse()?)?;
+            loaded.load(dir.join(name).to_str().unwrap_or_default())?;
+            assert_eq!(loaded.states(), game.states());
+            assert_eq!(loaded.state.population, 4);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn random_soup() -> Result<()> {
+        let mut game = Game::blank("8:6".parse()?)?;
+        game.state.soup = Some("4:2".parse()?);
+        game.state.symmetry = Symmetry::C2;
+        game.random_from(1)?;
+        let live: Vec<(usize, usize)> = (0..game.state.len)
+            .filter(|&i| game.game[i])
+            .map(|i| (i % 8, i / 8))
+            .collect();
+        assert!(!live.is_empty());
+        assert!(live
+            .iter()
+            .all(|&(x, y)| (2..6).contains(&x) && (2..4).contains(&y)));
+        // a half turn of the board gives it back.
+        assert!(live.iter().all(|&(x, y)| game.game[(5 - y) * 8 + 7 - x]));
+        game.state.soup = Some("9:1".parse()?);
+        assert!(game.random_from(1).is_err());
+        // a soup or a board without cells has nothing to fill.
+        game.state.soup = Some("0:3".parse()?);
+        assert!(game.random_from(1).is_err());
+        assert!(Game::blank("0:4".parse()?)?.random_from(1).is_err());
+
+        // a new random board starts over from generation 0, with its seed recorded.
+        game.state.soup = None;
+        game.step()?;
+        let seed = game.random()?;
+        assert_eq!(game.state.seed, Some(seed));
+        assert_eq!((game.state.time, game.state.history.len()), (0, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn switch_rule() -> Result<()> {
+        let mut game = Game::blank("4:1".parse()?)?;
+        game.state.rule = Rule::quadlife();
+        for (x, colour) in [(0, 1), (1, 2), (2, 4)] {
+            game.set_state((x, 0), colour)?;
+        }
+        // colours are kept, or lost to plain live cells.
+        game.set_rule(Rule::immigration());
+        assert_eq!(game.states(), [1, 2, 1, 0]);
+        game.set_rule(Rule::default());
+        assert_eq!(game.states(), [1, 1, 1, 0]);
+        assert_eq!(game.state.population, 3);
+        // dying cells too many for the new rule die.
+        game.set_rule("B2/S/C4".parse()?);
+        game.set_state((1, 0), 3)?;
+        game.set_rule(Rule::brians_brain());
+        assert_eq!(game.states(), [1, 0, 1, 0]);
+        assert_eq!(game.state.population, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn count_a_rectangle() -> Result<()> {
+        let mut game = Game::from_board(
+            "6:4".parse()?,
+            "glider".parse::<Pattern>()?.centered("6:4".parse()?)?,
+        )?;
+        assert_eq!(game.live_in((0, 0), (5, 3)), (5, Some(((1, 0), (3, 2)))));
+        assert_eq!(game.live_in((3, 3), (2, 1)), (3, Some(((2, 1), (3, 2)))));
+        game.clear();
+        assert_eq!(game.live_in((0, 0), (5, 3)), (0, None));
+        Ok(())
+    }
+
+    #[test]
+    fn fill_randomly() -> Result<()> {
+        let mut game = Game::blank("6:4".parse()?)?;
+        let mut rng = StdRng::seed_from_u64(0);
+        game.fill_random((4, 2), (1, 1), 1.0, &mut rng)?;
+        assert_eq!(game.show_board(), "------\n-@@@@-\n-@@@@-\n------\n");
+        game.fill_random((0, 0), (5, 3), 0.5, &mut rng)?;
+        assert!((1..24).contains(&game.state.population));
+        game.fill_random((0, 0), (5, 3), 0.0, &mut rng)?;
+        assert_eq!(game.state.population, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn transform_board() -> Result<()> {
+        let mut game = Game::blank("3:2".parse()?)?;
+        game.set_pos((0, 0))?;
+        game.set_state((1, 0), 1)?;
+        game.state.rule = "B2/S/C3".parse()?;
+        game.set_state((2, 1), 2)?;
+        game.transform(Transform::Shift(-1, 3));
+        assert_eq!(game.to_native(), "3:2\n020\n101");
+        game.transform(Transform::Rotate);
+        assert_eq!(game.to_native(), "2:3\n10\n02\n10");
+        game.transform(Transform::FlipHorizontal);
+        assert_eq!(game.to_native(), "2:3\n01\n20\n01");
+        game.transform(Transform::FlipVertical);
+        game.transform(Transform::Shift(0, 1));
+        assert_eq!(game.to_native(), "2:3\n01\n01\n20");
+        assert_eq!(game.state.population, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn stamp_pattern() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        let path = dir.join("domino.cells");
+        std::fs::write(&path, "....\n.OO.\n")?;
+        let mut game = Game::blank("5:4".parse()?)?;
+        let size = game.stamp(path.to_str().unwrap(), (0, 0), Anchor::TopLeft, false)?;
+        assert_eq!((size.width, size.height), (2, 1));
+        game.stamp("blinker", (2, 2), Anchor::Center, false)?;
+        assert_eq!(game.show_board(), "@@---\n-----\n-@@@-\n-----\n");
+        assert!(game
+            .stamp("blinker", (4, 0), Anchor::TopLeft, false)
+            .is_err());
+        game.stamp("blinker", (4, 0), Anchor::TopLeft, true)?;
+        assert_eq!(game.show_board(), "@@--@\n-----\n-@@@-\n-----\n");
+        Ok(())
+    }
+
+    #[test]
+    fn track_edits() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        let mut game = Game::blank("6:5".parse()?)?;
+        game.step()?;
+        assert_eq!(game.state.edits, 0);
+        game.set_pos((2, 1))?;
+        assert_eq!(game.state.edits, 1);
+        // saving and loading are no edits.
+        game.save_to(&dir.join("edited.txt"))?;
+        game.load("glider")?;
+        assert_eq!(game.state.edits, 1);
+        game.transform(Transform::Rotate);
+        game.clear();
+        game.random_from(1)?;
+        let checkpoint = game.checkpoint();
+        game.restore(&checkpoint);
+        assert_eq!(game.state.edits, 5);
+        Ok(())
+    }
+
+    #[test]
+    fn trim_board() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        let mut game = Game::blank("6:5".parse()?)?;
+        assert!(game.trimmed(0).is_err());
+        // an empty board has nothing to cut down to, but can be saved.
+        game.state.trim = Some(0);
+        game.save_to(&dir.join("empty.txt"))?;
+        assert!(std::fs::read_to_string(dir.join("empty.txt"))?
+            .ends_with("6:5\n000000\n000000\n000000\n000000\n000000"));
+        game.set_pos((2, 1))?;
+        game.set_pos((3, 3))?;
+        assert_eq!(game.bounding_box(), Some(((2, 1), (3, 3))));
+        let (size, states) = game.trimmed(3)?;
+        assert_eq!((size.width, size.height, states.len()), (8, 9, 72));
+        let path = dir.join("trimmed.txt");
+        game.save_to(&path)?;
+        assert!(std::fs::read_to_string(&path)?.ends_with("2:3\n10\n00\n01"));
+        // the board itself is only cut by trim.
+        assert_eq!(game.state.len, 30);
+        game.trim(1)?;
+        assert_eq!(game.to_native(), "4:5\n0000\n0100\n0000\n0010\n0000");
+        Ok(())
+    }
+
+    #[test]
+    fn bounds_and_center() -> Result<()> {
+        let mut game = Game::blank("6:5".parse()?)?;
+        assert_eq!(game.center_of_mass(), None);
+        for pos in [(1, 1), (3, 1), (2, 4)] {
+            game.set_pos(pos)?;
+        }
+        assert_eq!(game.center_of_mass(), Some((2.0, 2.0)));
+        assert_eq!(game.bounding_box(), Some(((1, 1), (3, 4))));
+        Ok(())
+    }
+
+    #[test]
+    fn resize_board() -> Result<()> {
+        let mut game = Game::blank("3:2".parse()?)?;
+        game.set_pos((0, 0))?;
+        game.set_pos((2, 1))?;
+        game.resize("5:4".parse()?, Anchor::Center)?;
+        assert_eq!(game.show_board(), "-----\n-@---\n---@-\n-----\n");
+        assert_eq!((game.state.len, game.dying.len()), (20, 20));
+        game.resize("4:3".parse()?, Anchor::TopLeft)?;
+        assert_eq!(game.show_board(), "----\n-@--\n---@\n");
+        game.resize("2:2".parse()?, Anchor::TopLeft)?;
+        assert_eq!(game.show_board(), "--\n-@\n");
+        assert_eq!(game.state.population, 1);
+        assert!(game.resize("0:2".parse()?, Anchor::Center).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn crop_board() -> Result<()> {
+        let mut game = Game::blank("5:5".parse()?)?;
+        game.set_pos((2, 2))?;
+        game.crop(Size {
+            width: 3,
+            height: 1,
+        })?;
+        assert_eq!(game.show_board(), "-@-\n");
+        Ok(())
+    }
+
+    #[test]
+    fn delta_round_trip() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        let mut game = Game::blank("4:3".parse()?)?;
+        game.set_pos((1, 1))?;
+        write(dir.join("base.txt"), game.to_native())?;
+
+        let base = game.game.clone();
+        game.set_pos((1, 1))?;
+        game.set_pos((3, 2))?;
+        let delta = game.to_delta(dir, &dir.join("base.txt"), &base);
+        assert_eq!(delta, "#delta base=base.txt\n4:3\n1:1\n3:2\n");
+        write(dir.join("delta.txt"), delta)?;
+
+        let loaded = formats::load(&dir.join("delta.txt"), None)?;
+        assert_eq!((loaded.size.width, loaded.size.height), (4, 3));
+        assert_eq!(loaded.cells, game.game);
+
+        // saved elsewhere, the base is found from there.
+        create_dir_all(dir.join("saves"))?;
+        game.state.delta_base = Some((dir.join("saves/../base.txt"), base));
+        game.save_to(&dir.join("saves/delta.txt"))?;
+        let saved = std::fs::read_to_string(dir.join("saves/delta.txt"))?;
+        assert!(saved.contains("#delta base=../base.txt\n"));
+        let loaded = formats::load(&dir.join("saves/delta.txt"), None)?;
+        assert_eq!(loaded.cells, game.game);
+        Ok(())
+    }
+
+    #[test]
+    fn hex_board() -> Result<()> {
+        let mut game = Game::blank("3:2".parse()?)?;
+        game.state.rule = "B2/S34H".parse()?;
+        game.set_pos((0, 1))?;
+        assert_eq!(game.show_board(), "- - -\n @ - -\n");
+        Ok(())
+    }
+
+    #[test]
+    fn zoomed_board() -> Result<()> {
+        let mut game = Game::blank("3:1".parse()?)?;
+        game.set_pos((0, 0))?;
+        let glyphs = Glyphs::default();
+        let show = |xs, zoom, highlight: (u16, u16)| {
+            game.show_cells(&glyphs, (xs, 0..1), zoom, |p| p == highlight)
+        };
+        assert_eq!(show(0..2, 2, (1, 0)), "@@++\n@@++\n");
+        assert_eq!(show(0..2, 1, (0, 0)), "#-\n");
+        assert_eq!(show(1..3, 1, (0, 0)), "--\n");
+        Ok(())
+    }
+}
@@ -0,0 +1,470 @@
+//! boards made up instead of read: random ones, their symmetries, and [`Generator`]s.
+
+use crate::Size;
+use anyhow::{anyhow, bail, ensure, Error, Result};
+use clap::ValueEnum;
+use rand::{seq::SliceRandom, thread_rng, Rng};
+use std::{f64::consts::TAU, str::FromStr};
+
+/// how random boards are filled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum RandomKind {
+    /// every cell is alive or dead by a coin toss.
+    #[default]
+    Uniform,
+    /// blobs of mostly live cells on a dead board.
+    Clusters,
+    /// smooth perlin noise, which gives large live and dead areas.
+    Perlin,
+}
+
+/// cells per cluster of a clustered board, on average.
+const CLUSTER_AREA: usize = 64;
+/// cells between the lattice points of perlin noise.
+const PERLIN_SCALE: f64 = 8.0;
+
+impl RandomKind {
+    /// a random board of `size` in rows.
+    pub fn fill(self, rng: &mut impl Rng, size: Size) -> Vec<bool> {
+        let (width, height) = (size.width as usize, size.height as usize);
+        let mut cells = vec![false; width * height];
+        match self {
+            Self::Uniform => rng.fill(&mut cells[..]),
+            Self::Clusters => {
+                for _ in 0..(cells.len() / CLUSTER_AREA).max(1) {
+                    let (cx, cy) = (rng.gen_range(0..width), rng.gen_range(0..height));
+                    let radius: usize = rng.gen_range(2..=5);
+                    for y in cy.saturating_sub(radius)..(cy + radius + 1).min(height) {
+                        for x in cx.saturating_sub(radius)..(cx + radius + 1).min(width) {
+                            if x.abs_diff(cx).pow(2) + y.abs_diff(cy).pow(2) <= radius * radius
+                                && rng.gen_bool(0.7)
+                            {
+                                cells[y * width + x] = true;
+                            }
+                        }
+                    }
+                }
+            }
+            Self::Perlin => {
+                // a random gradient at every lattice point, one more than the board needs.
+                let columns = (width as f64 / PERLIN_SCALE) as usize + 2;
+                let rows = (height as f64 / PERLIN_SCALE) as usize + 2;
+                let gradients: Vec<(f64, f64)> = (0..columns * rows)
+                    .map(|_| {
+                        let angle = rng.gen_range(0.0..TAU);
+                        (angle.cos(), angle.sin())
+                    })
+                    .collect();
+                let fade = |t: f64| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+                let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+                for (i, cell) in cells.iter_mut().enumerate() {
+                    // the middle of the cell, as the noise is 0 on lattice points.
+                    let x = ((i % width) as f64 + 0.5) / PERLIN_SCALE;
+                    let y = ((i / width) as f64 + 0.5) / PERLIN_SCALE;
+                    let (x0, y0) = (x.floor(), y.floor());
+                    let dot = |gx: f64, gy: f64| {
+                        let (gx0, gy0) = (gx as usize, gy as usize);
+                        let (dx, dy) = gradients[gy0 * columns + gx0];
+                        dx * (x - gx) + dy * (y - gy)
+                    };
+                    let (u, v) = (fade(x - x0), fade(y - y0));
+                    let top = lerp(dot(x0, y0), dot(x0 + 1.0, y0), u);
+                    let bottom = lerp(dot(x0, y0 + 1.0), dot(x0 + 1.0, y0 + 1.0), u);
+                    *cell = lerp(top, bottom, v) > 0.0;
+                }
+            }
+        }
+        cells
+    }
+}
+
+/// symmetry of random soups, named as in Golly and apgsearch. `C` are rotations by 360°/N
+/// around the middle of the board, and `D` add mirror images.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Symmetry {
+    /// no symmetry.
+    #[default]
+    C1,
+    /// half turns.
+    C2,
+    /// quarter turns. needs a square board.
+    C4,
+    /// mirrored left to right.
+    D2,
+    /// mirrored left to right and top to bottom.
+    D4,
+    /// mirrored along both axes and both diagonals. needs a square board.
+    D8,
+}
+
+impl Symmetry {
+    /// make `cells`, a board of `size` in rows, symmetric: every cell takes the value of the
+    /// first cell its images hit.
+    pub fn apply<T: Copy>(self, size: Size, cells: &mut [T]) -> Result<()> {
+        let (w, h) = (size.width as usize, size.height as usize);
+        ensure!(
+            w == h || !matches!(self, Self::C4 | Self::D8),
+            "{:?} symmetry needs a square board, not {}.",
+            self,
+            size
+        );
+        let (mx, my) = (|x: usize| w - 1 - x, |y: usize| h - 1 - y);
+        for y in 0..h {
+            for x in 0..w {
+                let images: &[(usize, usize)] = match self {
+                    Self::C1 => &[],
+                    Self::C2 => &[(mx(x), my(y))],
+                    Self::C4 => &[(mx(x), my(y)), (mx(y), x), (y, my(x))],
+                    Self::D2 => &[(mx(x), y)],
+                    Self::D4 => &[(mx(x), y), (x, my(y)), (mx(x), my(y))],
+                    Self::D8 => &[
+                        (mx(x), y),
+                        (x, my(y)),
+                        (mx(x), my(y)),
+                        (y, x),
+                        (mx(y), x),
+                        (y, my(x)),
+                        (mx(y), my(x)),
+                    ],
+                };
+                // the first image is already final, as it is its own first image.
+                if let Some(&(ix, iy)) = images.iter().min_by_key(|&&(ix, iy)| (iy, ix)) {
+                    if (iy, ix) < (y, x) {
+                        cells[y * w + x] = cells[iy * w + ix];
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 3x5 bitmap font. each row is 3 bits, MSB is the left column.
+const FONT: &[(char, [u8; 5])] = &[
+    ('A', [2, 5, 7, 5, 5]),
+    ('B', [6, 5, 6, 5, 6]),
+    ('C', [3, 4, 4, 4, 3]),
+    ('D', [6, 5, 5, 5, 6]),
+    ('E', [7, 4, 6, 4, 7]),
+    ('F', [7, 4, 6, 4, 4]),
+    ('G', [3, 4, 5, 5, 3]),
+    ('H', [5, 5, 7, 5, 5]),
+    ('I', [7, 2, 2, 2, 7]),
+    ('J', [1, 1, 1, 5, 2]),
+    ('K', [5, 5, 6, 5, 5]),
+    ('L', [4, 4, 4, 4, 7]),
+    ('M', [5, 7, 7, 5, 5]),
+    ('N', [6, 5, 5, 5, 5]),
+    ('O', [2, 5, 5, 5, 2]),
+    ('P', [6, 5, 6, 4, 4]),
+    ('Q', [2, 5, 5, 6, 3]),
+    ('R', [6, 5, 6, 5, 5]),
+    ('S', [3, 4, 2, 1, 6]),
+    ('T', [7, 2, 2, 2, 2]),
+    ('U', [5, 5, 5, 5, 7]),
+    ('V', [5, 5, 5, 5, 2]),
+    ('W', [5, 5, 7, 7, 5]),
+    ('X', [5, 5, 2, 5, 5]),
+    ('Y', [5, 5, 2, 2, 2]),
+    ('Z', [7, 1, 2, 4, 7]),
+    ('0', [7, 5, 5, 5, 7]),
+    ('1', [2, 6, 2, 2, 7]),
+    ('2', [6, 1, 2, 4, 7]),
+    ('3', [6, 1, 2, 1, 6]),
+    ('4', [5, 5, 7, 1, 1]),
+    ('5', [7, 4, 6, 1, 6]),
+    ('6', [3, 4, 6, 5, 2]),
+    ('7', [7, 1, 2, 2, 2]),
+    ('8', [2, 5, 2, 5, 2]),
+    ('9', [2, 5, 3, 1, 6]),
+    (' ', [0, 0, 0, 0, 0]),
+    ('!', [2, 2, 2, 0, 2]),
+    ('?', [6, 1, 2, 0, 2]),
+    ('.', [0, 0, 0, 0, 2]),
+    ('-', [0, 0, 7, 0, 0]),
+];
+const GLYPH_WIDTH: usize = 3;
+const GLYPH_HEIGHT: usize = 5;
+
+fn glyph(c: char) -> Result<&'static [u8; 5]> {
+    let c = c.to_ascii_uppercase();
+    FONT.iter()
+        .find(|(k, _)| *k == c)
+        .map(|(_, g)| g)
+        .ok_or_else(|| anyhow!("character {:?} is not in the built-in font.", c))
+}
+
+/// boards drawn by a rule instead of read from a file, picked with `--generate`.
+#[derive(Debug, Clone)]
+pub enum Generator {
+    /// a random maze, with live walls and dead passages.
+    Maze,
+    /// horizontal stripes this many rows high, with gaps as high.
+    Stripes(u16),
+    /// squares of this size, alive and dead in turn.
+    Checkerboard(u16),
+    /// rings around the middle of the board this far apart.
+    Rings(u16),
+    /// the text written in the built-in font.
+    Text(String),
+}
+
+impl FromStr for Generator {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (kind, arg) = match s.split_once(':') {
+            Some((kind, arg)) => (kind, Some(arg.trim_matches('"'))),
+            None => (s, None),
+        };
+        let step = |name: &str, default: u16| -> Result<u16> {
+            let v = arg.map_or(Ok(default), |a| a.parse())?;
+            ensure!(v > 0, "{} must be bigger than 0.", name);
+            Ok(v)
+        };
+        Ok(match kind {
+            "maze" => Self::Maze,
+            "stripes" => Self::Stripes(step("stripe width", 1)?),
+            "checkerboard" => Self::Checkerboard(step("square size", 1)?),
+            "rings" => Self::Rings(step("ring spacing", 3)?),
+            "text" => Self::Text(
+                arg.filter(|a| !a.is_empty())
+                    .ok_or_else(|| anyhow!(r#"note:: you must use a "text:<word>" format."#))?
+                    .to_string(),
+            ),
+            _ => bail!(concat!(
+                "Unknown generator!.",
+                "note:: available: maze, stripes[:N], checkerboard[:N], rings[:N], text:<word>."
+            )),
+        })
+    }
+}
+
+impl Generator {
+    /// the board of `size` drawn, in rows.
+    pub fn generate(&self, size: Size) -> Result<Vec<bool>> {
+        let (width, height) = (size.width as usize, size.height as usize);
+        let cell = |f: &dyn Fn(usize, usize) -> bool| -> Vec<bool> {
+            (0..height)
+                .flat_map(|y| (0..width).map(move |x| (x, y)))
+                .map(|(x, y)| f(x, y))
+                .collect()
+        };
+        Ok(match self {
+            Self::Maze => maze(width, height),
+            Self::Stripes(n) => cell(&|_, y| (y / *n as usize).is_multiple_of(2)),
+            Self::Checkerboard(n) => {
+                cell(&|x, y| (x / *n as usize + y / *n as usize).is_multiple_of(2))
+            }
+            Self::Rings(n) => {
+                let (cx, cy) = (width as f64 / 2.0, height as f64 / 2.0);
+                cell(&|x, y| {
+                    let d = (x as f64 - cx).hypot(y as f64 - cy).round() as usize;
+                    d.is_multiple_of(*n as usize)
+                })
+            }
+            Self::Text(s) => text(s, width, height)?,
+        })
+    }
+}
+
+/// walls are alive, passages are dead. passages lie on odd coordinates.
+fn maze(width: usize, height: usize) -> Vec<bool> {
+    let mut board = vec![true; width * height];
+    let (cols, rows) = (width.saturating_sub(1) / 2, height.saturating_sub(1) / 2);
+    if cols == 0 || rows == 0 {
+        return board;
+    }
+    let idx = |c: usize, r: usize| (2 * r + 1) * width + 2 * c + 1;
+
+    let mut rng = thread_rng();
+    let mut visited = vec![false; cols * rows];
+    let mut stack = vec![(0usize, 0usize)];
+    visited[0] = true;
+    board[idx(0, 0)] = false;
+    while let Some(&(c, r)) = stack.last() {
+        let mut next: Vec<(usize, usize)> = [
+            (c.wrapping_sub(1), r),
+            (c + 1, r),
+            (c, r.wrapping_sub(1)),
+            (c, r + 1),
+        ]
+        .into_iter()
+        .filter(|&(nc, nr)| nc < cols && nr < rows && !visited[nr * cols + nc])
+        .collect();
+        next.shuffle(&mut rng);
+        match next.first() {
+            Some(&(nc, nr)) => {
+                visited[nr * cols + nc] = true;
+                // open the wall between the two cells, then the cell itself.
+                board[(idx(c, r) + idx(nc, nr)) / 2] = false;
+                board[idx(nc, nr)] = false;
+                stack.push((nc, nr));
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+    board
+}
+
+fn text(s: &str, width: usize, height: usize) -> Result<Vec<bool>> {
+    let glyphs = s.chars().map(glyph).collect::<Result<Vec<_>>>()?;
+    let text_width = glyphs.len() * (GLYPH_WIDTH + 1) - 1;
+    ensure!(
+        text_width <= width && GLYPH_HEIGHT <= height,
+        "text needs {}x{} cells, but board is {}x{}.",
+        text_width,
+        GLYPH_HEIGHT,
+        width,
+        height
+    );
+
+    let mut board = vec![false; width * height];
+    let (ox, oy) = ((width - text_width) / 2, (height - GLYPH_HEIGHT) / 2);
+    for (i, g) in glyphs.iter().enumerate() {
+        for (dy, row) in g.iter().enumerate() {
+            for dx in 0..GLYPH_WIDTH {
+                if row >> (GLYPH_WIDTH - 1 - dx) & 1 == 1 {
+                    let x = ox + i * (GLYPH_WIDTH + 1) + dx;
+                    board[(oy + dy) * width + x] = true;
+                }
+            }
+        }
+    }
+    Ok(board)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn text_generator() -> Result<()> {
+        let gen: Generator = r#"text:"HI""#.parse()?;
+        let board = gen.generate(Size {
+            width: 7,
+            height: 5,
+        })?;
+        let rows: Vec<String> = board
+            .chunks(7)
+            .map(|r| r.iter().map(|&v| if v { '@' } else { '-' }).collect())
+            .collect();
+        assert_eq!(
+            rows,
+            ["@-@-@@@", "@-@--@-", "@@@--@-", "@-@--@-", "@-@-@@@"]
+        );
+        assert!(gen
+            .generate(Size {
+                width: 6,
+                height: 5
+            })
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn symmetric_soups() -> Result<()> {
+        let size = Size {
+            width: 4,
+            height: 4,
+        };
+        let soup: Vec<u8> = (0..16).collect();
+        let symmetric = |symmetry: Symmetry| -> Result<Vec<u8>> {
+            let mut cells = soup.clone();
+            symmetry.apply(size, &mut cells)?;
+            Ok(cells)
+        };
+        assert_eq!(symmetric(Symmetry::C1)?, soup);
+        #[rustfmt::skip]
+        let expected = [
+            0, 1, 2, 3,
+            4, 5, 6, 7,
+            7, 6, 5, 4,
+            3, 2, 1, 0,
+        ];
+        assert_eq!(symmetric(Symmetry::C2)?, expected);
+        #[rustfmt::skip]
+        let expected = [
+            0, 1, 1, 0,
+            1, 5, 5, 1,
+            1, 5, 5, 1,
+            0, 1, 1, 0,
+        ];
+        assert_eq!(symmetric(Symmetry::D8)?, expected);
+        // every quarter turn of the board gives it back.
+        let c4 = symmetric(Symmetry::C4)?;
+        assert!((0..16).all(|i| c4[i] == c4[(3 - i % 4) * 4 + i / 4]));
+        let mut wide = vec![0; 8];
+        assert!(Symmetry::C4
+            .apply(
+                Size {
+                    width: 4,
+                    height: 2
+                },
+                &mut wide
+            )
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn random_kinds() {
+        use rand::{rngs::StdRng, SeedableRng};
+        let size = Size {
+            width: 40,
+            height: 24,
+        };
+        // live cells next to each other, out of every pair of neighbours in a row.
+        let together = |cells: &[bool]| {
+            let pairs = cells.chunks(40).flat_map(|row| row.windows(2));
+            let (same, all) = pairs.fold((0, 0), |(same, all), w| {
+                (same + usize::from(w[0] == w[1]), all + 1)
+            });
+            same as f64 / all as f64
+        };
+        for kind in [
+            RandomKind::Uniform,
+            RandomKind::Clusters,
+            RandomKind::Perlin,
+        ] {
+            let cells = kind.fill(&mut StdRng::seed_from_u64(2), size);
+            assert_eq!(cells.len(), 40 * 24);
+            assert!(cells.iter().any(|&v| v) && cells.iter().any(|&v| !v));
+            // white noise has no structure, the others have areas of one kind.
+            assert_eq!(
+                kind == RandomKind::Uniform,
+                together(&cells) < 0.6,
+                "{:?}",
+                kind
+            );
+        }
+    }
+
+    #[test]
+    fn maze_generator() -> Result<()> {
+        let board = Generator::Maze.generate(Size {
+            width: 9,
+            height: 7,
+        })?;
+        // every passage cell is carved, every "pillar" stays a wall.
+        for r in 0..3 {
+            for c in 0..4 {
+                assert!(!board[(2 * r + 1) * 9 + 2 * c + 1]);
+                assert!(board[(2 * r) * 9 + 2 * c]);
+            }
+        }
+        // a spanning tree over 12 cells opens exactly 11 walls.
+        assert_eq!(board.iter().filter(|&&v| !v).count(), 12 + 11);
+        Ok(())
+    }
+
+    #[test]
+    fn zero_parameters() {
+        let err = |s: &str| format!("{}", s.parse::<Generator>().unwrap_err());
+        assert_eq!(err("stripes:0"), "stripe width must be bigger than 0.");
+        assert_eq!(err("checkerboard:0"), "square size must be bigger than 0.");
+        assert_eq!(err("rings:0"), "ring spacing must be bigger than 0.");
+    }
+}
@@ -0,0 +1,180 @@
+//! the http endpoint of `--http`, which serves the board while the tui runs, e.g. to dashboards
+//! and scripts:
+//!
+//! - `GET /state.json`: the generation, population, size, rule and seed, and the state of every
+//!   cell row by row.
+//! - `GET /board.png`: a picture of the board, drawn like `--screenshot`.
+//! - `POST /step?n=N`: step N generations, 1 if not given, and answer the state like
+//!   `/state.json`.
+//!
+//! requests are answered by the main loop between keys, so an auto run stops for a moment for
+//! each. without the `http` feature `--http` is an error.
+
+#[cfg(not(feature = "http"))]
+use anyhow::bail;
+use anyhow::Result;
+use life_game::Game;
+use std::net::SocketAddr;
+
+/// a request waiting for its answer.
+#[cfg(feature = "http")]
+pub(crate) type Request = tiny_http::Request;
+#[cfg(not(feature = "http"))]
+pub(crate) enum Request {}
+
+#[cfg(feature = "http")]
+pub(crate) struct Endpoint(tiny_http::Server);
+#[cfg(not(feature = "http"))]
+pub(crate) enum Endpoint {}
+
+impl Endpoint {
+    #[cfg(feature = "http")]
+    pub(crate) fn start(addr: SocketAddr) -> Result<Self> {
+        tiny_http::Server::http(addr)
+            .map(Self)
+            .map_err(|e| anyhow::anyhow!("{}: {}", addr, e))
+    }
+
+    #[cfg(not(feature = "http"))]
+    pub(crate) fn start(_: SocketAddr) -> Result<Self> {
+        bail!("--http needs a build with the http feature.")
+    }
+
+    /// the next request, if one came.
+    pub(crate) fn try_recv(&self) -> Option<Request> {
+        #[cfg(feature = "http")]
+        return self.0.try_recv().ok().flatten();
+        #[cfg(not(feature = "http"))]
+        match *self {}
+    }
+}
+
+/// the board as `/state.json` has it.
+#[cfg(feature = "http")]
+#[derive(serde::Serialize)]
+struct State {
+    generation: usize,
+    population: usize,
+    width: u16,
+    height: u16,
+    rule: String,
+    seed: Option<u64>,
+    cells: Vec<u8>,
+}
+
+#[cfg(feature = "http")]
+fn state(game: &Game) -> Result<String> {
+    Ok(serde_json::to_string(&State {
+        generation: game.state.time,
+        population: game.state.population,
+        width: game.state.size.width,
+        height: game.state.size.height,
+        rule: game.state.rule.to_string(),
+        seed: game.state.seed,
+        cells: game.states(),
+    })?)
+}
+
+/// answer `request` from `game`, stepping it for `/step`. a failed answer is the client's loss.
+#[cfg(feature = "http")]
+pub(crate) fn respond(game: &mut Game, request: Request) -> Result<()> {
+    use life_game::render::raster;
+    use std::io::Cursor;
+    use tiny_http::{Header, Method, Response};
+
+    let json = |body: String| {
+        Response::from_string(body).with_header(
+            Header::from_bytes("Content-Type", "application/json").expect("a valid header"),
+        )
+    };
+    let (path, query) = request.url().split_once('?').unwrap_or((request.url(), ""));
+    let response = match (request.method(), path) {
+        (Method::Get, "/state.json") => json(state(game)?),
+        (Method::Get, "/board.png") => {
+            let mut png = Vec::new();
+            raster::image(game, &game.state.picture)?
+                .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)?;
+            Response::from_data(png).with_header(
+                Header::from_bytes("Content-Type", "image/png").expect("a valid header"),
+            )
+        }
+        (Method::Post, "/step") => {
+            let n = match query.strip_prefix("n=").map(str::parse::<usize>) {
+                None => Ok(1),
+                Some(n) => n,
+            };
+            match n {
+                Ok(n) => {
+                    for _ in 0..n {
+                        game.step()?;
+                    }
+                    json(state(game)?)
+                }
+                Err(e) => Response::from_string(format!("bad n: {}", e)).with_status_code(400),
+            }
+        }
+        (_, "/state.json" | "/board.png" | "/step") => {
+            Response::from_string("method not allowed").with_status_code(405)
+        }
+        _ => Response::from_string("not found").with_status_code(404),
+    };
+    let _ = request.respond(response);
+    Ok(())
+}
+
+#[cfg(not(feature = "http"))]
+pub(crate) fn respond(_: &mut Game, request: Request) -> Result<()> {
+    match request {}
+}
+
+#[cfg(all(test, feature = "http"))]
+mod test {
+    use super::*;
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+        thread,
+    };
+
+    /// send `request` to `endpoint` and answer it from `game`.
+    fn ask(endpoint: &Endpoint, game: &mut Game, request: &str) -> Result<String> {
+        let addr = endpoint.0.server_addr().to_ip().unwrap();
+        let request = format!("{}\r\nConnection: close\r\n\r\n", request);
+        let client = thread::spawn(move || -> Result<String> {
+            let mut stream = TcpStream::connect(addr)?;
+            stream.write_all(request.as_bytes())?;
+            let mut response = Vec::new();
+            stream.read_to_end(&mut response)?;
+            Ok(String::from_utf8_lossy(&response).into_owned())
+        });
+        let request = loop {
+            if let Some(request) = endpoint.try_recv() {
+                break request;
+            }
+            thread::sleep(std::time::Duration::from_millis(5));
+        };
+        respond(game, request)?;
+        client.join().unwrap()
+    }
+
+    #[test]
+    fn serve_the_board() -> Result<()> {
+        let endpoint = Endpoint::start("127.0.0.1:0".parse()?)?;
+        let mut game = Game::blank("5:5".parse()?)?;
+        game.load("blinker")?;
+
+        let response = ask(&endpoint, &mut game, "GET /state.json HTTP/1.1")?;
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.ends_with(r#"{"generation":0,"population":3,"width":5,"height":5,"rule":"B3/S23","seed":null,"cells":[0,0,0,0,0,0,0,0,0,0,0,1,1,1,0,0,0,0,0,0,0,0,0,0,0]}"#));
+        let response = ask(&endpoint, &mut game, "POST /step?n=3 HTTP/1.1")?;
+        assert!(response.contains(r#""generation":3"#));
+        assert_eq!(game.state.time, 3);
+        let response = ask(&endpoint, &mut game, "GET /step HTTP/1.1")?;
+        assert!(response.starts_with("HTTP/1.1 405"));
+        let response = ask(&endpoint, &mut game, "POST /step?n=x HTTP/1.1")?;
+        assert!(response.starts_with("HTTP/1.1 400"));
+        let response = ask(&endpoint, &mut game, "GET /board.png HTTP/1.1")?;
+        assert!(response.contains("image/png"));
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use anyhow::Result;
+use clap::Args;
+use life_game::{
+    apgcode::{self, Object},
+    formats::{self, Board, Format},
+};
+use std::{fmt::Write as _, path::PathBuf};
+
+#[derive(Args, Debug)]
+pub(crate) struct InspectArgs {
+    file: PathBuf,
+    #[arg(long, value_enum, help = "default: guessed from the content.")]
+    format: Option<Format>,
+}
+
+/// (left, top, right, bottom) of the live cells, inclusive.
+fn bounding_box(board: &Board) -> Option<(usize, usize, usize, usize)> {
+    let width = board.size.width as usize;
+    board
+        .cells
+        .iter()
+        .enumerate()
+        .filter(|(_, &v)| v)
+        .map(|(i, _)| (i % width, i / width))
+        .fold(None, |bbox, (x, y)| {
+            Some(match bbox {
+                None => (x, y, x, y),
+                Some((l, t, r, b)) => (l.min(x), t.min(y), r.max(x), b.max(y)),
+            })
+        })
+}
+
+fn report(board: &Board) -> String {
+    let mut report = String::new();
+    let _ = writeln!(report, "format: {}", board.format);
+    let _ = writeln!(report, "size: {}", board.size);
+    let population = board.cells.iter().filter(|&&v| v).count();
+    let _ = writeln!(report, "population: {}", population);
+    match bounding_box(board) {
+        Some((l, t, r, b)) => {
+            let _ = writeln!(
+                report,
+                "bounding box: {}:{} to {}:{} ({}x{})",
+                l,
+                t,
+                r,
+                b,
+                r - l + 1,
+                b - t + 1
+            );
+        }
+        None => report.push_str("bounding box: none\n"),
+    }
+    let meta = &board.meta;
+    // only for still lifes, oscillators and spaceships.
+    if let Some((object, _)) = Object::crop(board.size, &board.cells) {
+        if let Ok(code) = apgcode::encode(&object, &meta.rule.unwrap_or_default()) {
+            let _ = writeln!(report, "apgcode: {}", code);
+        }
+    }
+    if let Some(rule) = meta.rule {
+        let _ = writeln!(report, "rule: {}", rule);
+    }
+    if let Some(generation) = meta.generation {
+        let _ = writeln!(report, "generation: {}", generation);
+    }
+    if let Some(seed) = meta.seed {
+        let _ = writeln!(report, "seed: {}", seed);
+    }
+    if let Some(name) = &meta.name {
+        let _ = writeln!(report, "name: {}", name);
+    }
+    for comment in &meta.comments {
+        let _ = writeln!(report, "comment: {}", comment);
+    }
+    report
+}
+
+/// print what `file` holds, without starting the game.
+pub(crate) fn inspect(args: &InspectArgs) -> Result<()> {
+    let board = formats::load(&args.file, args.format)?;
+    print!("{}", report(&board));
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs::write;
+
+    #[test]
+    fn report_rle() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        let path = dir.join("glider.rle");
+        write(
+            &path,
+            "#N Glider\n#C the smallest spaceship\nx = 5, y = 4, rule = B3/S23\n$2bo$3bo$b3o!\n",
+        )?;
+        let board = formats::load(&path, None)?;
+        assert_eq!(
+            report(&board),
+            "format: rle\nsize: 5x4\npopulation: 5\nbounding box: 1:1 to 3:3 (3x3)\napgcode: xq4_153\nrule: B3/S23\nname: Glider\ncomment: the smallest spaceship\n"
+        );
+        Ok(())
+    }
+}
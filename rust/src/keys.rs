@@ -0,0 +1,466 @@
+use anyhow::{bail, Error, Result};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use serde::Deserialize;
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+/// something a key can be bound to. named in snake_case in the config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Action {
+    Quit,
+    Step,
+    Auto,
+    Edit,
+    Random,
+    Save,
+    Open,
+    Mark,
+    Recall,
+    SavePopulation,
+    Screenshot,
+    Messages,
+    Statistics,
+    Skip,
+    Command,
+    Help,
+    Turbo,
+    Outline,
+    Clear,
+    Left,
+    Down,
+    Up,
+    Right,
+    FarLeft,
+    FarDown,
+    FarUp,
+    FarRight,
+    RowStart,
+    RowEnd,
+    Top,
+    Bottom,
+    Toggle,
+    Cycle,
+    Select,
+    Paint,
+    Mirror,
+    Line,
+    NextBrush,
+    PrevBrush,
+    Forward,
+    Back,
+    Paste,
+    Place,
+    Rotate,
+    FlipHorizontal,
+    FlipVertical,
+    Zoom,
+    Yank,
+    Cut,
+    Fill,
+    Scatter,
+    Erase,
+    Count,
+    Cancel,
+}
+
+use Action::*;
+
+pub(crate) const MAIN: &[Action] = &[
+    Quit,
+    Step,
+    Auto,
+    Edit,
+    Random,
+    Save,
+    Open,
+    Mark,
+    Recall,
+    SavePopulation,
+    Screenshot,
+    Messages,
+    Statistics,
+    Skip,
+    Outline,
+    Clear,
+    Command,
+    Help,
+];
+pub(crate) const AUTO: &[Action] = &[Quit, Turbo, Outline, Command, Help];
+pub(crate) const EDITOR: &[Action] = &[
+    Left,
+    Down,
+    Up,
+    Right,
+    FarLeft,
+    FarDown,
+    FarUp,
+    FarRight,
+    RowStart,
+    RowEnd,
+    Top,
+    Bottom,
+    Toggle,
+    Cycle,
+    Select,
+    Paint,
+    Mirror,
+    Line,
+    NextBrush,
+    PrevBrush,
+    Forward,
+    Back,
+    Paste,
+    Rotate,
+    FlipHorizontal,
+    FlipVertical,
+    Zoom,
+    Clear,
+    Command,
+    Quit,
+    Help,
+];
+/// the editor while the players of a versus game place their cells.
+pub(crate) const VERSUS: &[Action] = &[Left, Down, Up, Right, Toggle, Quit, Help];
+/// the editor while the clipboard is placed, drawn where it would go.
+pub(crate) const PLACING: &[Action] = &[
+    Left,
+    Down,
+    Up,
+    Right,
+    FarLeft,
+    FarDown,
+    FarUp,
+    FarRight,
+    RowStart,
+    RowEnd,
+    Top,
+    Bottom,
+    Place,
+    Rotate,
+    FlipHorizontal,
+    FlipVertical,
+    Cancel,
+    Help,
+];
+/// the editor while a selection is active.
+pub(crate) const SELECTING: &[Action] = &[
+    Left, Down, Up, Right, FarLeft, FarDown, FarUp, FarRight, RowStart, RowEnd, Top, Bottom, Yank,
+    Cut, Fill, Scatter, Erase, Count, Cancel, Help,
+];
+
+impl Action {
+    fn default_key(self) -> KeyCode {
+        KeyCode::Char(match self {
+            Quit => 'q',
+            Step => return KeyCode::Enter,
+            Auto => 'a',
+            Edit => 'e',
+            Random => 'r',
+            Save => 's',
+            Open => 'o',
+            Mark => 'm',
+            Recall => '\'',
+            SavePopulation => 'C',
+            Screenshot => 'p',
+            Messages => 'L',
+            Statistics => 'i',
+            Skip => 'g',
+            Command => ':',
+            Help => '?',
+            Turbo => 't',
+            Outline => 'b',
+            Clear => 'X',
+            Left => 'h',
+            Down => 'j',
+            Up => 'k',
+            Right => 'l',
+            FarLeft => 'H',
+            FarDown => 'J',
+            FarUp => 'K',
+            FarRight => 'L',
+            RowStart => '0',
+            RowEnd => '$',
+            Top => 'g',
+            Bottom => 'G',
+            Toggle => return KeyCode::Enter,
+            Cycle => 'c',
+            Select => 'v',
+            Paint => 'b',
+            Mirror => 'm',
+            Line => 'n',
+            NextBrush => return KeyCode::Tab,
+            PrevBrush => return KeyCode::BackTab,
+            Forward => '.',
+            Back => ',',
+            Paste => 'p',
+            Place => return KeyCode::Enter,
+            Rotate => 'r',
+            FlipHorizontal => 'f',
+            FlipVertical => 'F',
+            Zoom => 'z',
+            Yank => 'y',
+            Cut => 'd',
+            Fill => 'f',
+            Scatter => 'r',
+            Erase => 'x',
+            Count => '#',
+            Cancel => 'v',
+        })
+    }
+
+    /// the name used in the config file.
+    fn name(self) -> String {
+        let mut name = String::new();
+        for c in format!("{:?}", self).chars() {
+            if c.is_ascii_uppercase() && !name.is_empty() {
+                name.push('_');
+            }
+            name.push(c.to_ascii_lowercase());
+        }
+        name
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Quit => "quit",
+            Step => "next generation",
+            Auto => "auto run",
+            Edit => "editor",
+            Random => "regenerate random",
+            Save => "save to file",
+            Open => "open a file",
+            Mark => "store the board in a slot",
+            Recall => "restore the board from a slot",
+            SavePopulation => "save population csv",
+            Screenshot => "save a png",
+            Messages => "messages",
+            Statistics => "statistics",
+            Skip => "skip N (100) generations",
+            Command => "command line",
+            Help => "help",
+            Turbo => "turbo: draw every 1/10/100 generations",
+            Outline => "show the bounding box of the live cells",
+            Clear => "kill every cell",
+            Left => "left",
+            Down => "down",
+            Up => "up",
+            Right => "right",
+            FarLeft => "5 cells left",
+            FarDown => "5 cells down",
+            FarUp => "5 cells up",
+            FarRight => "5 cells right",
+            RowStart => "first column",
+            RowEnd => "last column",
+            Top => "first row, or row N",
+            Bottom => "last row, or row N",
+            Toggle => "reverse the cell",
+            Cycle => "next state of the cell",
+            Select => "select",
+            Paint => "paint while moving",
+            Mirror => "cycle mirrored drawing",
+            Line => "start a line, then draw it to here with the brush",
+            NextBrush => "next brush state",
+            PrevBrush => "previous brush state",
+            Forward => "next generation",
+            Back => "back a generation stepped here",
+            Paste => "place the clipboard",
+            Place => "paste here",
+            Rotate => "rotate clipboard",
+            FlipHorizontal => "flip clipboard left and right",
+            FlipVertical => "flip clipboard top and bottom",
+            Zoom => "zoom",
+            Yank => "yank",
+            Cut => "cut",
+            Fill => "fill with brush",
+            Scatter => "fill randomly, N% (50) alive",
+            Erase => "clear",
+            Count => "count the live cells",
+            Cancel => "cancel",
+        }
+    }
+}
+
+/// a key as written in the config file: a single character, or a name like `enter`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub(crate) struct Key(KeyCode);
+
+impl FromStr for Key {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Self(KeyCode::Char(c)));
+        }
+        Ok(Self(match s.to_lowercase().as_str() {
+            "enter" | "cr" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backtab" | "s-tab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "space" => KeyCode::Char(' '),
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            _ => bail!("unknown key: {}", s),
+        }))
+    }
+}
+
+impl TryFrom<String> for Key {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl Display for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self.0 {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Enter => write!(f, "CR"),
+            KeyCode::BackTab => write!(f, "S-Tab"),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// which key triggers each action.
+#[derive(Debug)]
+pub(crate) struct Keys {
+    bindings: HashMap<Action, Key>,
+}
+
+impl Default for Keys {
+    fn default() -> Self {
+        let bindings = [MAIN, AUTO, EDITOR, VERSUS, PLACING, SELECTING]
+            .concat()
+            .into_iter()
+            .map(|action| (action, Key(action.default_key())))
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl Keys {
+    /// the default bindings with `overrides` applied. two actions of one screen may not share a key.
+    pub(crate) fn new(overrides: &HashMap<Action, Key>) -> Result<Self> {
+        let mut keys = Self::default();
+        keys.bindings.extend(overrides);
+        for mode in [MAIN, AUTO, EDITOR, VERSUS, PLACING, SELECTING] {
+            for (i, &a) in mode.iter().enumerate() {
+                if let Some(&b) = mode[i + 1..].iter().find(|&&b| keys.key(a) == keys.key(b)) {
+                    bail!(
+                        "{} and {} are both bound to {}.",
+                        a.name(),
+                        b.name(),
+                        keys.key(a)
+                    );
+                }
+            }
+        }
+        Ok(keys)
+    }
+
+    pub(crate) fn key(&self, action: Action) -> Key {
+        self.bindings
+            .get(&action)
+            .copied()
+            .unwrap_or(Key(action.default_key()))
+    }
+
+    /// the action of `mode` that `event` triggers.
+    pub(crate) fn action(&self, event: &Event, mode: &[Action]) -> Option<Action> {
+        let Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event
+        else {
+            return None;
+        };
+        mode.iter().copied().find(|&a| self.key(a).0 == *code)
+    }
+
+    /// `<key>: what it does.` for every action of `mode`.
+    pub(crate) fn hints(&self, mode: &[Action]) -> String {
+        mode.iter()
+            .map(|&a| match a {
+                Skip | Left | Down | Up | Right | FarLeft | FarDown | FarUp | FarRight | Bottom
+                | Scatter => {
+                    format!("<[N]{}>: {}.", self.key(a), a.describe())
+                }
+                Top => format!("<[N]{0}{0}>: {1}.", self.key(a), a.describe()),
+                Mark | Recall => format!("<{}[1-9]>: {}.", self.key(a), a.describe()),
+                _ => format!("<{}>: {}.", self.key(a), a.describe()),
+            })
+            .collect::<Vec<_>>()
+            .join("\t")
+    }
+
+    /// every action of `mode` in two columns, for the help screen.
+    pub(crate) fn help(&self, mode: &[Action]) -> String {
+        mode.chunks(2)
+            .map(|pair| {
+                pair.iter()
+                    .map(|&a| format!("  {:<10}{:<32}", self.key(a).to_string(), a.describe()))
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn press(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn remap_keys() -> Result<()> {
+        let overrides: HashMap<Action, Key> = toml::from_str(
+            r#"
+            left = "a"
+            down = "s"
+            up = "w"
+            right = "d"
+            cut = "c"
+            paste = "P"
+            select = "space"
+            "#,
+        )?;
+        let keys = Keys::new(&overrides)?;
+        let event = press(KeyCode::Char('a'));
+        assert_eq!(keys.action(&event, EDITOR), Some(Left));
+        assert_eq!(keys.action(&event, MAIN), Some(Auto));
+        assert_eq!(
+            keys.action(&press(KeyCode::Char(' ')), EDITOR),
+            Some(Select)
+        );
+        assert_eq!(keys.action(&press(KeyCode::Char('h')), EDITOR), None);
+        assert!(keys
+            .hints(EDITOR)
+            .starts_with("<[N]a>: left.\t<[N]s>: down."));
+        Ok(())
+    }
+
+    #[test]
+    fn reject_conflicts() {
+        let overrides = HashMap::from([(Save, Key(KeyCode::Char('q')))]);
+        let err = Keys::new(&overrides).unwrap_err().to_string();
+        assert_eq!(err, "quit and save are both bound to q.");
+        assert_eq!(SavePopulation.name(), "save_population");
+        assert!(Keys::new(&HashMap::new()).is_ok());
+        assert!("ctrl-x".parse::<Key>().is_err());
+    }
+}
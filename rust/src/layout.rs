@@ -0,0 +1,157 @@
+//! the canonical serde layout of boards and games. in json:
+//!
+//! ```json
+//! {"size":"3:3","rule":"B3/S23","generation":0,"seed":null,"name":null,"comments":[],
+//!  "cells":["010","001","111"]}
+//! ```
+//!
+//! rows are strings of `0` and `1`, and dying states, as in the native format. everything but `size` and `cells`
+//! may be left out. only the metadata of a game's [`State`](crate::State) is written: the rest
+//! are settings of a run, not part of the board.
+
+use crate::{
+    engine,
+    formats::{self, Board, Format},
+    meta::Metadata,
+    Game, Size,
+};
+use anyhow::{anyhow, ensure, Error, Result};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize, Deserialize)]
+struct Layout {
+    size: Size,
+    #[serde(flatten)]
+    meta: Metadata,
+    cells: Vec<String>,
+}
+
+impl Layout {
+    fn new(size: Size, states: &[u8], meta: Metadata) -> Self {
+        Self {
+            size,
+            meta,
+            cells: states
+                .chunks(size.width.max(1) as usize)
+                .map(|row| row.iter().map(|&s| formats::state_char(s)).collect())
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<Layout> for Board {
+    type Error = Error;
+
+    fn try_from(layout: Layout) -> Result<Self> {
+        let Size { width, height } = layout.size;
+        ensure!(
+            layout.cells.len() == height as usize,
+            "expected {} rows, found {}.",
+            height,
+            layout.cells.len()
+        );
+        let mut states = Vec::with_capacity(width as usize * height as usize);
+        for (y, row) in layout.cells.iter().enumerate() {
+            ensure!(
+                row.len() == width as usize,
+                "row {} has {} cells, expected {}.",
+                y,
+                row.len(),
+                width
+            );
+            for c in row.chars() {
+                states.push(formats::char_state(c).ok_or_else(|| {
+                    anyhow!("row {}: expected 0, 1 or a dying state, found {:?}", y, c)
+                })?);
+            }
+        }
+        let (cells, dying) = engine::split(&states, &layout.meta.rule.unwrap_or_default());
+        Ok(Board {
+            size: layout.size,
+            cells,
+            dying,
+            meta: layout.meta,
+            format: Format::Native,
+        })
+    }
+}
+
+impl TryFrom<Layout> for Game {
+    type Error = Error;
+
+    fn try_from(layout: Layout) -> Result<Self> {
+        let board = Board::try_from(layout)?;
+        let mut game = Game::from_board(board.size, board.cells)?;
+        game.dying = board.dying;
+        game.state.apply_metadata(board.meta);
+        Ok(game)
+    }
+}
+
+impl Serialize for Board {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Layout::new(
+            self.size,
+            &engine::states(&self.cells, &self.dying),
+            self.meta.clone(),
+        )
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Board {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Layout::deserialize(deserializer)?
+            .try_into()
+            .map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for Game {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Layout::new(self.state.size, &self.states(), self.metadata()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Game {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Layout::deserialize(deserializer)?
+            .try_into()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::patterns::Pattern;
+
+    #[test]
+    fn json_layout() -> Result<()> {
+        let size = "5:4".parse()?;
+        let mut game = Game::from_board(size, "glider".parse::<Pattern>()?.centered(size)?)?;
+        game.state.name = Some("glider".into());
+        let json = serde_json::to_string(&game)?;
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"size":"5:4","rule":"B3/S23","generation":0,"seed":null,"name":"glider","#,
+                r#""comments":[],"cells":["00100","00010","01110","00000"]}"#
+            )
+        );
+
+        game.step()?;
+        let read: Game = serde_json::from_str(&serde_json::to_string(&game)?)?;
+        assert_eq!(read.game, game.game);
+        assert_eq!(read.state.time, 1);
+        assert_eq!(read.state.population, game.state.population);
+
+        let board: Board =
+            serde_json::from_str(r#"{"size":"2:1","rule":"B36/S23","cells":["10"]}"#)?;
+        assert_eq!(board.cells, [true, false]);
+        assert_eq!(board.meta.rule, Some("B36/S23".parse()?));
+        assert!(serde_json::from_str::<Board>(r#"{"size":"2:1","cells":["1"]}"#).is_err());
+        assert!(serde_json::from_str::<Board>(r#"{"size":"2:1","cells":["1x"]}"#).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+//! conway's game of life, and the variants a rule string describes, on a wrapping board.
+//!
+//! [`Game`] holds a board and its [`State`]. [`formats`] reads boards from files,
+//! [`rle`] and [`Game::to_native`] write them, and [`render`] draws them as pictures. all of
+//! them need the default `std` feature; without it only [`engine`], [`rule`], [`script`],
+//! [`apgcode`] and [`Size`] are left, which build with `#![no_std]` and `alloc`.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![warn(missing_docs)]
+
+extern crate alloc;
+
+pub mod apgcode;
+#[cfg(feature = "std")]
+pub mod clip;
+pub mod engine;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "std")]
+pub mod formats;
+#[cfg(feature = "std")]
+mod game;
+#[cfg(feature = "std")]
+pub mod generate;
+#[cfg(feature = "serde")]
+pub mod layout;
+#[cfg(feature = "std")]
+pub mod meta;
+#[cfg(feature = "std")]
+pub mod patterns;
+#[cfg(feature = "std")]
+pub mod render;
+#[cfg(feature = "std")]
+pub mod replay;
+#[cfg(feature = "std")]
+pub mod rle;
+pub mod rule;
+pub mod script;
+#[cfg(feature = "std")]
+pub mod stats;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "std")]
+pub use game::{
+    show_duration, Anchor, Bounds, Checkpoint, DumpFormat, FrameDump, Game, Glyphs, Noise, State,
+    Transform,
+};
+
+use alloc::{format, string::String};
+use anyhow::Result;
+use core::fmt::Display;
+#[cfg(feature = "std")]
+use {
+    anyhow::{anyhow, Error},
+    once_cell::sync::Lazy,
+    regex::Regex,
+    std::{concat, str::FromStr},
+};
+
+#[cfg(feature = "std")]
+static POINT_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?P<width>\d+):(?P<height>\d+)$").unwrap());
+/// parse `X:Y`, as used by sizes and positions.
+#[cfg(feature = "std")]
+pub fn point_from_str(s: &str) -> Result<(u16, u16)> {
+    let cap = POINT_REGEX.captures(s).ok_or_else(|| {
+        anyhow!(concat!(
+            "Invalid Format!.",
+            r#"note:: you must use a "<width>:<height>" format."#
+        ))
+    })?;
+    Ok((
+        cap.name("width").unwrap().as_str().parse()?,
+        cap.name("height").unwrap().as_str().parse()?,
+    ))
+}
+
+/// width and height of a board, written `W:H`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "std",
+    derive(serde::Deserialize),
+    serde(try_from = "String")
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(into = "String"))]
+pub struct Size {
+    /// columns of the board.
+    pub width: u16,
+    /// rows of the board.
+    pub height: u16,
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<String> for Size {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<Size> for String {
+    fn from(size: Size) -> Self {
+        format!("{}:{}", size.width, size.height)
+    }
+}
+
+impl Display for Size {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+impl Default for Size {
+    fn default() -> Self {
+        Self {
+            width: 160,
+            height: 32,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl FromStr for Size {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (width, height) = point_from_str(s)?;
+        Ok(Self { width, height })
+    }
+}
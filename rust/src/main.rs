@@ -1,26 +1,91 @@
-use anyhow::{anyhow, ensure, Error, Result};
-use chrono::prelude::Local;
-use clap::{ArgGroup, Parser};
+mod bench;
+mod census;
+mod command;
+mod complete;
+mod config;
+#[cfg(feature = "fetch")]
+mod fetch;
+mod frontend;
+mod http;
+mod inspect;
+mod keys;
+mod mirror;
+mod notify;
+mod playback;
+mod rpc;
+mod screen;
+#[cfg(feature = "serve")]
+mod serve;
+mod spectate;
+mod sweep;
+mod ui;
+mod versus;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use clap::{
+    parser::ValueSource, ArgGroup, ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand,
+    ValueEnum,
+};
 use crossterm::{
     cursor::{Hide, MoveTo, SetCursorStyle, Show},
-    event::{poll, read, Event, KeyCode, KeyEvent, KeyEventKind},
-    execute, queue,
-    terminal::{size, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+    event::{
+        poll, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind, MouseButton, MouseEvent,
+        MouseEventKind,
+    },
+    execute,
+    terminal::{size, Clear, ClearType, EnterAlternateScreen},
 };
-use once_cell::sync::Lazy;
 use rand::{thread_rng, Rng};
-use regex::Regex;
 use std::{
     cmp::max,
+    collections::HashSet,
     concat,
-    fmt::Display,
-    fs::{read_to_string, write},
-    io::{stdout, Stdout, Write},
+    fs::{create_dir_all, read_to_string, write, File},
+    io::{stdin, BufRead, BufReader, IsTerminal, Write},
+    net::SocketAddr,
     path::PathBuf,
-    str::FromStr,
-    thread::sleep,
-    time::Duration,
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError},
+        Mutex, MutexGuard, PoisonError,
+    },
+    thread::{self, Scope, ScopedJoinHandle},
+    time::{Duration, Instant},
+};
+
+use bench::BenchArgs;
+use census::CensusArgs;
+use command::Cmd;
+use config::Config;
+#[cfg(feature = "fetch")]
+use fetch::FetchArgs;
+use frontend::{Frontend, Viewport};
+use http::Endpoint;
+use inspect::InspectArgs;
+use keys::{Action, Keys};
+use life_game::{
+    clip::{self, Clip},
+    formats::{self, Format, Placement},
+    generate::{Generator, RandomKind, Symmetry},
+    patterns::Pattern,
+    point_from_str,
+    render::{self, raster},
+    replay::Replay,
+    rule::{Neighbourhood, Rule},
+    show_duration,
+    stats::population_csv,
+    Checkpoint, DumpFormat, FrameDump, Game, Noise, Size, State, Transform,
 };
+use mirror::Mirror;
+use notify::Notifications;
+use playback::ReplayArgs;
+use rpc::Socket;
+use screen::{read, Interrupted, RawMode, Screen};
+#[cfg(feature = "serve")]
+use serve::ServeArgs;
+use spectate::{Host, SpectateArgs};
+use sweep::SweepArgs;
+use ui::{BoardWidget, LogArea, Panel, Snapshot, StatusBar};
+use versus::{Turn, Versus};
 
 #[allow(unused_macros)]
 macro_rules! debug {
@@ -86,500 +151,2629 @@ macro_rules! press {
     };
 }
 
-static POINT_REGEX: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^(?P<width>\d+):(?P<height>\d+)$").unwrap());
-static FILE_FORMAT_REGEX: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^(?P<width>\d+):(?P<height>\d+)\n(?P<data>[01\n]+)$").unwrap());
-
-fn point_from_str(s: &str) -> Result<(u16, u16)> {
-    let cap = POINT_REGEX.captures(s).ok_or_else(|| {
-        anyhow!(concat!(
-            "Invalid Format!.",
-            r#"note:: you must use a "<width>:<height>" format."#
-        ))
-    })?;
-    Ok((
-        cap.name("width").unwrap().as_str().parse()?,
-        cap.name("height").unwrap().as_str().parse()?,
-    ))
-}
+/// lines used under the board by the status and hints.
+const STATUS_LINES: u16 = 6;
 
-#[derive(Debug, Clone, Copy)]
-struct Size {
-    width: u16,
-    height: u16,
-}
+/// shortest duration between generations unless `--unlimited-speed` is given.
+const MIN_DURATION: Duration = Duration::from_millis(15);
 
-impl Display for Size {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
-        write!(f, "{}x{}", self.width, self.height)
-    }
+/// parse a probability from 0 to 1.
+fn probability_arg(v: &str) -> Result<f64> {
+    v.parse()
+        .ok()
+        .filter(|p| (0.0..=1.0).contains(p))
+        .ok_or_else(|| anyhow!("a probability is a number from 0 to 1, e.g. 0.01."))
 }
 
-impl Default for Size {
-    fn default() -> Self {
-        Self {
-            width: 160,
-            height: 32,
-        }
-    }
+/// parse `250ms`, `1s`, `0.5s`, ... bare numbers are milliseconds.
+fn duration_arg(v: &str) -> Result<Duration> {
+    let v = if v.bytes().all(|b| b.is_ascii_digit()) {
+        Duration::from_millis(v.parse()?)
+    } else if let Ok(v) = humantime::parse_duration(v) {
+        v
+    } else {
+        let secs = v
+            .strip_suffix('s')
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|s| s.is_finite() && *s >= 0.0)
+            .ok_or_else(|| anyhow!("Invalid Duration!.note:: e.g. 100, 250ms, 1s, 0.5s."))?;
+        Duration::from_secs_f64(secs)
+    };
+    Ok(v)
 }
 
-impl FromStr for Size {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self> {
-        let (width, height) = if s == "M" {
-            let (w, h) = size()?;
-            (
-                w,
-                h.checked_sub(5).ok_or(anyhow!("Terminal is too small!"))?,
-            )
-        } else {
-            point_from_str(s)?
-        };
-        Ok(Self { width, height })
+/// `W:H`, or `M` for the whole terminal but the status lines.
+fn size_arg(v: &str) -> Result<Size> {
+    if v != "M" {
+        return v.parse();
     }
+    let (width, height) = size()?;
+    Ok(Size {
+        width,
+        height: height
+            .checked_sub(STATUS_LINES)
+            .ok_or(anyhow!("Terminal is too small!"))?,
+    })
 }
 
-fn min_15(v: &str) -> Result<u64> {
-    let v = v.parse::<u64>()?;
-    Ok(max(v, 15))
+fn fps(v: &str) -> Result<Duration> {
+    let v = v.parse::<f64>()?;
+    ensure!(v.is_finite() && v > 0.0, "fps must be bigger than 0.");
+    Ok(Duration::from_secs_f64(1.0 / v))
 }
 
 #[derive(Parser, Debug)]
 #[command(group(
-    ArgGroup::new("initialize").required(false).args(["file", "random"])
+    ArgGroup::new("initialize").required(false).args(["file", "random", "generate", "pattern"])
 ))]
 struct Args {
     #[arg(
         short,
         long,
         default_value = "160:32",
-        default_value_if("max", "true", "M")
+        default_value_if("max", "true", "M"),
+        value_parser = size_arg
     )]
     size: Size,
     #[arg(short = 'M', long, conflicts_with = "size")]
     max: bool,
     #[arg(short, long)]
     random: bool,
-    #[arg(short, long, value_name = "FILE", conflicts_with_all = ["size", "random", "max"])]
-    file: Option<PathBuf>,
-    #[arg(short, long, default_value = "100", help = "ms. min: 15ms.", value_parser = min_15)]
-    duration: u64,
+    #[arg(
+        long,
+        help = "seed of the random board and of --noise. saves record it either way."
+    )]
+    seed: Option<u64>,
+    #[arg(
+        long,
+        value_enum,
+        ignore_case = true,
+        default_value = "C1",
+        help = "symmetry of random boards, also of <r>. C4 and D8 need a square board."
+    )]
+    symmetry: Symmetry,
+    #[arg(
+        long,
+        value_enum,
+        default_value = "uniform",
+        help = "how random boards are filled, also by <r>."
+    )]
+    random_kind: RandomKind,
+    #[arg(
+        long,
+        value_name = "W:H",
+        help = "randomize only a W:H soup in the middle of random boards, also of <r>. the rest starts dead."
+    )]
+    soup_size: Option<Size>,
+    #[arg(
+        short,
+        long,
+        value_name = "FILE",
+        conflicts_with = "random",
+        help = "start from FILE, or FILE@X,Y. - is stdin. repeat to combine files on a board of --size."
+    )]
+    file: Vec<Placement>,
+    #[arg(
+        long,
+        value_name = "X:Y",
+        requires = "file",
+        value_parser = point_from_str,
+        help = "top left of --file without @X,Y. default: centered."
+    )]
+    offset: Option<(u16, u16)>,
+    /// place --file on a board of --size, instead of taking the size of the file.
+    #[arg(skip)]
+    embed: bool,
+    #[arg(
+        long,
+        value_enum,
+        requires = "file",
+        help = "format of --file. default: guessed from the content."
+    )]
+    format: Option<Format>,
+    #[arg(
+        short,
+        long,
+        value_name = "KIND",
+        help = "maze, stripes[:N], checkerboard[:N], rings[:N] or text:<word>."
+    )]
+    generate: Option<Generator>,
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "built-in pattern placed on the center."
+    )]
+    pattern: Option<Pattern>,
+    #[arg(
+        short,
+        long,
+        default_value = "100ms",
+        help = "e.g. 250ms, 1s, 0.5s. bare numbers are ms. min: 15ms.",
+        value_parser = duration_arg
+    )]
+    duration: Duration,
+    #[arg(long, conflicts_with = "duration", help = "frames per second. instead of --duration.", value_parser = fps)]
+    fps: Option<Duration>,
+    #[arg(long, help = "allow durations below 15ms, down to no wait at all.")]
+    unlimited_speed: bool,
+    #[arg(
+        long,
+        conflicts_with_all = ["once", "screenshot", "animation", "versus"],
+        help = "run without the tui and without waiting until the run stops by itself or reaches --generations, e.g. to --dump-frames."
+    )]
+    headless: bool,
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "write the board to DIR while auto running, or --headless."
+    )]
+    dump_frames: Option<PathBuf>,
+    #[arg(
+        long,
+        alias = "every",
+        value_name = "N",
+        default_value = "100",
+        help = "generations between dumped frames.",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    dump_every: u64,
+    #[arg(long, value_enum, default_value = "native")]
+    dump_format: DumpFormat,
+    #[arg(
+        long,
+        value_enum,
+        default_value = "ask",
+        help = "what to do when the board is bigger than the terminal."
+    )]
+    on_oversize: Oversize,
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "save only the cells that differ from FILE."
+    )]
+    delta_base: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "write the population of every generation as csv on exit."
+    )]
+    population_csv: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "N",
+        default_value = "1",
+        help = "draw only every Nth generation while auto running.",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    render_every: u64,
+    #[arg(long, value_name = "N", help = "stop auto running at generation N.")]
+    generations: Option<usize>,
+    #[arg(
+        long,
+        value_name = "P",
+        value_parser = probability_arg,
+        help = "flip every cell with probability P after each generation, e.g. 0.001. auto running doesn't stop by itself then."
+    )]
+    noise: Option<f64>,
+    #[arg(
+        long,
+        default_value = "B3/S23",
+        help = "life-like rule in B/S notation, a Generations rule, e.g. B2/S345/C4 or 345/2/4, a Larger than Life rule, e.g. R5,C0,M1,S34..58,B34..45,NM, or the number of an elementary rule, e.g. 110."
+    )]
+    rule: Rule,
+    /// the rule came from --rule, --ca, --neighborhood or the config file, and wins over the one
+    /// saved in --file.
+    #[arg(skip)]
+    rule_given: bool,
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with = "versus",
+        help = "a rule written as an expression of `state` and `count`, the live neighbours, e.g. `count == 3 || state == 1 && count == 2`. --rule gives its states and neighbourhood."
+    )]
+    rule_script: Option<PathBuf>,
+    #[arg(long, value_enum, default_value = "life", help = "the automaton.")]
+    ca: Automaton,
+    #[arg(
+        long,
+        value_enum,
+        help = "neighbours of a cell under --rule. hex draws odd rows half a cell to the right."
+    )]
+    neighborhood: Option<Neighbourhood>,
+    #[arg(
+        long,
+        conflicts_with_all = ["rule", "ca", "neighborhood", "generations"],
+        help = "a game for two: the players take turns placing red and blue cells under the Immigration rule, then the board runs. live cells of each colour score."
+    )]
+    versus: bool,
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 10,
+        help = "cells each player places per round of --versus."
+    )]
+    turn_cells: usize,
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 50,
+        help = "generations every round of --versus runs."
+    )]
+    turn_generations: usize,
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 3,
+        help = "rounds of --versus."
+    )]
+    rounds: usize,
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "default: ~/.config/life-game/config.toml"
+    )]
+    config: Option<PathBuf>,
+    #[arg(
+        long,
+        help = "keep auto running after the board died out or stabilized."
+    )]
+    no_auto_stop: bool,
+    #[arg(long, help = "track run statistics. printed on exit, shown with <i>.")]
+    stats: bool,
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "where <s> and <C> write their files. default: the current directory."
+    )]
+    output_dir: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "N",
+        num_args = 0..=1,
+        default_missing_value = "0",
+        help = "cut saves, but not deltas, down to the live cells and N dead cells around them. default N: 0."
+    )]
+    trim: Option<u16>,
+    #[arg(
+        long,
+        value_name = "N",
+        num_args = 0..=1,
+        default_missing_value = "0",
+        help = "mark the edges the board wraps around at, and draw N cells of the opposite edges around it. default N: 0."
+    )]
+    seam: Option<u16>,
+    #[arg(
+        long,
+        help = "number the columns above the board and the rows left of it."
+    )]
+    rulers: bool,
+    #[arg(
+        long,
+        help = "print the board to stdout on exit, in the native format."
+    )]
+    print_on_exit: bool,
+    #[arg(
+        long,
+        help = "compute one generation and print it without the tui, e.g. as a pipe filter."
+    )]
+    once: bool,
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with = "once",
+        help = "write a picture of the board to FILE.png or FILE.svg without the tui. <p> writes a png while running."
+    )]
+    screenshot: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["once", "screenshot"],
+        help = "run to --generations (default: 100) without the tui and write an animated png, the only animation format, to FILE.png, a frame every --render-every generations, --duration apart."
+    )]
+    animation: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "record the run, every step and edit, to FILE to play it back with `replay`."
+    )]
+    record: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "ADDR",
+        help = "serve the board over http at ADDR while the tui runs: GET /state.json, GET /board.png and POST /step?n=N. needs the http feature."
+    )]
+    http: Option<SocketAddr>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "take json-rpc calls on a unix socket at PATH while the tui runs: step, set_cell, load and get_population. needs the rpc feature."
+    )]
+    control: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "ADDR",
+        help = "send every board drawn to instances attached with `spectate` on ADDR."
+    )]
+    host: Option<SocketAddr>,
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "pixels on a side of a cell in pictures. default: 1, or the config file."
+    )]
+    cell_pixels: Option<u32>,
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
-impl Args {
-    pub(crate) fn init_from_file(&self, path: PathBuf) -> Result<Vec<bool>> {
-        let path = path.as_path();
-
-        ensure!(path.exists() && path.is_file());
-
-        let str = read_to_string(path)?;
-        let cap = FILE_FORMAT_REGEX
-            .captures(str.as_str())
-            .ok_or_else(|| anyhow!("Invalid File Format!"))?;
-
-        let width = cap.name("width").unwrap().as_str().parse::<u16>()?;
-        let height = cap.name("height").unwrap().as_str().parse::<u16>()?;
-        let length = width as usize * height as usize;
-
-        let data = cap.name("data").unwrap().as_str();
-        let mut game: Vec<bool> = Vec::with_capacity(length);
-        for c in data.chars() {
-            match c {
-                '\n' => continue,
-                '0' => game.push(false),
-                '1' => game.push(true),
-                _ => panic!("unreachable"),
-            }
-        }
-
-        ensure!(length == game.len(), "Invalid Data!");
-
-        Ok(game)
-    }
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// run random soups headlessly over a grid of parameters and aggregate their statistics.
+    Sweep(SweepArgs),
+    /// run random soups headlessly until they settle and count how they ended and the objects
+    /// they left, like apgsearch.
+    Census(CensusArgs),
+    /// time every engine on a random board and print its generations per second.
+    Bench(BenchArgs),
+    /// print the size, population, bounding box, format and metadata of a pattern file.
+    Inspect(InspectArgs),
+    /// play a run recorded with --record back.
+    Replay(ReplayArgs),
+    /// draw the board of an instance run with --host, without touching it.
+    Spectate(SpectateArgs),
+    /// download a pattern from LifeWiki, or an apgcode from catagolue, into the pattern
+    /// directory.
+    #[cfg(feature = "fetch")]
+    Fetch(FetchArgs),
+    /// run the board without the tui, --duration a step, and stream it to websocket clients.
+    #[cfg(feature = "serve")]
+    Serve(ServeArgs),
 }
 
-#[derive(Debug)]
-struct State {
-    size: Size,
-    time: usize,
-    duration: u64,
-    len: usize,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Automaton {
+    /// life-like, Generations or Larger than Life, as --rule.
+    Life,
+    /// empty cells, electron heads, tails and conductors.
+    Wireworld,
+    /// on, dying and off cells, i.e. the rule B2/S/C3.
+    BriansBrain,
+    /// one row of cells, which scrolls up every generation. --rule is its number, 110 by default.
+    Elementary,
+    /// life with 2 colours. cells are born with the colour most of their parents have.
+    Immigration,
+    /// life with 4 colours. three parents of different colours give the fourth.
+    Quadlife,
 }
 
-impl Display for State {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
-        write!(f, "{}, {}times", self.size, self.time)
-    }
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Oversize {
+    Ask,
+    Clamp,
+    Scroll,
+    Abort,
 }
 
-impl State {
-    fn new(args: &Args) -> Result<Self> {
-        Ok(Self {
-            size: args.size,
-            time: 0,
-            duration: args.duration,
-            len: usize::checked_mul(args.size.width.into(), args.size.height.into())
-                .ok_or_else(|| anyhow!("overflow"))?,
-        })
+impl Args {
+    /// parse the command line, then fill options it didn't give from the config file.
+    fn parse_with_config() -> Result<(Self, Config)> {
+        let matches = Self::command().get_matches();
+        let mut args = Self::from_arg_matches(&matches)?;
+        let config = Config::load(args.config.clone())?;
+        args.merge(&config, &matches)?;
+        Ok((args, config))
     }
 
-    fn move_to(&self, pos: (u16, u16), amount: (i16, i16)) -> Result<(u16, u16)> {
-        let (px, py) = pos;
-        let (ax, ay) = amount;
-        Ok((
-            ((self.size.width as i32 + px as i32 + ax as i32) % self.size.width as i32) as u16,
-            ((self.size.height as i32 + py as i32 + ay as i32) % self.size.height as i32) as u16,
-        ))
+    /// take defaults from `config` for every option `matches` didn't get on the command line.
+    fn merge(&mut self, config: &Config, matches: &ArgMatches) -> Result<()> {
+        let given = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+        self.embed = given("size")
+            || self.max
+            || self.offset.is_some()
+            || self.file.len() > 1
+            || self.file.iter().any(|f| f.offset.is_some());
+        if let Some(size) = config.size.filter(|_| !given("size") && !self.max) {
+            self.size = size;
+        }
+        if let Some(duration) = config.duration.as_deref() {
+            if !given("duration") && !given("fps") {
+                self.duration = duration_arg(duration)?;
+            }
+        }
+        if let Some(rule) = config.rule.filter(|_| !given("rule")) {
+            self.rule = rule;
+        }
+        self.rule_given = given("rule")
+            || config.rule.is_some()
+            || !matches!(self.ca, Automaton::Life)
+            || self.neighborhood.is_some();
+        match self.ca {
+            Automaton::Life => {}
+            Automaton::Elementary if self.rule.wolfram().is_some() => {}
+            Automaton::Elementary => {
+                ensure!(
+                    !given("rule"),
+                    "--ca elementary needs the number of an elementary rule, e.g. --rule 110."
+                );
+                self.rule = Rule::elementary(110);
+            }
+            _ if given("rule") => bail!("--ca {:?} has its own rule.", self.ca),
+            Automaton::Wireworld => self.rule = Rule::wireworld(),
+            Automaton::BriansBrain => self.rule = Rule::brians_brain(),
+            Automaton::Immigration => self.rule = Rule::immigration(),
+            Automaton::Quadlife => self.rule = Rule::quadlife(),
+        }
+        if let Some(neighbourhood) = self.neighborhood {
+            self.rule = self.rule.with_neighbourhood(neighbourhood)?;
+        }
+        if self.versus {
+            ensure!(
+                self.turn_cells > 0 && self.rounds > 0,
+                "--versus needs cells to place and rounds to play."
+            );
+            self.rule = Rule::immigration();
+        }
+        ensure!(
+            self.seed.is_none() || self.random || self.noise.is_some(),
+            "--seed needs --random or --noise."
+        );
+        if self.output_dir.is_none() {
+            self.output_dir = config.save_dir.clone();
+        }
+        Ok(())
     }
 }
 
-#[derive(Debug)]
-struct Game {
-    game: Vec<bool>,
-    state: State,
-}
-
-impl Display for Game {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
-        write!(f, "{}\n{}", self.show_board(), self.state)
+/// the state a run starts with, from the command line.
+fn new_state(args: &Args) -> Result<State> {
+    let dump = if let Some(dir) = args.dump_frames.clone() {
+        create_dir_all(&dir)?;
+        Some(FrameDump {
+            dir,
+            every: args.dump_every,
+            format: args.dump_format,
+        })
+    } else {
+        None
+    };
+    let mut duration = args.fps.unwrap_or(args.duration);
+    if duration < MIN_DURATION && !args.unlimited_speed {
+        eprintln!(
+            "duration {:?} is clamped to {:?}. use --unlimited-speed to run faster.",
+            duration, MIN_DURATION
+        );
+        duration = max(duration, MIN_DURATION);
     }
+    Ok(State {
+        duration,
+        rule: args.rule,
+        script: match &args.rule_script {
+            Some(path) => {
+                let context = || format!("rule script {}", path.display());
+                Some(
+                    read_to_string(path)
+                        .with_context(context)?
+                        .parse()
+                        .with_context(context)?,
+                )
+            }
+            None => None,
+        },
+        // a noisy board never settles for good.
+        auto_stop: !args.no_auto_stop && args.noise.is_none(),
+        limit: args.generations,
+        render_every: args.render_every,
+        dump,
+        trim: args.trim,
+        symmetry: args.symmetry,
+        random_kind: args.random_kind,
+        soup: args.soup_size,
+        noise: args
+            .noise
+            .map(|p| Noise::new(p, args.seed.unwrap_or_else(|| thread_rng().gen()))),
+        delta_base: match args.delta_base.clone() {
+            Some(path) => {
+                let base = formats::load(&path, None)?.cells;
+                Some((path, base))
+            }
+            None => None,
+        },
+        ..State::blank(args.size)?
+    })
 }
 
-impl Game {
-    fn init(args: &Args) -> Result<Self> {
-        let state = State::new(args)?;
+/// the board a run starts from: a file, a pattern, generated text, random or blank.
+fn init_game(args: &Args) -> Result<Game> {
+    let mut state = new_state(args)?;
+    // only a board read as it is keeps its dying cells.
+    let mut dying = None;
+    // a random board is filled in once the game is built.
+    let mut seed = None;
 
-        let game = if let Some(path) = args.file.clone() {
-            args.init_from_file(path)?
-        } else if args.random {
-            let mut base = vec![false; state.len];
-            thread_rng().fill(&mut base[..]);
-            base
+    let game = if let [file] = &args.file[..] {
+        let board = formats::load(&file.path, args.format)?;
+        let cells = if args.embed {
+            let mut cells = vec![false; state.len];
+            board.paste_onto(&mut cells, state.size, file.offset.or(args.offset))?;
+            cells
         } else {
-            vec![false; state.len]
+            state.size = board.size;
+            state.len = board.cells.len();
+            dying = Some(board.dying);
+            board.cells
         };
+        state.apply_metadata(board.meta);
+        if args.rule_given {
+            state.rule = args.rule;
+        }
+        cells
+    } else if !args.file.is_empty() {
+        // several files start from generation 0 under --rule.
+        let mut cells = vec![false; state.len];
+        for file in &args.file {
+            formats::load(&file.path, args.format)?
+                .paste_onto(&mut cells, state.size, file.offset.or(args.offset))
+                .with_context(|| format!("{}", file.path.display()))?;
+        }
+        cells
+    } else if let Some(pattern) = &args.pattern {
+        pattern.centered(state.size)?
+    } else if let Some(gen) = &args.generate {
+        gen.generate(state.size)?
+    } else if args.random {
+        seed = Some(args.seed.unwrap_or_else(|| thread_rng().gen()));
+        vec![false; state.len]
+    } else {
+        let mut cells = vec![false; state.len];
+        if state.rule.wolfram().is_some() {
+            // an elementary automaton starts from one cell in the middle of the bottom row.
+            let width = state.size.width as usize;
+            cells[state.len - width + width / 2] = true;
+        }
+        cells
+    };
 
-        Ok(Self { game, state })
-    }
-
-    fn clear(&mut self) {
-        self.game = vec![false; self.state.len];
-    }
-
-    fn random(&mut self) {
-        self.clear();
-        thread_rng().fill(&mut self.game[..]);
-    }
-
-    fn show_board(&self) -> String {
-        let chars: Vec<char> = self
-            .game
-            .iter()
-            .map(|&v| if v { '@' } else { '-' })
-            .collect();
-        let mut formatted = String::with_capacity(
-            usize::checked_add(self.state.len, self.state.size.height.into())
-                .ok_or_else(|| anyhow!("overflow"))
-                .unwrap(),
-        );
-
-        let mut i = 0;
-        for _ in 0..self.state.size.height {
-            for _ in 0..self.state.size.width {
-                formatted.push(chars[i]);
-                i += 1;
-            }
-            formatted.push('\n');
-        }
-        formatted
-    }
-
-    fn next(&mut self) -> Result<()> {
-        self.state.time += 1;
-        self.game = self
-            .game
-            .iter()
-            .enumerate()
-            .map(|(i, &v)| -> Result<bool> {
-                let pts = self.get_pt(i)?;
-                let alive = pts.iter().filter(|&&j| self.game[j]).count();
-                Ok(if v {
-                    // idx: alive
-                    1 < alive && alive < 4
-                } else {
-                    // idx: dead
-                    alive == 3
-                })
-            })
-            .collect::<Result<Vec<_>>>()?;
-        Ok(())
-    }
-
-    fn get_pt(&self, idx: usize) -> Result<[usize; 8]> {
-        // cu ru rm rd cd ld lm lu
-        let idx: u32 = idx.try_into()?;
-
-        let width = self.state.size.width.into();
-        let size: u32 = self.state.len.try_into()?;
-
-        let left_weight = if idx % width == 0 { width } else { 0 };
-        let right_weight = if idx % width == width - 1 { width } else { 0 };
-
-        let cu = (size + idx - width) % size;
-        let cd = (size + idx + width) % size;
-
-        let ru = (size + idx + 1 - right_weight - width) % size;
-        let rm = (size + idx + 1 - right_weight) % size;
-        let rd = (size + idx + 1 - right_weight + width) % size;
-
-        let lu = (size + idx - 1 + left_weight - width) % size;
-        let lm = (size + idx - 1 + left_weight) % size;
-        let ld = (size + idx - 1 + left_weight + width) % size;
-
-        Ok([cu, ru, rm, rd, cd, ld, lm, lu].map(|v| v as usize))
-    }
-
-    fn check_pos(&self, pos: (u16, u16)) -> Result<()> {
-        let (x, y) = pos;
+    if let Some((path, base)) = &state.delta_base {
         ensure!(
-            x < self.state.size.width,
-            "x:{} is bigger than width:{}",
-            x,
-            self.state.size.width
-        );
-        ensure!(
-            y < self.state.size.height,
-            "y:{} is bigger than height:{}",
-            y,
-            self.state.size.height
+            base.len() == game.len(),
+            "delta base {} has another size than the board.",
+            path.display()
         );
-        Ok(())
     }
 
-    fn set_pos(&mut self, pos: (u16, u16)) -> Result<()> {
-        self.check_pos(pos)?;
-        let (x, y) = pos;
-        let idx = (y * self.state.size.width + x) as usize;
-        self.game[idx] = !self.game[idx];
-        Ok(())
+    let mut game = Game::from_board(state.size, game)?;
+    game.state = state;
+    if let Some(dying) = dying {
+        game.dying = dying;
     }
-
-    fn move_to(&self, pos: (u16, u16), amount: (i16, i16)) -> Result<(u16, u16)> {
-        self.state.move_to(pos, amount)
+    if let Some(seed) = seed {
+        game.random_from(seed)?;
     }
-
-    fn save(&self) -> Result<String> {
-        let path = Local::now().format("./%F_%H.%M.%ST%z.txt").to_string();
-        let mut data = format!("{}:{}", self.state.size.width, self.state.size.height);
-        for (i, &v) in self.game.iter().enumerate() {
-            if i % self.state.size.width as usize == 0 {
-                data.push('\n')
-            }
-            data.push(if v { '1' } else { '0' });
-        }
-        write(&path, data)?;
-
-        Ok(format!("success save to {}", path))
+    game.recount();
+    game.record_population(0, 0);
+    if args.stats {
+        game.reset_stats();
     }
+    Ok(game)
 }
 
 fn main() -> Result<()> {
     // setup App by cmd line options
-    let args = Args::parse();
-    let mut game = Game::init(&args)?;
+    #[cfg_attr(not(feature = "fetch"), allow(unused_mut))]
+    let (mut args, config) = Args::parse_with_config()?;
+    match &args.command {
+        Some(Command::Sweep(sweep)) => return sweep::sweep(sweep),
+        Some(Command::Census(census)) => return census::census(census),
+        Some(Command::Bench(bench)) => return bench::bench(bench),
+        Some(Command::Inspect(inspect)) => return inspect::inspect(inspect),
+        Some(Command::Replay(replay)) => return playback::replay(replay, &config),
+        Some(Command::Spectate(spectate)) => return spectate::spectate(spectate, &config),
+        #[cfg(feature = "serve")]
+        Some(Command::Serve(serve)) => return serve::serve(serve, init_game(&args)?),
+        #[cfg(feature = "fetch")]
+        Some(Command::Fetch(fetch)) => {
+            let path = fetch::fetch(fetch)?;
+            if !fetch.run {
+                return Ok(());
+            }
+            args.file = vec![Placement { path, offset: None }];
+        }
+        None => {}
+    }
+    let keys = Keys::new(&config.keys)?;
+    let mut game = init_game(&args)?;
+    game.state.picture = config.picture;
+    if let Some(scale) = args.cell_pixels {
+        game.state.picture.scale = scale;
+    }
+    game.state.picture.check()?;
+    if let Some(path) = &args.screenshot {
+        println!("{}", render::save(&game, path)?);
+        return Ok(());
+    }
+    if args.headless {
+        println!("{}", headless(&mut game)?);
+        if let Some(path) = &args.population_csv {
+            write(path, population_csv(&game.state.history))?;
+        }
+        if args.print_on_exit {
+            print!("{}", game.to_text());
+        }
+        return Ok(());
+    }
+    if let Some(path) = &args.animation {
+        let (generations, every) = (args.generations.unwrap_or(100), args.render_every);
+        let delay = game.state.duration;
+        println!(
+            "{}",
+            raster::animate(&mut game, path, generations, every as usize, delay)?
+        );
+        return Ok(());
+    }
+    if args.once {
+        game.step()?;
+        print!("{}", game.to_text());
+        return Ok(());
+    }
+    let mut frontend = new_frontend(&args, &config, &game)?;
+    fit_terminal(&mut game, &mut frontend, args.on_oversize)?;
+    if let Some(path) = &args.record {
+        game.record(path)?;
+    }
+    let app = App {
+        keys: &keys,
+        notes: Notifications::default(),
+        frontend,
+        editor: Editor::default(),
+        checkpoints: Default::default(),
+        count: None,
+        versus: args
+            .versus
+            .then(|| Versus::new(args.turn_cells, args.turn_generations, args.rounds)),
+        http: args.http.map(Endpoint::start).transpose()?,
+        control: args.control.as_deref().map(Socket::bind).transpose()?,
+        host: args.host.map(Host::start).transpose()?,
+    };
     // setup tui
-    let mut stdout = stdout();
-    execute!(stdout, Hide, EnterAlternateScreen)?;
+    screen::restore_on_exit()?;
+    let mut screen = Screen::new(screen::terminal(), &config.colors)?;
+    execute!(screen, Hide, EnterAlternateScreen, EnableMouseCapture)?;
     // run app logic. error logic is after.
-    let result = main_loop(&mut stdout, &mut game);
+    let result = match RawMode::enable().and_then(|_raw| main_loop(&mut screen, &mut game, app)) {
+        Err(e) if e.is::<Interrupted>() => Ok(()),
+        result => result,
+    };
     // clean up
-    execute!(
-        stdout,
-        MoveTo(0, 0),
-        Clear(ClearType::FromCursorDown),
-        Show,
-        LeaveAlternateScreen
-    )?;
-    stdout.flush()?;
+    execute!(screen, MoveTo(0, 0), Clear(ClearType::FromCursorDown))?;
+    screen::restore_terminal()?;
+    game.finish_recording()?;
+    if let Some(stats) = &game.state.stats {
+        println!("{}", stats);
+    }
+    if let Some(path) = &args.population_csv {
+        write(path, population_csv(&game.state.history))?;
+    }
+    if args.print_on_exit {
+        print!("{}", game.to_text());
+    }
 
     result
 }
 
-fn main_loop(stdout: &mut Stdout, game: &mut Game) -> Result<()> {
-    let mut info: Option<String> = None;
-    loop {
-        queue!(stdout, MoveTo(0, 0), Clear(ClearType::FromCursorDown))?;
-        println!(
-            "{}\n<q>: quit program.\t<a>: auto run.\t<r> regenerate random.\t<e>: switch to editor.\t<s>: save to file.\t<CR>: next.\n{}",
-            game, if let Some(msg) = info { msg } else { "".to_string() }
-        );
-        info = None;
-        match read()? {
-            press!(char 'q') => break,
-            press!(enter) => game.next()?,
-            press!(char 'e') => {
-                execute!(stdout, Show, SetCursorStyle::BlinkingBlock)?;
-                editor_loop(stdout, game)?;
-                execute!(stdout, Show, SetCursorStyle::DefaultUserShape)?;
-            }
-            press!(char 'a') => auto_loop(stdout, game)?,
-            press!(char 'r') => game.random(),
-            press!(char 's') => info = Some(game.save()?),
-            _ => continue,
-        };
-    }
-    Ok(())
+/// how the tui shows the game of `args`. the board a run starts from is no edit.
+fn new_frontend(args: &Args, config: &Config, game: &Game) -> Result<Frontend> {
+    Ok(Frontend {
+        glyphs: config.glyphs,
+        save_dir: match args.output_dir.clone() {
+            Some(dir) => {
+                create_dir_all(&dir)?;
+                dir
+            }
+            None => PathBuf::from("."),
+        },
+        seam: args.seam,
+        rulers: args.rulers,
+        saved: game.state.edits,
+        ..Frontend::default()
+    })
 }
 
-fn auto_loop(stdout: &mut Stdout, game: &mut Game) -> Result<()> {
-    let dur = Duration::from_millis(game.state.duration);
-    let zero_sec = Duration::from_secs(0);
-
-    loop {
-        game.next()?;
-        queue!(stdout, MoveTo(0, 0), Clear(ClearType::FromCursorDown))?;
-        println!(
-            "{}\n<q>: quit auto run.\tduration: {}ms",
-            game, game.state.duration
-        );
+/// check the board fits the terminal before entering the tui, and clamp or scroll it if not.
+fn fit_terminal(game: &mut Game, frontend: &mut Frontend, policy: Oversize) -> Result<()> {
+    let Ok((cols, rows)) = size() else {
+        // not a terminal. nothing to check.
+        return Ok(());
+    };
+    let (rx, ry) = frontend.ruler_size(&game.state);
+    let fit = Size {
+        width: cols.saturating_sub(rx),
+        height: rows.saturating_sub(STATUS_LINES + ry),
+    };
+    let board = game.state.size;
+    if board.width <= fit.width && board.height <= fit.height {
+        return Ok(());
+    }
 
-        sleep(dur);
-        if poll(zero_sec)? {
-            match read()? {
-                press!(char 'q') => break,
-                _ => continue,
+    let policy = match policy {
+        Oversize::Ask => {
+            let mut terminal = screen::terminal();
+            write!(
+                terminal,
+                "board {} does not fit the terminal (up to {}). [c]lamp, [s]croll or [a]bort? ",
+                board, fit
+            )?;
+            terminal.flush()?;
+            let mut answer = String::new();
+            if stdin().is_terminal() {
+                stdin().read_line(&mut answer)?;
+            } else {
+                // the board was piped in. ask the terminal instead.
+                BufReader::new(File::open("/dev/tty")?).read_line(&mut answer)?;
+            }
+            match answer.trim() {
+                "c" | "clamp" => Oversize::Clamp,
+                "s" | "scroll" => Oversize::Scroll,
+                _ => Oversize::Abort,
             }
         }
+        policy => policy,
+    };
+    match policy {
+        Oversize::Clamp => game.crop(fit)?,
+        Oversize::Scroll => frontend.set_view(
+            board,
+            Viewport {
+                x: 0,
+                y: 0,
+                width: fit.width.min(board.width),
+                height: fit.height.min(board.height),
+            },
+        ),
+        _ => bail!("board {} does not fit the terminal (up to {}).", board, fit),
     }
-
     Ok(())
 }
 
-fn editor_loop(stdout: &mut Stdout, game: &mut Game) -> Result<()> {
-    let mut pos = (0u16, 0u16);
-    loop {
-        execute!(stdout, MoveTo(0, 0), Clear(ClearType::FromCursorDown))?;
-        println!(
-            "{}\n`<h>`:left\t`<j>`:down\t`<k>`:up\t`<l>`:right\t`<CR>`: reverse.\t`q`: quit editor mode.\n",
-            game
-        );
-        execute!(stdout, MoveTo(pos.0, pos.1))?;
-        pos = match read()? {
-            press!(char 'q') => break,
-            press!(char 'h') | press!(left) => game.move_to(pos, (-1, 0)).unwrap_or(pos),
-            press!(char 'j') | press!(down) => game.move_to(pos, (0, 1)).unwrap_or(pos),
-            press!(char 'k') | press!(up) => game.move_to(pos, (0, -1)).unwrap_or(pos),
-            press!(char 'l') | press!(right) => game.move_to(pos, (1, 0)).unwrap_or(pos),
-            press!(enter) => {
-                game.set_pos(pos)?;
-                continue;
-            }
-            _ => continue,
-        };
-    }
-    Ok(())
+/// how long the main loop waits for a key before it looks for boards of the simulation thread.
+const TICK: Duration = Duration::from_millis(10);
+
+/// what keys do and what is drawn.
+enum Mode<'s> {
+    Main,
+    Edit,
+    /// the board runs on the simulation thread, which holds it until the run ends.
+    Auto(Box<Run<'s>>),
+}
+
+/// an auto run on the simulation thread.
+struct Run<'s> {
+    control: Sender<Control>,
+    updates: Receiver<Update>,
+    worker: ScopedJoinHandle<'s, Result<()>>,
+    duration: Duration,
+    /// the last board received.
+    board: Snapshot,
+    /// speed of the run, drawn with the hints.
+    status: String,
+    last_frame: Instant,
+    /// smoothed frames per second actually drawn, 0 until a frame was timed.
+    rate: f64,
+}
+
+impl<'s> Run<'s> {
+    fn start<'g>(
+        scope: &'s Scope<'s, '_>,
+        shared: &'s Mutex<&'g mut Game>,
+        frontend: &Frontend,
+    ) -> Self
+    where
+        'g: 's,
+    {
+        let (duration, board) = {
+            let game = lock(shared);
+            (game.state.duration, Snapshot::of(&game, frontend))
+        };
+        let frontend = frontend.clone();
+        let (control, controls) = mpsc::channel();
+        let (send, updates) = mpsc::channel();
+        Self {
+            control,
+            updates,
+            worker: scope.spawn(move || simulate(&mut lock(shared), frontend, controls, send)),
+            duration,
+            board,
+            status: String::new(),
+            last_frame: Instant::now(),
+            rate: 0.0,
+        }
+    }
+
+    /// stop the simulation thread, if it is still running, and wait for it. the inner result is
+    /// how the run went, which is no reason to quit. a panic is.
+    fn finish(self) -> Result<Result<()>> {
+        drop(self.control);
+        self.worker
+            .join()
+            .map_err(|_| anyhow!("the simulation thread panicked."))
+    }
+}
+
+/// what the main loop reacts to.
+enum AppEvent {
+    /// a key, a mouse event or a resize.
+    Terminal(Event),
+    Update(Update),
+    /// the simulation thread ended without a word, i.e. it failed.
+    Finished,
+    /// nothing happened for a while, e.g. a notification expired.
+    Tick,
+    /// a request to `--http`.
+    Http(http::Request),
+    /// a call to `--control`.
+    Rpc(rpc::Call),
+}
+
+/// the board, shared with the simulation thread.
+fn lock<'a, 'g>(shared: &'a Mutex<&'g mut Game>) -> MutexGuard<'a, &'g mut Game> {
+    // a panic on the simulation thread is reported when it is joined.
+    shared.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// everything the tui keeps between events.
+struct App<'k> {
+    keys: &'k Keys,
+    notes: Notifications,
+    /// how the board is shown and where it is saved.
+    frontend: Frontend,
+    editor: Editor,
+    checkpoints: [Option<Checkpoint>; 9],
+    /// numeric prefix typed before a command.
+    count: Option<usize>,
+    /// the game of `--versus` while it is played.
+    versus: Option<Versus>,
+    http: Option<Endpoint>,
+    control: Option<Socket>,
+    /// where spectators get the boards drawn.
+    host: Option<Host>,
+}
+
+fn main_loop(screen: &mut Screen, game: &mut Game, mut app: App) -> Result<()> {
+    let shared = Mutex::new(game);
+    thread::scope(|scope| {
+        // a versus game starts with the first player placing cells.
+        let mut mode = match app.versus {
+            Some(_) => {
+                execute!(screen, Show, SetCursorStyle::BlinkingBlock)?;
+                Mode::Edit
+            }
+            None => Mode::Main,
+        };
+        let mut run = || -> Result<()> {
+            let (mut frame, mut redraw) = (String::new(), true);
+            loop {
+                if redraw {
+                    frame = app.draw(screen, &shared, &mut mode)?;
+                }
+                let event = next_event(&mode, &app)?;
+                // the pointer moving with no button held changes nothing, not even a count typed.
+                redraw = !matches!(
+                    event,
+                    AppEvent::Terminal(Event::Mouse(MouseEvent {
+                        kind: MouseEventKind::Moved,
+                        ..
+                    }))
+                );
+                if !redraw {
+                    continue;
+                }
+                if !app.dispatch(scope, screen, &shared, &mut mode, &frame, event)? {
+                    return Ok(());
+                }
+            }
+        };
+        let result = run();
+        // a run still going ends with the tui.
+        if let Mode::Auto(run) = mode {
+            run.finish()??;
+        }
+        result
+    })
+}
+
+/// wait for a key, a board of the simulation thread, a request or call, or a notification to
+/// expire.
+fn next_event(mode: &Mode, app: &App) -> Result<AppEvent> {
+    if let Some(request) = app.http.as_ref().and_then(Endpoint::try_recv) {
+        return Ok(AppEvent::Http(request));
+    }
+    if let Some(call) = app.control.as_ref().and_then(Socket::try_recv) {
+        return Ok(AppEvent::Rpc(call));
+    }
+    let timeout = match mode {
+        Mode::Auto(run) => match run.updates.try_recv() {
+            Ok(update) => return Ok(AppEvent::Update(update)),
+            Err(TryRecvError::Disconnected) => return Ok(AppEvent::Finished),
+            Err(TryRecvError::Empty) => Some(TICK),
+        },
+        _ => app.notes.next_expiry(),
+    };
+    // requests and calls are looked for every tick.
+    let timeout = if app.http.is_some() || app.control.is_some() {
+        Some(timeout.map_or(TICK, |t| t.min(TICK)))
+    } else {
+        timeout
+    };
+    if let Some(timeout) = timeout {
+        if !poll(timeout)? {
+            return Ok(AppEvent::Tick);
+        }
+    }
+    Ok(AppEvent::Terminal(read()?))
+}
+
+impl App<'_> {
+    /// draw the current mode, returning it as text for prompts drawn under it.
+    fn draw(
+        &mut self,
+        screen: &mut Screen,
+        shared: &Mutex<&mut Game>,
+        mode: &mut Mode,
+    ) -> Result<String> {
+        let keys = self.keys;
+        let log = LogArea::new(self.notes.recent());
+        match mode {
+            Mode::Main => {
+                let game = lock(shared);
+                if let Some(host) = &mut self.host {
+                    host.publish(Replay::of(&game));
+                }
+                let hints = format!(
+                    "{}{}{}",
+                    keys.hints(keys::MAIN),
+                    self.count
+                        .map_or(String::new(), |n| format!("\tcount: {}", n)),
+                    if self.frontend.viewport(&game.state).is_some() {
+                        "\t<arrows>: scroll."
+                    } else {
+                        ""
+                    }
+                );
+                let status = self.frontend.summary(&game);
+                screen.render(|f| {
+                    let board = BoardWidget::new(&game, &self.frontend, 1, &|_| false);
+                    let status = StatusBar {
+                        status: &status,
+                        hints: &hints,
+                    };
+                    ui::view(f.area(), f.buffer_mut(), board, status, log);
+                })?;
+                Ok(format!(
+                    "{}\n{}\n{}",
+                    self.frontend.show(&game, 1, |_| false),
+                    status,
+                    hints
+                ))
+            }
+            Mode::Edit => {
+                let game = lock(shared);
+                if let Some(host) = &mut self.host {
+                    host.publish(Replay::of(&game));
+                }
+                let editor = &self.editor;
+                self.frontend
+                    .scroll_to(&game.state, editor.pos, editor.zoom);
+                let (status, hints) = match &self.versus {
+                    Some(versus) => (
+                        format!(
+                            "{}, {}, {}",
+                            game.state,
+                            editor.cursor(&game),
+                            versus.status()
+                        ),
+                        keys.hints(keys::VERSUS),
+                    ),
+                    None => (
+                        format!("{}, {}", game.state, editor.status(&game)),
+                        keys.hints(editor.mode()),
+                    ),
+                };
+                let line: HashSet<_> = editor
+                    .line
+                    .map(|start| clip::line(start, editor.pos).into_iter().collect())
+                    .unwrap_or_default();
+                let highlight = |p| editor.highlighted(p) || line.contains(&p);
+                let preview = editor
+                    .clipboard
+                    .as_ref()
+                    .filter(|_| editor.placing)
+                    .map(|clip| (clip, editor.pos));
+                screen.render(|f| {
+                    let board = BoardWidget::new(&game, &self.frontend, editor.zoom, &highlight)
+                        .preview(preview);
+                    let cursor = board.clone();
+                    let status = StatusBar {
+                        status: &status,
+                        hints: &hints,
+                    };
+                    let area = ui::view(f.area(), f.buffer_mut(), board, status, log);
+                    f.set_cursor_position(cursor.cell_at(area, editor.pos));
+                })?;
+                Ok(format!(
+                    "{}\n{}\n{}",
+                    self.frontend.show(&game, editor.zoom, highlight),
+                    status,
+                    hints
+                ))
+            }
+            Mode::Auto(run) => {
+                if let Some(host) = &mut self.host {
+                    host.publish(run.board.replay());
+                }
+                let hints = format!("{}{}", keys.hints(keys::AUTO), run.status);
+                screen.render(|f| {
+                    let status = StatusBar {
+                        status: &run.board.summary,
+                        hints: &hints,
+                    };
+                    ui::view(f.area(), f.buffer_mut(), run.board.board(), status, log);
+                })?;
+                Ok(hints)
+            }
+        }
+    }
+
+    /// end the auto run of `mode`, back to the main mode. a failed run is reported.
+    fn stop(&mut self, mode: &mut Mode) -> Result<()> {
+        if let Mode::Auto(run) = std::mem::replace(mode, Mode::Main) {
+            if let Err(e) = run.finish()? {
+                self.notes.error(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// answer a request or call from outside with the board. a run holds it, so it stops while
+    /// the answer is made.
+    fn answer<'s, 'g: 's>(
+        &mut self,
+        scope: &'s Scope<'s, '_>,
+        shared: &'s Mutex<&'g mut Game>,
+        mode: &mut Mode<'s>,
+        respond: impl FnOnce(&mut Game) -> Result<()>,
+    ) -> Result<()> {
+        let running = matches!(mode, Mode::Auto(_));
+        if running {
+            self.stop(mode)?;
+        }
+        respond(&mut lock(shared))?;
+        if running {
+            *mode = Mode::Auto(Box::new(Run::start(scope, shared, &self.frontend)));
+        }
+        Ok(())
+    }
+
+    /// react to `event` in `mode`, switching modes as needed. false to quit.
+    fn dispatch<'s, 'g: 's>(
+        &mut self,
+        scope: &'s Scope<'s, '_>,
+        screen: &mut Screen,
+        shared: &'s Mutex<&'g mut Game>,
+        mode: &mut Mode<'s>,
+        frame: &str,
+        event: AppEvent,
+    ) -> Result<bool> {
+        match (event, &mut *mode) {
+            (AppEvent::Tick, _) => {}
+            // the next draw starts over on the new size.
+            (AppEvent::Terminal(Event::Resize(..)), _) => {}
+            (
+                AppEvent::Update(Update::Frame {
+                    board,
+                    render_every,
+                }),
+                Mode::Auto(run),
+            ) => {
+                run.board = board;
+                let elapsed = run.last_frame.elapsed().as_secs_f64();
+                run.last_frame = Instant::now();
+                if elapsed > 0.0 {
+                    // the first frame sets the rate, which a duration of 0 can't.
+                    run.rate = match run.rate {
+                        0.0 => 1.0 / elapsed,
+                        rate => rate * 0.8 + 0.2 / elapsed,
+                    };
+                }
+                run.status = format!(
+                    "\tduration: {}, draw every {} generations, {:.1} fps ({:.0} generations/s)",
+                    show_duration(run.duration),
+                    render_every,
+                    run.rate,
+                    run.rate * render_every as f64
+                );
+            }
+            (AppEvent::Update(Update::Stopped(note)), Mode::Auto(_)) => {
+                self.notes.push(note);
+                self.stop(mode)?;
+                self.next_round(screen, shared, mode)?;
+            }
+            (AppEvent::Finished, Mode::Auto(_)) => {
+                self.stop(mode)?;
+                self.next_round(screen, shared, mode)?;
+            }
+            (AppEvent::Terminal(event), Mode::Main) => {
+                return self.on_main(scope, screen, shared, mode, frame, event)
+            }
+            (AppEvent::Terminal(event), Mode::Edit) if self.versus.is_some() => {
+                self.on_versus(scope, screen, shared, mode, event)?
+            }
+            (AppEvent::Terminal(event), Mode::Edit) => {
+                self.on_edit(screen, &mut lock(shared), mode, frame, event)?
+            }
+            (AppEvent::Terminal(event), Mode::Auto(run)) => {
+                match self.keys.action(&event, keys::AUTO) {
+                    Some(Action::Quit) => {
+                        self.stop(mode)?;
+                        self.next_round(screen, shared, mode)?;
+                    }
+                    Some(Action::Help) => {
+                        // the help needs the board, so the run stops while it is shown.
+                        self.stop(mode)?;
+                        help_loop(screen, &lock(shared), &self.frontend, self.keys)?;
+                        *mode = Mode::Auto(Box::new(Run::start(scope, shared, &self.frontend)));
+                    }
+                    Some(Action::Command) => {
+                        // e.g. `:rule` switches the rule of the running board.
+                        self.stop(mode)?;
+                        let mut game = lock(shared);
+                        command_line(
+                            screen,
+                            frame,
+                            &mut game,
+                            &mut self.editor,
+                            &mut self.frontend,
+                            &mut self.notes,
+                        )?;
+                        drop(game);
+                        *mode = Mode::Auto(Box::new(Run::start(scope, shared, &self.frontend)));
+                    }
+                    Some(Action::Turbo) => {
+                        // it is gone if it stopped just now.
+                        let _ = run.control.send(Control::Turbo);
+                    }
+                    Some(Action::Outline) => {
+                        // the run draws with its own copy.
+                        self.frontend.outline = !self.frontend.outline;
+                        let _ = run.control.send(Control::Outline);
+                    }
+                    _ => {}
+                }
+            }
+            (AppEvent::Http(request), _) => {
+                self.answer(scope, shared, mode, |game| http::respond(game, request))?
+            }
+            (AppEvent::Rpc(call), _) => {
+                self.answer(scope, shared, mode, |game| rpc::respond(game, call))?
+            }
+            // news of a run which already ended.
+            (AppEvent::Update(_) | AppEvent::Finished, _) => {}
+        }
+        Ok(true)
+    }
+
+    fn on_main<'s, 'g: 's>(
+        &mut self,
+        scope: &'s Scope<'s, '_>,
+        screen: &mut Screen,
+        shared: &'s Mutex<&'g mut Game>,
+        mode: &mut Mode<'s>,
+        frame: &str,
+        event: Event,
+    ) -> Result<bool> {
+        // any key but a digit ends the count.
+        let prefix = if matches!(event, press!(char '0'..='9')) {
+            self.count
+        } else {
+            self.count.take()
+        };
+        let action = self.keys.action(&event, keys::MAIN);
+        if let Some(Action::Auto) = action {
+            *mode = Mode::Auto(Box::new(Run::start(scope, shared, &self.frontend)));
+            return Ok(true);
+        }
+        let mut game = lock(shared);
+        let game: &mut Game = &mut game;
+        let notes = &mut self.notes;
+        let frontend = &mut self.frontend;
+        match (action, event) {
+            (Some(Action::Quit), _) => {
+                if !frontend.edited(game) {
+                    return Ok(false);
+                }
+                let question = "the board has unsaved edits. [s]ave, [q]uit anyway or cancel?";
+                match choose(screen, frame, question, &['s', 'q'])? {
+                    Some('q') => return Ok(false),
+                    Some(_) => {
+                        save_prompt(screen, frame, game, frontend, notes)?;
+                        // a save cancelled or failed keeps the game open.
+                        if !frontend.edited(game) {
+                            return Ok(false);
+                        }
+                    }
+                    None => {}
+                }
+            }
+            (Some(Action::Step), _) => game.step()?,
+            (Some(Action::Skip), _) => {
+                let start = Instant::now();
+                let done = game.skip(prefix.unwrap_or(100))?;
+                notes.push(format!(
+                    "skipped {} generations in {:.1?}",
+                    done,
+                    start.elapsed()
+                ));
+            }
+            (Some(Action::Edit), _) => {
+                execute!(screen, Show, SetCursorStyle::BlinkingBlock)?;
+                *mode = Mode::Edit;
+            }
+            (Some(Action::Random), _) => match game.random() {
+                Ok(seed) => notes.push(format!("new random board, seed {}", seed)),
+                Err(e) => notes.error(e),
+            },
+            (Some(Action::Save), _) => save_prompt(screen, frame, game, frontend, notes)?,
+            (Some(Action::Clear), _) => clear_prompt(screen, frame, game, notes)?,
+            (Some(Action::Screenshot), _) => {
+                let path = frontend.save_dir.join(Game::save_name());
+                match render::save(game, &path.with_extension("png")) {
+                    Ok(message) => notes.push(message),
+                    Err(e) => notes.error(e),
+                }
+            }
+            (Some(Action::Open), _) => {
+                if let Some(path) = prompt(screen, frame, "open: ", "")? {
+                    if !path.trim().is_empty() {
+                        let cmd = Cmd::Load(path.trim().to_string());
+                        match run_command(game, &mut self.editor, frontend, cmd) {
+                            Ok(message) => notes.push(message),
+                            Err(e) => notes.error(e),
+                        }
+                    }
+                }
+            }
+            (Some(Action::Mark), _) => {
+                if let Some(slot) = read_slot(screen, frame, "store in slot")? {
+                    self.checkpoints[slot - 1] = Some(game.checkpoint());
+                    notes.push(format!(
+                        "stored generation {} in slot {}",
+                        game.state.time, slot
+                    ));
+                }
+            }
+            (Some(Action::Recall), _) => {
+                if let Some(slot) = read_slot(screen, frame, "restore slot")? {
+                    match &self.checkpoints[slot - 1] {
+                        Some(checkpoint) => {
+                            game.restore(checkpoint);
+                            if game.check_pos(self.editor.pos).is_err() {
+                                self.editor.pos = (0, 0);
+                            }
+                            notes.push(format!(
+                                "restored generation {} from slot {}",
+                                checkpoint.time, slot
+                            ));
+                        }
+                        None => notes.push(format!("slot {} is empty", slot)),
+                    }
+                }
+            }
+            (Some(Action::SavePopulation), _) => match game.save_population(&frontend.save_dir) {
+                Ok(message) => notes.push(message),
+                Err(e) => notes.error(e),
+            },
+            (Some(Action::Messages), _) => history_loop(screen, notes)?,
+            (Some(Action::Statistics), _) => stats_loop(screen, game)?,
+            (Some(Action::Outline), _) => frontend.outline = !frontend.outline,
+            (Some(Action::Help), _) => help_loop(screen, game, frontend, self.keys)?,
+            (Some(Action::Command), _) => {
+                command_line(screen, frame, game, &mut self.editor, frontend, notes)?
+            }
+            (_, press!(char c @ '0'..='9')) => {
+                let digit = c.to_digit(10).unwrap_or(0) as usize;
+                self.count = Some(prefix.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+            }
+            (_, press!(left)) => frontend.scroll(&game.state, (-8, 0)),
+            (_, press!(right)) => frontend.scroll(&game.state, (8, 0)),
+            (_, press!(up)) => frontend.scroll(&game.state, (0, -4)),
+            (_, press!(down)) => frontend.scroll(&game.state, (0, 4)),
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    /// the players of a versus game place their cells. once both did, the board runs.
+    fn on_versus<'s, 'g: 's>(
+        &mut self,
+        scope: &'s Scope<'s, '_>,
+        screen: &mut Screen,
+        shared: &'s Mutex<&'g mut Game>,
+        mode: &mut Mode<'s>,
+        event: Event,
+    ) -> Result<()> {
+        let Some(versus) = &mut self.versus else {
+            return Ok(());
+        };
+        let mut game = lock(shared);
+        let pos = self.editor.pos;
+        let moved = |amount| game.move_to(pos, amount).unwrap_or(pos);
+        match (self.keys.action(&event, keys::VERSUS), event) {
+            (Some(Action::Left), _) | (_, press!(left)) => self.editor.pos = moved((-1, 0)),
+            (Some(Action::Down), _) | (_, press!(down)) => self.editor.pos = moved((0, 1)),
+            (Some(Action::Up), _) | (_, press!(up)) => self.editor.pos = moved((0, -1)),
+            (Some(Action::Right), _) | (_, press!(right)) => self.editor.pos = moved((1, 0)),
+            (Some(Action::Toggle), _) => match versus.place(&mut game, pos) {
+                Ok(Turn::Placing) => {}
+                Ok(Turn::Next) => self.notes.push(format!("{}.", versus.status())),
+                Ok(Turn::Run) => {
+                    drop(game);
+                    execute!(screen, Show, SetCursorStyle::DefaultUserShape)?;
+                    *mode = Mode::Auto(Box::new(Run::start(scope, shared, &self.frontend)));
+                }
+                Err(e) => self.notes.error(e),
+            },
+            (Some(Action::Help), _) => help_loop(screen, &game, &self.frontend, self.keys)?,
+            (Some(Action::Quit), _) => {
+                game.state.limit = None;
+                self.versus = None;
+                self.notes.push("gave up the versus game.".to_string());
+                execute!(screen, Show, SetCursorStyle::DefaultUserShape)?;
+                *mode = Mode::Main;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// score the round of the versus game which just ran. unless it was the last, the players
+    /// place cells again.
+    fn next_round(
+        &mut self,
+        screen: &mut Screen,
+        shared: &Mutex<&mut Game>,
+        mode: &mut Mode,
+    ) -> Result<()> {
+        let Some(versus) = &mut self.versus else {
+            return Ok(());
+        };
+        let (message, over) = versus.end_round(&mut lock(shared));
+        self.notes.push(message);
+        if over {
+            self.versus = None;
+        } else {
+            execute!(screen, Show, SetCursorStyle::BlinkingBlock)?;
+            *mode = Mode::Edit;
+        }
+        Ok(())
+    }
+
+    fn on_edit(
+        &mut self,
+        screen: &mut Screen,
+        game: &mut Game,
+        mode: &mut Mode,
+        frame: &str,
+        event: Event,
+    ) -> Result<()> {
+        let editor = &mut self.editor;
+        let pos = editor.pos;
+        // any key but a digit ends the count. `0` is a motion unless it continues a count.
+        let count = editor.count.take();
+        if let press!(char c @ '0'..='9') = event {
+            if c != '0' || count.is_some() {
+                let digit = c.to_digit(10).unwrap_or(0);
+                editor.count = Some(count.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+                return Ok(());
+            }
+        }
+        let top = std::mem::take(&mut editor.top);
+        let n = i64::from(count.unwrap_or(1));
+        let (width, height) = (game.state.size.width, game.state.size.height);
+        // row N of `G` and `gg`, the last row if N is too big.
+        let row = |n: u32| u16::try_from(n).unwrap_or(u16::MAX).min(height - 1);
+        editor.pos = match (
+            self.keys.action(&event, editor.mode()),
+            event,
+            editor.anchor,
+        ) {
+            (Some(Action::Left), ..) | (_, press!(left), _) => step(game, pos, (-n, 0)),
+            (Some(Action::Down), ..) | (_, press!(down), _) => step(game, pos, (0, n)),
+            (Some(Action::Up), ..) | (_, press!(up), _) => step(game, pos, (0, -n)),
+            (Some(Action::Right), ..) | (_, press!(right), _) => step(game, pos, (n, 0)),
+            (Some(Action::FarLeft), ..) => step(game, pos, (-n * FAR, 0)),
+            (Some(Action::FarDown), ..) => step(game, pos, (0, n * FAR)),
+            (Some(Action::FarUp), ..) => step(game, pos, (0, -n * FAR)),
+            (Some(Action::FarRight), ..) => step(game, pos, (n * FAR, 0)),
+            (Some(Action::RowStart), ..) => (0, pos.1),
+            (Some(Action::RowEnd), ..) => (width - 1, pos.1),
+            (Some(Action::Top), ..) if !top => {
+                // wait for the second `g`, keeping the count.
+                editor.top = true;
+                editor.count = count;
+                return Ok(());
+            }
+            (Some(Action::Top), ..) => (pos.0, count.map_or(0, row)),
+            (Some(Action::Bottom), ..) => (pos.0, count.map_or(height - 1, row)),
+            (Some(Action::Help), ..) => return help_loop(screen, game, &self.frontend, self.keys),
+            (Some(Action::Yank), _, Some(anchor)) => {
+                editor.clipboard = Some(game.copy_rect(anchor, pos)?);
+                editor.anchor = None;
+                return Ok(());
+            }
+            (Some(Action::Cut), _, Some(anchor)) => {
+                editor.clipboard = Some(game.copy_rect(anchor, pos)?);
+                game.fill_rect(anchor, pos, 0)?;
+                editor.anchor = None;
+                return Ok(());
+            }
+            (Some(Action::Fill), _, Some(anchor)) => {
+                game.fill_rect(anchor, pos, editor.brush)?;
+                editor.anchor = None;
+                return Ok(());
+            }
+            (Some(Action::Scatter), _, Some(anchor)) => {
+                let density = f64::from(count.unwrap_or(50).min(100)) / 100.0;
+                game.fill_random(anchor, pos, density, &mut thread_rng())?;
+                editor.anchor = None;
+                return Ok(());
+            }
+            (Some(Action::Count), _, Some(anchor)) => {
+                let ((x0, y0), (x1, y1)) = clip::rect(anchor, pos);
+                let (live, bbox) = game.live_in(anchor, pos);
+                let mut note = format!(
+                    "{} live cells in the {}x{} selection",
+                    live,
+                    x1 - x0 + 1,
+                    y1 - y0 + 1
+                );
+                if let Some(((l, t), (r, b))) = bbox {
+                    note.push_str(&format!(
+                        ", box {}:{} to {}:{} ({}x{})",
+                        l,
+                        t,
+                        r,
+                        b,
+                        r - l + 1,
+                        b - t + 1
+                    ));
+                }
+                self.notes.push(note);
+                return Ok(());
+            }
+            (Some(Action::Erase), _, Some(anchor)) => {
+                game.fill_rect(anchor, pos, 0)?;
+                editor.anchor = None;
+                return Ok(());
+            }
+            (Some(Action::Cancel), ..) | (_, press!(KeyCode::Esc), Some(_)) => {
+                editor.anchor = None;
+                editor.placing = false;
+                return Ok(());
+            }
+            (_, press!(KeyCode::Esc), _) if editor.placing || editor.line.is_some() => {
+                editor.placing = false;
+                editor.line = None;
+                return Ok(());
+            }
+            (_, _, Some(_)) => return Ok(()),
+            (
+                _,
+                Event::Mouse(MouseEvent {
+                    kind:
+                        kind @ (MouseEventKind::Down(MouseButton::Left)
+                        | MouseEventKind::Drag(MouseButton::Left)),
+                    column,
+                    row,
+                    ..
+                }),
+                None,
+            ) => {
+                let Some(to) =
+                    self.frontend
+                        .screen_to_board(&game.state, (column, row), editor.zoom)
+                else {
+                    return Ok(());
+                };
+                if let MouseEventKind::Down(_) = kind {
+                    game.set_pos(to)?;
+                    editor.drag = game.game[game.index(to)?];
+                } else {
+                    game.set_cell(to, editor.drag)?;
+                }
+                editor.mirror_cell(game, to)?;
+                editor.pos = to;
+                return Ok(());
+            }
+            (Some(Action::Quit), ..) => {
+                execute!(screen, Show, SetCursorStyle::DefaultUserShape)?;
+                // the board may run on, which the kept generations don't know about.
+                editor.history.clear();
+                *mode = Mode::Main;
+                return Ok(());
+            }
+            (Some(Action::Forward), ..) => {
+                if editor.history.len() == EDITOR_HISTORY {
+                    editor.history.remove(0);
+                }
+                editor.history.push(game.checkpoint());
+                game.step()?;
+                // stepped in the editor, the board is a new one to save.
+                game.state.edits += 1;
+                return Ok(());
+            }
+            (Some(Action::Back), ..) => {
+                match editor.history.pop() {
+                    Some(checkpoint) => {
+                        game.restore(&checkpoint);
+                        if game.check_pos(editor.pos).is_err() {
+                            editor.pos = (0, 0);
+                        }
+                    }
+                    None => self
+                        .notes
+                        .push("no earlier generation stepped in the editor"),
+                }
+                return Ok(());
+            }
+            (Some(Action::Command), ..) => {
+                return command_line(
+                    screen,
+                    frame,
+                    game,
+                    editor,
+                    &mut self.frontend,
+                    &mut self.notes,
+                );
+            }
+            (Some(Action::Clear), ..) => {
+                return clear_prompt(screen, frame, game, &mut self.notes);
+            }
+            (Some(Action::Zoom), ..) => {
+                editor.zoom = editor.zoom % 3 + 1;
+                return Ok(());
+            }
+            (Some(Action::Select), ..) => {
+                editor.anchor = Some(pos);
+                return Ok(());
+            }
+            (Some(Action::Paint), ..) => {
+                editor.painting = !editor.painting;
+                pos
+            }
+            (Some(Action::NextBrush), ..) => {
+                let states = game.state_names().len() as u8;
+                editor.brush = (editor.brush + 1) % states;
+                return Ok(());
+            }
+            (Some(Action::PrevBrush), ..) => {
+                let states = game.state_names().len() as u8;
+                editor.brush = (editor.brush + states - 1) % states;
+                return Ok(());
+            }
+            (Some(Action::Rotate), ..) => {
+                editor.clipboard = editor.clipboard.as_ref().map(Clip::rotate);
+                return Ok(());
+            }
+            (Some(Action::FlipHorizontal), ..) => {
+                editor.clipboard = editor.clipboard.as_ref().map(Clip::flip_horizontal);
+                return Ok(());
+            }
+            (Some(Action::FlipVertical), ..) => {
+                editor.clipboard = editor.clipboard.as_ref().map(Clip::flip_vertical);
+                return Ok(());
+            }
+            (Some(Action::Paste), ..) => {
+                editor.placing = editor.clipboard.is_some();
+                return Ok(());
+            }
+            (Some(Action::Place), ..) => {
+                if let Some(clip) = &editor.clipboard {
+                    game.paste(pos, clip)?;
+                }
+                editor.placing = false;
+                return Ok(());
+            }
+            (Some(Action::Toggle), ..) => {
+                game.set_pos(pos)?;
+                return editor.mirror_cell(game, pos);
+            }
+            (Some(Action::Cycle), ..) => {
+                let states = game.state_names().len() as u8;
+                let state = game.state_at(game.index(pos)?);
+                game.set_state(pos, (state + 1) % states)?;
+                return editor.mirror_cell(game, pos);
+            }
+            (Some(Action::Line), ..) => {
+                match editor.line.take() {
+                    None => editor.line = Some(pos),
+                    Some(start) => {
+                        for cell in clip::line(start, pos) {
+                            game.set_state(cell, editor.brush)?;
+                            editor.mirror_cell(game, cell)?;
+                        }
+                    }
+                }
+                return Ok(());
+            }
+            (Some(Action::Mirror), ..) => {
+                editor.mirror = editor.mirror.next();
+                return Ok(());
+            }
+            _ => return Ok(()),
+        };
+        if editor.painting && editor.anchor.is_none() {
+            game.set_state(editor.pos, editor.brush)?;
+            editor.mirror_cell(game, editor.pos)?;
+        }
+        Ok(())
+    }
+}
+
+/// show `body` in a full screen panel until any key is pressed.
+fn panel_loop(screen: &mut Screen, title: &str, body: &str) -> Result<()> {
+    screen.render(|f| f.render_widget(Panel { title, body }, f.area()))?;
+    wait_for_key()
+}
+
+fn wait_for_key() -> Result<()> {
+    loop {
+        if let press!(_) = read()? {
+            return Ok(());
+        }
+    }
+}
+
+fn stats_loop(screen: &mut Screen, game: &Game) -> Result<()> {
+    let body = match &game.state.stats {
+        Some(stats) => stats.to_string(),
+        None => "statistics are off. restart with --stats to track them.".to_string(),
+    };
+    panel_loop(screen, "statistics. <any key>: back.", &body)
+}
+
+fn history_loop(screen: &mut Screen, notes: &Notifications) -> Result<()> {
+    let log = LogArea::new(notes.history());
+    screen.render(|f| f.render_widget(log.boxed("messages. <any key>: back."), f.area()))?;
+    wait_for_key()
+}
+
+const COMMANDS: &str = "\
+commands
+  :save FILE            save the board. FILE.rle is written as rle
+  :load NAME|FILE       replace the board with a built-in pattern, an apgcode or a file
+  :read NAME|FILE [center] [wrap]
+                        stamp a pattern or a file with its top left, or center, at the cursor
+  :yank NAME|FILE       put a pattern or a file in the clipboard and place it with the editor
+  :rule B3/S23          switch the rule, keeping the board
+  :resize W:H [corner]  grow or crop the board around its center, or its top left corner
+  :trim [N]             cut the board down to its live cells and N dead cells around them
+  :shift DX,DY          move every cell, wrapping around the edges
+  :rotate               rotate the board 90° clockwise
+  :flip h|v             mirror the board left and right, or top and bottom
+  :goto X,Y             move the editor cursor and the view to a cell
+  :clear [reset]        kill every cell, and count generations from 0 again
+  :name TEXT            name written into saves
+  :export FILE          write a picture of the board, FILE.png or FILE.svg";
+
+/// read a line typed under `frame`, after `label`. `None` if cancelled.
+/// tab completes the last word as a path.
+fn prompt(screen: &mut Screen, frame: &str, label: &str, initial: &str) -> Result<Option<String>> {
+    let mut line = initial.to_string();
+    let mut candidates = Vec::new();
+    loop {
+        screen.draw(&format!(
+            "{}\n{}{}\n{}",
+            frame,
+            label,
+            line,
+            candidates.join("  ")
+        ))?;
+        let event = read()?;
+        candidates.clear();
+        match event {
+            press!(KeyCode::Tab) => (line, candidates) = complete::complete(&line),
+            press!(enter) => return Ok(Some(line)),
+            press!(KeyCode::Esc) => return Ok(None),
+            press!(KeyCode::Backspace) => {
+                // backspace on an empty line leaves it, like vim.
+                if line.pop().is_none() {
+                    return Ok(None);
+                }
+            }
+            press!(char c) => line.push(c),
+            _ => {}
+        }
+    }
+}
+
+/// read a checkpoint slot, 1 to 9, under `frame`. `None` for any other key.
+fn read_slot(screen: &mut Screen, frame: &str, label: &str) -> Result<Option<usize>> {
+    screen.draw(&format!("{}\n{} (1-9): ", frame, label))?;
+    loop {
+        match read()? {
+            press!(char c @ '1'..='9') => return Ok(c.to_digit(10).map(|d| d as usize)),
+            Event::Key(KeyEvent {
+                kind: KeyEventKind::Press,
+                ..
+            }) => return Ok(None),
+            _ => {}
+        }
+    }
+}
+
+/// ask a yes/no question under `frame`. anything but `y` is no.
+fn confirm(screen: &mut Screen, frame: &str, question: &str) -> Result<bool> {
+    screen.draw(&format!("{}\n{} [y/N] ", frame, question))?;
+    loop {
+        match read()? {
+            press!(char 'y' | 'Y') => return Ok(true),
+            Event::Key(KeyEvent {
+                kind: KeyEventKind::Press,
+                ..
+            }) => return Ok(false),
+            _ => {}
+        }
+    }
+}
+
+/// ask a question under `frame`, answered by one of `answers`. `None` for any other key.
+fn choose(
+    screen: &mut Screen,
+    frame: &str,
+    question: &str,
+    answers: &[char],
+) -> Result<Option<char>> {
+    screen.draw(&format!("{}\n{} ", frame, question))?;
+    loop {
+        match read()? {
+            press!(char c) if answers.contains(&c.to_ascii_lowercase()) => {
+                return Ok(Some(c.to_ascii_lowercase()))
+            }
+            Event::Key(KeyEvent {
+                kind: KeyEventKind::Press,
+                ..
+            }) => return Ok(None),
+            _ => {}
+        }
+    }
+}
+
+/// ask where to save the board, relative to the save directory, and confirm overwriting.
+fn save_prompt(
+    screen: &mut Screen,
+    frame: &str,
+    game: &mut Game,
+    frontend: &mut Frontend,
+    notes: &mut Notifications,
+) -> Result<()> {
+    let Some(name) = prompt(screen, frame, "save as: ", &Game::save_name())? else {
+        return Ok(());
+    };
+    if name.trim().is_empty() {
+        return Ok(());
+    }
+    let path = frontend.save_dir.join(name.trim());
+    if path.exists()
+        && !confirm(
+            screen,
+            frame,
+            &format!("{} exists. overwrite?", path.display()),
+        )?
+    {
+        return Ok(());
+    }
+    match game.save_to(&path) {
+        Ok(message) => {
+            frontend.mark_saved(game);
+            notes.push(message)
+        }
+        Err(e) => notes.error(e),
+    }
+    Ok(())
+}
+
+/// ask before killing every cell, and whether to count generations from 0 again.
+fn clear_prompt(
+    screen: &mut Screen,
+    frame: &str,
+    game: &mut Game,
+    notes: &mut Notifications,
+) -> Result<()> {
+    let question = "kill every cell? [y]es, [r]estart from generation 0 too, or cancel";
+    if let Some(answer) = choose(screen, frame, question, &['y', 'r'])? {
+        notes.push(clear(game, answer == 'r'));
+    }
+    Ok(())
+}
+
+/// kill every cell, and count generations from 0 again if `reset`.
+fn clear(game: &mut Game, reset: bool) -> String {
+    game.clear();
+    if reset {
+        game.reset_time();
+        "cleared the board, back at generation 0".to_string()
+    } else {
+        "cleared the board".to_string()
+    }
+}
+
+/// read and run a `:` command. failures are shown as notifications instead of quitting.
+fn command_line(
+    screen: &mut Screen,
+    frame: &str,
+    game: &mut Game,
+    editor: &mut Editor,
+    frontend: &mut Frontend,
+    notes: &mut Notifications,
+) -> Result<()> {
+    let Some(line) = prompt(screen, frame, ":", "")? else {
+        return Ok(());
+    };
+    if line.trim().is_empty() {
+        return Ok(());
+    }
+    match line
+        .parse::<Cmd>()
+        .and_then(|cmd| run_command(game, editor, frontend, cmd))
+    {
+        Ok(message) => notes.push(message),
+        Err(e) => notes.error(e),
+    }
+    Ok(())
+}
+
+/// run a `:` command, returning the message to show.
+fn run_command(
+    game: &mut Game,
+    editor: &mut Editor,
+    frontend: &mut Frontend,
+    cmd: Cmd,
+) -> Result<String> {
+    Ok(match cmd {
+        Cmd::Save(path) => {
+            let message = game.save_to(&path)?;
+            frontend.mark_saved(game);
+            message
+        }
+        Cmd::Load(what) => {
+            game.load(&what)?;
+            frontend.mark_saved(game);
+            if game.check_pos(editor.pos).is_err() {
+                editor.pos = (0, 0);
+            }
+            fit_terminal(game, frontend, Oversize::Scroll)?;
+            editor.fit_brush(game);
+            format!("loaded {}", what)
+        }
+        Cmd::Read(what, anchor, wrap) => {
+            let size = game.stamp(&what, editor.pos, anchor, wrap)?;
+            format!(
+                "read {} ({}) at {}:{}",
+                what, size, editor.pos.0, editor.pos.1
+            )
+        }
+        Cmd::Yank(what) => {
+            let clip = Game::clip_of(&what)?;
+            let message = format!("placing {} ({}x{})", what, clip.width, clip.height);
+            editor.clipboard = Some(clip);
+            editor.placing = true;
+            message
+        }
+        Cmd::Rule(rule) => {
+            game.set_rule(rule);
+            editor.fit_brush(game);
+            format!("rule is {}", rule)
+        }
+        Cmd::Resize(size, anchor) => {
+            game.resize(size, anchor)?;
+            if game.check_pos(editor.pos).is_err() {
+                editor.pos = (0, 0);
+            }
+            fit_terminal(game, frontend, Oversize::Scroll)?;
+            format!("resized the board to {}", size)
+        }
+        Cmd::Trim(padding) => {
+            game.trim(padding)?;
+            if game.check_pos(editor.pos).is_err() {
+                editor.pos = (0, 0);
+            }
+            fit_terminal(game, frontend, Oversize::Scroll)?;
+            format!("trimmed the board to {}", game.state.size)
+        }
+        Cmd::Transform(transform) => {
+            game.transform(transform);
+            if game.check_pos(editor.pos).is_err() {
+                editor.pos = (0, 0);
+            }
+            fit_terminal(game, frontend, Oversize::Scroll)?;
+            match transform {
+                Transform::Shift(dx, dy) => format!("shifted the board by {},{}", dx, dy),
+                Transform::Rotate => "rotated the board".to_string(),
+                Transform::FlipHorizontal | Transform::FlipVertical => {
+                    "mirrored the board".to_string()
+                }
+            }
+        }
+        Cmd::Goto(pos) => {
+            game.check_pos(pos)?;
+            editor.pos = pos;
+            frontend.scroll_to(&game.state, pos, editor.zoom);
+            format!("moved to {}:{}", pos.0, pos.1)
+        }
+        Cmd::Clear(reset) => clear(game, reset),
+        Cmd::Export(path) => render::save(game, &path)?,
+        Cmd::Name(name) => {
+            let message = format!("saves are named {}", name);
+            game.state.name = Some(name);
+            message
+        }
+    })
+}
+
+/// full screen list of every key binding and the current settings.
+fn help_loop(screen: &mut Screen, game: &Game, frontend: &Frontend, keys: &Keys) -> Result<()> {
+    let body = format!(
+        "main\n{}\n  arrows    scroll a large board\n\nauto run\n{}\n\neditor\n{}\n  arrows    move                              mouse     click and drag to draw\n\neditor, placing the clipboard\n{}\n  Esc       cancel\n\neditor, selecting\n{}\n  Esc       cancel\n\n{}\n\nsettings\n{}",
+        keys.help(keys::MAIN),
+        keys.help(keys::AUTO),
+        keys.help(keys::EDITOR),
+        keys.help(keys::PLACING),
+        keys.help(keys::SELECTING),
+        COMMANDS,
+        frontend
+            .settings(&game.state)
+            .lines()
+            .map(|line| format!("  {}", line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+    panel_loop(screen, "help. <any key>: back.", &body)
+}
+
+/// messages from the simulation thread while auto running.
+enum Update {
+    /// a board to draw.
+    Frame { board: Snapshot, render_every: u64 },
+    /// the run stopped by itself, and why.
+    Stopped(String),
+}
+
+/// messages to the simulation thread.
+enum Control {
+    Turbo,
+    Outline,
+}
+
+/// why the run stops before the next step, if `game` is at the generation limit.
+fn limit_reached(game: &Game) -> Option<String> {
+    let limit = game.state.limit.filter(|&l| game.state.time >= l)?;
+    Some(format!(
+        "reached the generation limit {}: population {}",
+        limit, game.state.population
+    ))
+}
+
+/// why the run stops after a step, if `game` died out or stabilized and stops by itself then.
+fn settled(game: &Game) -> Option<String> {
+    if !game.state.auto_stop {
+        return None;
+    }
+    if game.state.population == 0 {
+        return Some(format!("died out at generation {}", game.state.time));
+    }
+    let (at, period) = game.state.cycle?;
+    Some(format!(
+        "stabilized at generation {} (period {})",
+        at, period
+    ))
+}
+
+/// step `game` as fast as it goes until it stops by itself, writing frames on the way and the
+/// starting board as the first.
+fn headless(game: &mut Game) -> Result<String> {
+    ensure!(
+        game.state.limit.is_some() || game.state.auto_stop,
+        "--headless needs --generations, or a run which stops by itself."
+    );
+    game.dump_frame()?;
+    loop {
+        if let Some(note) = limit_reached(game) {
+            return Ok(note);
+        }
+        game.step()?;
+        game.dump_frame()?;
+        if let Some(note) = settled(game) {
+            return Ok(note);
+        }
+    }
+}
+
+/// step `game` until it stops by itself or `controls` hangs up. boards to draw go to `updates`.
+fn simulate(
+    game: &mut Game,
+    mut frontend: Frontend,
+    controls: Receiver<Control>,
+    updates: Sender<Update>,
+) -> Result<()> {
+    let stop = |note: String| {
+        // nobody listens if the run was quit at the same time.
+        let _ = updates.send(Update::Stopped(note));
+        Ok(())
+    };
+    for step in 1.. {
+        if let Some(note) = limit_reached(game) {
+            return stop(note);
+        }
+        game.step()?;
+        game.dump_frame()?;
+        if let Some(note) = settled(game) {
+            return stop(note);
+        }
+        let wait = if step % game.state.render_every == 0 {
+            let frame = Update::Frame {
+                board: Snapshot::of(game, &frontend),
+                render_every: game.state.render_every,
+            };
+            if updates.send(frame).is_err() {
+                return Ok(());
+            }
+            game.state.duration
+        } else {
+            Duration::ZERO
+        };
+        // wait for the next generation, but take controls meanwhile.
+        let deadline = Instant::now() + wait;
+        loop {
+            match controls.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(Control::Turbo) => {
+                    // 1 -> 10 -> 100 -> 1
+                    game.state.render_every = match game.state.render_every {
+                        1..=9 => 10,
+                        10..=99 => 100,
+                        _ => 1,
+                    };
+                }
+                Ok(Control::Outline) => frontend.outline = !frontend.outline,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// cells the far motions, `HJKL`, move.
+const FAR: i64 = 5;
+/// generations the editor keeps to step back to.
+const EDITOR_HISTORY: usize = 100;
+
+/// `pos` moved by `amount`, wrapping around the edges any number of times.
+fn step(game: &Game, pos: (u16, u16), amount: (i64, i64)) -> (u16, u16) {
+    let size = game.state.size;
+    (
+        (i64::from(pos.0) + amount.0).rem_euclid(size.width.into()) as u16,
+        (i64::from(pos.1) + amount.1).rem_euclid(size.height.into()) as u16,
+    )
+}
+
+/// editor state kept between editor sessions.
+#[derive(Debug)]
+struct Editor {
+    pos: (u16, u16),
+    zoom: u16,
+    /// the count typed before a motion, e.g. 10 of `10l`.
+    count: Option<u32>,
+    /// the first key of `gg` was pressed.
+    top: bool,
+    /// the other corner of the visual selection, if selecting.
+    anchor: Option<(u16, u16)>,
+    clipboard: Option<Clip>,
+    /// the clipboard is drawn at the cursor, to be pasted there.
+    placing: bool,
+    /// where the line being drawn starts. it ends at the cursor.
+    line: Option<(u16, u16)>,
+    /// boards before each generation stepped in the editor, the latest last.
+    history: Vec<Checkpoint>,
+    /// index of the state picked from the palette.
+    brush: u8,
+    /// paint `brush` on every cell the cursor moves onto.
+    painting: bool,
+    /// state a mouse drag paints, decided by the click starting it.
+    drag: bool,
+    /// cells drawn are drawn at their mirror images too.
+    mirror: Mirror,
+}
+
+impl Default for Editor {
+    fn default() -> Self {
+        Self {
+            pos: (0, 0),
+            zoom: 1,
+            count: None,
+            top: false,
+            anchor: None,
+            clipboard: None,
+            placing: false,
+            line: None,
+            history: Vec::new(),
+            brush: 1,
+            painting: false,
+            drag: true,
+            mirror: Mirror::Off,
+        }
+    }
+}
+
+impl Editor {
+    fn highlighted(&self, pos: (u16, u16)) -> bool {
+        (self.zoom > 1 && pos == self.pos)
+            || self
+                .anchor
+                .is_some_and(|anchor| clip::contains(anchor, self.pos, pos))
+    }
+
+    /// give the mirror images of `pos` the state of the cell at `pos`.
+    fn mirror_cell(&self, game: &mut Game, pos: (u16, u16)) -> Result<()> {
+        let state = game.state_at(game.index(pos)?);
+        for image in self.mirror.images(game.state.size, pos) {
+            game.set_state(image, state)?;
+        }
+        Ok(())
+    }
+
+    /// keep the brush one of the states of the rule, after it changed.
+    fn fit_brush(&mut self, game: &Game) {
+        self.brush = self.brush.min(game.state.rule.states() - 1);
+    }
+
+    /// key bindings in use: selecting or not.
+    fn mode(&self) -> &'static [Action] {
+        match self.anchor {
+            Some(_) => keys::SELECTING,
+            None if self.placing => keys::PLACING,
+            None => keys::EDITOR,
+        }
+    }
+
+    /// where the cursor is and the state of the cell under it.
+    fn cursor(&self, game: &Game) -> String {
+        let (x, y) = self.pos;
+        let state = game.index(self.pos).map_or(0, |i| game.state_at(i));
+        format!(
+            "cursor {}:{} {}",
+            x,
+            y,
+            game.state_names()[usize::from(state)]
+        )
+    }
+
+    fn status(&self, game: &Game) -> String {
+        let mut status = format!("{}, zoom x{}", self.cursor(game), self.zoom);
+        if let Some(count) = self.count {
+            status.push_str(&format!(", count: {}", count));
+        }
+        if self.mirror != Mirror::Off {
+            status.push_str(&format!(", mirror: {}", self.mirror));
+        }
+        if self.anchor.is_some() {
+            status.push_str(", -- VISUAL --");
+        }
+        if self.placing {
+            status.push_str(", -- PLACE --");
+        }
+        if let Some((x, y)) = self.line {
+            status.push_str(&format!(", -- LINE from {}:{} --", x, y));
+        }
+        status.push_str(&format!(
+            ", brush: {}{}",
+            game.state_names()[self.brush as usize],
+            if self.painting { " (painting)" } else { "" }
+        ));
+        if let Some(clip) = &self.clipboard {
+            status.push_str(&format!(", clipboard {}x{}", clip.width, clip.height));
+        }
+        status
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    fn args(size: &str) -> Args {
+        Args::parse_from(["life-game", "--size", size])
+    }
+
     #[test]
-    fn test_of_test() -> Result<()> {
-        let args = Args {
-            size: Size::default(),
-            max: false,
-            file: None,
-            duration: 100,
-            random: false,
+    fn parse_duration() -> Result<()> {
+        assert_eq!(duration_arg("100")?, Duration::from_millis(100));
+        assert_eq!(duration_arg("250ms")?, Duration::from_millis(250));
+        assert_eq!(duration_arg("1s")?, Duration::from_secs(1));
+        assert_eq!(duration_arg("0.5s")?, Duration::from_millis(500));
+        assert!(duration_arg("fast").is_err());
+        assert_eq!(fps("20")?, Duration::from_millis(50));
+        assert!(fps("0").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn command_line_wins_over_config() -> Result<()> {
+        let config: Config = toml::from_str(
+            "size = \"40:20\"\nduration = \"1s\"\nrule = \"B36/S23\"\nsave_dir = \"a\"",
+        )?;
+        let matches = Args::command().try_get_matches_from([
+            "life-game",
+            "-d",
+            "50ms",
+            "--output-dir",
+            "b",
+            "--neighborhood",
+            "hex",
+        ])?;
+        let mut args = Args::from_arg_matches(&matches)?;
+        args.merge(&config, &matches)?;
+        assert_eq!((args.size.width, args.size.height), (40, 20));
+        assert_eq!(args.duration, Duration::from_millis(50));
+        assert_eq!(args.rule.to_string(), "B36/S23H");
+        assert_eq!(args.output_dir, Some(PathBuf::from("b")));
+        Ok(())
+    }
+
+    #[test]
+    fn wireworld_mode() -> Result<()> {
+        let matches = Args::command().try_get_matches_from(["life-game", "--ca", "wireworld"])?;
+        let mut args = Args::from_arg_matches(&matches)?;
+        args.merge(&Config::default(), &matches)?;
+        assert!(args.rule.is_wireworld());
+        let mut game = Game::blank("2:1".parse()?)?;
+        game.state.rule = args.rule;
+        assert_eq!(game.state_names()[3], "conductor");
+        game.set_state((0, 0), 3)?;
+        assert_eq!(game.show_board(), "=-\n");
+        let matches = Args::command().try_get_matches_from([
+            "life-game",
+            "--ca",
+            "wireworld",
+            "--rule",
+            "B3/S23",
+        ])?;
+        let mut args = Args::from_arg_matches(&matches)?;
+        assert!(args.merge(&Config::default(), &matches).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn seeded_noise() -> Result<()> {
+        let noisy = || -> Result<Game> {
+            let args =
+                Args::parse_from(["life-game", "-s", "16:16", "--noise", "0.1", "--seed", "3"]);
+            let mut game = init_game(&args)?;
+            for _ in 0..5 {
+                game.step()?;
+            }
+            Ok(game)
         };
-        let mut game = Game::init(&args)?;
-        println!("{}", game);
-        game.set_pos((15, 20))?;
-        println!("{}", game);
+        let game = noisy()?;
+        // a blank board only gets cells from the noise.
+        assert!(game.state.population > 0);
+        assert_eq!(game.game, noisy()?.game);
+        assert!(Args::try_parse_from(["life-game", "--noise", "1.5"]).is_err());
+        let matches = Args::command().try_get_matches_from(["life-game", "--seed", "3"])?;
+        let mut args = Args::from_arg_matches(&matches)?;
+        assert!(args.merge(&Config::default(), &matches).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn rule_script() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let path = tmp.path().join("brain.script");
+        write(
+            &path,
+            "# brian's brain
+state == 1 ? 2 : state == 0 && count == 2
+",
+        )?;
+        let args = Args::parse_from([
+            "life-game",
+            "-s",
+            "4:4",
+            "--rule",
+            "B/S/C3",
+            "--rule-script",
+            path.to_str().unwrap(),
+        ]);
+        let mut game = init_game(&args)?;
+        game.set_cell((1, 1), true)?;
+        game.set_cell((2, 1), true)?;
+        game.step()?;
+        // the pair starts dying, and cells are born above and below it.
+        assert_eq!(game.show_board(), "-@@-\n-oo-\n-@@-\n----\n");
         Ok(())
     }
 
-    macro_rules! inner {
-        ( $v:ident) => {};
-        ( $v:ident, $e:literal) => {{$v.push($e != 0);}};
-        ( $v:ident, $e:literal, $($a:literal),* ) => {{
-            $v.push($e != 0);
-            inner!($v, $($a),* );
-        }};
+    #[test]
+    fn elementary_mode() -> Result<()> {
+        let args = Args::parse_from(["life-game", "-s", "5:3", "--rule", "90"]);
+        let mut game = init_game(&args)?;
+        assert_eq!(game.show_board(), "-----\n-----\n--@--\n");
+        game.step()?;
+        game.step()?;
+        assert_eq!(game.show_board(), "--@--\n-@-@-\n@---@\n");
+        let matches = Args::command().try_get_matches_from(["life-game", "--ca", "elementary"])?;
+        let mut args = Args::from_arg_matches(&matches)?;
+        args.merge(&Config::default(), &matches)?;
+        assert_eq!(args.rule.wolfram(), Some(110));
+        Ok(())
     }
 
-    macro_rules! board_init {
-        ($($e:literal),*) => {{
-            let mut v = Vec::new();
-            inner!(v, $($e),*);
-            v
-        }};
+    #[test]
+    fn clamp_fast_durations() -> Result<()> {
+        let clamped = Args::parse_from(["life-game", "-d", "1ms"]);
+        assert_eq!(new_state(&clamped)?.duration, MIN_DURATION);
+        let unlimited = Args::parse_from(["life-game", "-d", "0ms", "--unlimited-speed"]);
+        assert_eq!(new_state(&unlimited)?.duration, Duration::ZERO);
+        Ok(())
     }
 
     #[test]
-    fn blinker_test() -> Result<()> {
-        let args = Args {
-            size: Size {
-                width: 5,
-                height: 5,
-            },
-            max: false,
-            file: None,
-            duration: 100,
-            random: false,
+    fn simulate_on_a_thread() -> Result<()> {
+        let mut game = init_game(&Args::parse_from([
+            "life-game",
+            "-s",
+            "5:5",
+            "--pattern",
+            "blinker",
+            "-d",
+            "15ms",
+            "--no-auto-stop",
+            "--generations",
+            "4",
+        ]))?;
+        let (control, controls) = mpsc::channel();
+        let (send, updates) = mpsc::channel();
+        control.send(Control::Turbo)?;
+        thread::scope(|s| {
+            s.spawn(|| simulate(&mut game, Frontend::default(), controls, send))
+                .join()
+                .unwrap()
+        })?;
+        let updates: Vec<_> = updates.iter().collect();
+        // the first board is drawn before turbo is taken, none after it.
+        assert!(matches!(
+            updates[..],
+            [
+                Update::Frame {
+                    render_every: 1,
+                    ..
+                },
+                Update::Stopped(_)
+            ]
+        ));
+        assert_eq!(game.state.time, 4);
+        assert_eq!(game.state.render_every, 10);
+
+        // stops as soon as the other side hangs up.
+        game.state.limit = None;
+        let (control, controls) = mpsc::channel();
+        let (send, _updates) = mpsc::channel();
+        drop(control);
+        simulate(&mut game, Frontend::default(), controls, send)?;
+        assert_eq!(game.state.time, 5);
+        Ok(())
+    }
+
+    #[test]
+    fn failed_run_is_reported() -> Result<()> {
+        let mut game = init_game(&args("5:5"))?;
+        game.state.dump = Some(FrameDump {
+            dir: PathBuf::from("/nonexistent/life-game"),
+            every: 1,
+            format: DumpFormat::Native,
+        });
+        let keys = Keys::new(&Default::default())?;
+        let mut app = App {
+            keys: &keys,
+            notes: Notifications::default(),
+            frontend: Frontend::default(),
+            editor: Editor::default(),
+            checkpoints: Default::default(),
+            count: None,
+            versus: None,
+            http: None,
+            control: None,
+            host: None,
         };
-        let mut game = Game::init(&args)?;
-        game.set_pos((1, 2))?;
-        game.set_pos((2, 2))?;
-        game.set_pos((3, 2))?;
-        println!("{}", game);
-        game.next()?;
-        println!("{}", game);
-        game.next()?;
-        println!("{}", game);
+        let shared = Mutex::new(&mut game);
+        thread::scope(|scope| {
+            let mut mode = Mode::Auto(Box::new(Run::start(scope, &shared, &app.frontend)));
+            if let Mode::Auto(run) = &mode {
+                // the first frame can't be written, so the thread hangs up.
+                while run.updates.recv().is_ok() {}
+            }
+            app.stop(&mut mode)
+        })?;
+        let note = app.notes.recent().next().unwrap();
+        assert!(note.error);
+        Ok(())
+    }
+
+    #[test]
+    fn headless_dumps_frames() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        let mut game = init_game(&args("8:8"))?;
+        game.load("blinker")?;
+        game.state.auto_stop = false;
+        // it would never end.
+        assert!(headless(&mut game).is_err());
+        game.state.limit = Some(4);
+        game.state.dump = Some(FrameDump {
+            dir: dir.to_path_buf(),
+            every: 2,
+            format: DumpFormat::Png,
+        });
         assert_eq!(
-            game.game,
-            board_init!(0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0)
+            headless(&mut game)?,
+            "reached the generation limit 4: population 3"
         );
+        let mut frames: Vec<_> = std::fs::read_dir(dir)?
+            .map(|entry| Ok(entry?.file_name()))
+            .collect::<Result<_>>()?;
+        frames.sort();
+        assert_eq!(frames, ["00000000.png", "00000002.png", "00000004.png"]);
         Ok(())
     }
 
     #[test]
-    fn get_pt() -> Result<()> {
-        let args = Args {
-            size: Size {
-                width: 3,
-                height: 3,
-            },
-            max: false,
-            file: None,
-            duration: 100,
-            random: false,
+    fn run_commands() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        let mut game = init_game(&args("10:10"))?;
+        let (mut editor, mut frontend) = (Editor::default(), Frontend::default());
+        let mut run = |game: &mut Game, cmd: &str| {
+            run_command(game, &mut editor, &mut frontend, cmd.parse()?)
+        };
+
+        run(&mut game, "load glider")?;
+        assert_eq!(game.state.population, 5);
+        let saved = dir.join("glider.txt");
+        run(&mut game, &format!("save {}", saved.display()))?;
+        game.step()?;
+        run(&mut game, "clear")?;
+        assert_eq!((game.state.population, game.state.time), (0, 1));
+        run(&mut game, "clear reset")?;
+        assert_eq!((game.state.time, game.state.history.len()), (0, 1));
+        run(&mut game, &format!("load {}", saved.display()))?;
+        assert_eq!(game.state.population, 5);
+
+        // nothing is born or survives.
+        run(&mut game, "rule B/S")?;
+        game.step()?;
+        assert_eq!(game.state.population, 0);
+        assert!(run(&mut game, "goto 10,0").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn cursor_readout() -> Result<()> {
+        let mut game = init_game(&args("10:10"))?;
+        let mut editor = Editor::default();
+        game.set_pos((3, 4))?;
+        assert_eq!(editor.cursor(&game), "cursor 0:0 dead");
+        editor.pos = (3, 4);
+        assert!(editor
+            .status(&game)
+            .starts_with("cursor 3:4 alive, zoom x1"));
+        Ok(())
+    }
+
+    #[test]
+    fn step_wraps_around() -> Result<()> {
+        let game = Game::blank("10:4".parse()?)?;
+        assert_eq!(step(&game, (8, 1), (3, 0)), (1, 1));
+        assert_eq!(step(&game, (0, 1), (-25, -FAR)), (5, 0));
+        assert_eq!(step(&game, (0, 0), (0, i64::from(u32::MAX) * FAR)), (0, 3));
+        Ok(())
+    }
+
+    #[test]
+    fn embed_file() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        let path = dir.join("glider.cells");
+        write(&path, ".O.\n..O\nOOO\n")?;
+        let init = |extra: &[&str]| -> Result<Game> {
+            let mut argv = vec!["life-game", "-f", path.to_str().unwrap()];
+            argv.extend(extra);
+            let matches = Args::command().try_get_matches_from(argv)?;
+            let mut args = Args::from_arg_matches(&matches)?;
+            args.merge(&Config::default(), &matches)?;
+            init_game(&args)
         };
-        let game = Game::init(&args)?;
-        // 0 1 2 0 1 2
-        // 3 4 5 3 4 5
-        // 6 7 8 6 7 8
-        // 0 1 2 0 1 2
-        // 3 4 5 3 4 5
-        // 6 7 8 6 7 8
-
-        //                           cu ru rm rd cd ld lm lu
-        assert_eq!(game.get_pt(4)?, [1, 2, 5, 8, 7, 6, 3, 0,]);
-        assert_eq!(game.get_pt(0)?, [6, 7, 1, 4, 3, 5, 2, 8,]);
-        assert_eq!(game.get_pt(1)?, [7, 8, 2, 5, 4, 3, 0, 6,]);
-        assert_eq!(game.get_pt(2)?, [8, 6, 0, 3, 5, 4, 1, 7,]);
-        assert_eq!(game.get_pt(3)?, [0, 1, 4, 7, 6, 8, 5, 2,]);
-        assert_eq!(game.get_pt(4)?, [1, 2, 5, 8, 7, 6, 3, 0,]);
-        assert_eq!(game.get_pt(5)?, [2, 0, 3, 6, 8, 7, 4, 1,]);
-        assert_eq!(game.get_pt(6)?, [3, 4, 7, 1, 0, 2, 8, 5,]);
-        assert_eq!(game.get_pt(7)?, [4, 5, 8, 2, 1, 0, 6, 3,]);
-        assert_eq!(game.get_pt(8)?, [5, 3, 6, 0, 2, 1, 7, 4,]);
+        let alive =
+            |game: &Game| -> Vec<usize> { (0..game.state.len).filter(|&i| game.game[i]).collect() };
+
+        let game = init(&[])?;
+        assert_eq!((game.state.size.width, game.state.size.height), (3, 3));
+        let game = init(&["-s", "5:5"])?;
+        assert_eq!(alive(&game), [7, 13, 16, 17, 18]);
+        let game = init(&["-s", "5:5", "--offset", "2:0"])?;
+        assert_eq!(alive(&game), [3, 9, 12, 13, 14]);
+        assert!(init(&["-s", "5:5", "--offset", "3:0"]).is_err());
+        let file = format!("{}@3,3", path.display());
+        let game = init(&["-s", "6:6", "-f", &file])?;
+        assert_eq!(alive(&game), [8, 15, 19, 20, 21, 22, 29, 33, 34, 35]);
+        Ok(())
+    }
+
+    #[test]
+    fn given_rule_wins_over_saved() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        let path = dir.join("highlife.txt");
+        let mut game = Game::blank("4:4".parse()?)?;
+        game.state.rule = "B36/S23".parse()?;
+        game.save_to(&path)?;
+        let load = |extra: &[&str]| -> Result<Game> {
+            let mut argv = vec!["life-game", "-f", path.to_str().unwrap()];
+            argv.extend(extra);
+            let matches = Args::command().try_get_matches_from(argv)?;
+            let mut args = Args::from_arg_matches(&matches)?;
+            args.merge(&Config::default(), &matches)?;
+            init_game(&args)
+        };
+        assert_eq!(load(&[])?.state.rule.to_string(), "B36/S23");
+        // the default rule given on purpose still wins.
+        assert_eq!(load(&["--rule", "B3/S23"])?.state.rule, Rule::default());
+        Ok(())
+    }
+
+    #[test]
+    fn saves_keep_metadata() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        let mut game = init_game(&Args::parse_from([
+            "life-game",
+            "-s",
+            "8:8",
+            "-r",
+            "--seed",
+            "7",
+            "--rule",
+            "B36/S23",
+        ]))?;
+        let board = game.game.clone();
+        game.skip(3)?;
+        game.state.name = Some("seven".into());
+        let path = dir.join("seven.txt");
+        game.save_to(&path)?;
 
+        let mut loaded = init_game(&Args::parse_from(["life-game", "-s", "8:8"]))?;
+        loaded.load(path.to_str().unwrap())?;
+        assert_eq!(loaded.game, game.game);
+        assert_eq!(loaded.state.time, game.state.time);
+        assert_eq!(loaded.state.rule.to_string(), "B36/S23");
+        assert_eq!(loaded.state.seed, Some(7));
+        assert_eq!(loaded.state.name.as_deref(), Some("seven"));
+        // the same seed gives the same board.
+        let again = init_game(&Args::parse_from([
+            "life-game",
+            "-s",
+            "8:8",
+            "-r",
+            "--seed",
+            "7",
+        ]))?;
+        assert_eq!(again.game, board);
         Ok(())
     }
 }
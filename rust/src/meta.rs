@@ -0,0 +1,107 @@
+//! what a save says about its board besides the cells, e.g. its rule and generation.
+
+use crate::rule::Rule;
+use anyhow::{Context, Result};
+use std::fmt::Display;
+
+/// first line of a native save carrying metadata.
+const MAGIC: &str = "#life-game v2";
+
+/// how a saved board was produced. written as `#key=value` lines above the board.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct Metadata {
+    /// the rule the board runs under.
+    pub rule: Option<Rule>,
+    /// generation of the board.
+    pub generation: Option<usize>,
+    /// seed of the random board it started from.
+    pub seed: Option<u64>,
+    /// name of the pattern.
+    pub name: Option<String>,
+    /// free-form lines, e.g. `#C` lines of rle files.
+    pub comments: Vec<String>,
+}
+
+impl Metadata {
+    /// split the metadata lines off the top of a save. files without them give empty metadata.
+    pub fn split(text: &str) -> Result<(Self, &str)> {
+        let mut meta = Self::default();
+        let Some(mut rest) = text.strip_prefix(MAGIC).and_then(|t| t.strip_prefix('\n')) else {
+            return Ok((meta, text));
+        };
+        while let Some((line, next)) = rest.split_once('\n') {
+            if line.starts_with("#delta ") {
+                break;
+            }
+            let Some((key, value)) = line.strip_prefix('#').and_then(|l| l.split_once('=')) else {
+                break;
+            };
+            let context = || format!("bad metadata: {}", line);
+            match key {
+                "rule" => meta.rule = Some(value.parse().with_context(context)?),
+                "generation" => meta.generation = Some(value.parse().with_context(context)?),
+                "seed" => meta.seed = Some(value.parse().with_context(context)?),
+                "name" => meta.name = Some(value.to_string()),
+                "comment" => meta.comments.push(value.to_string()),
+                // written by a newer version.
+                _ => {}
+            }
+            rest = next;
+        }
+        Ok((meta, rest))
+    }
+}
+
+impl Display for Metadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        writeln!(f, "{}", MAGIC)?;
+        if let Some(rule) = self.rule {
+            writeln!(f, "#rule={}", rule)?;
+        }
+        if let Some(generation) = self.generation {
+            writeln!(f, "#generation={}", generation)?;
+        }
+        if let Some(seed) = self.seed {
+            writeln!(f, "#seed={}", seed)?;
+        }
+        if let Some(name) = &self.name {
+            // one line per key.
+            writeln!(f, "#name={}", name.replace('\n', " "))?;
+        }
+        for comment in &self.comments {
+            writeln!(f, "#comment={}", comment.replace('\n', " "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn metadata_round_trip() -> Result<()> {
+        let meta = Metadata {
+            rule: Some("B36/S23".parse()?),
+            generation: Some(42),
+            seed: Some(7),
+            name: Some("two gliders = one block".into()),
+            comments: vec!["found by hand".into()],
+        };
+        let text = format!("{}3:1\n010", meta);
+        let (read, board) = Metadata::split(&text)?;
+        assert_eq!(read, meta);
+        assert_eq!(board, "3:1\n010");
+
+        let (read, board) = Metadata::split("3:1\n010")?;
+        assert_eq!(read, Metadata::default());
+        assert_eq!(board, "3:1\n010");
+        assert!(Metadata::split("#life-game v2\n#generation=x\n3:1\n010").is_err());
+        Ok(())
+    }
+}
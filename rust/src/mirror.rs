@@ -0,0 +1,97 @@
+//! the editor's symmetric drawing: every cell drawn is drawn at its images around the middle
+//! of the board too.
+
+use life_game::Size;
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum Mirror {
+    #[default]
+    Off,
+    /// mirrored left to right.
+    Horizontal,
+    /// mirrored top to bottom.
+    Vertical,
+    /// mirrored left to right and top to bottom.
+    Both,
+    /// turned by quarter turns. a cell whose images fall off a board which isn't square has none.
+    Rotate,
+}
+
+impl Mirror {
+    /// the next one, for the key cycling through them.
+    pub(crate) fn next(self) -> Self {
+        match self {
+            Self::Off => Self::Horizontal,
+            Self::Horizontal => Self::Vertical,
+            Self::Vertical => Self::Both,
+            Self::Both => Self::Rotate,
+            Self::Rotate => Self::Off,
+        }
+    }
+
+    /// the other cells `pos` is mirrored to on a board of `size`, without `pos` itself.
+    pub(crate) fn images(self, size: Size, pos: (u16, u16)) -> Vec<(u16, u16)> {
+        // twice the distance from the middle, so that the middle of an even board is whole.
+        let (w, h) = (i32::from(size.width) - 1, i32::from(size.height) - 1);
+        let (x, y) = (2 * i32::from(pos.0) - w, 2 * i32::from(pos.1) - h);
+        let images: &[(i32, i32)] = match self {
+            Self::Off => &[],
+            Self::Horizontal => &[(-x, y)],
+            Self::Vertical => &[(x, -y)],
+            Self::Both => &[(-x, y), (x, -y), (-x, -y)],
+            Self::Rotate => &[(-y, x), (-x, -y), (y, -x)],
+        };
+        let mut cells: Vec<(u16, u16)> = images
+            .iter()
+            .filter_map(|&(x, y)| {
+                let (x, y) = (x + w, y + h);
+                // a quarter turn of a board with an odd and an even side misses the cells.
+                let on_board = x % 2 == 0 && y % 2 == 0 && (0..=2 * w).contains(&x);
+                (on_board && (0..=2 * h).contains(&y)).then_some(((x / 2) as u16, (y / 2) as u16))
+            })
+            .filter(|&image| image != pos)
+            .collect();
+        cells.sort_unstable();
+        cells.dedup();
+        cells
+    }
+}
+
+impl Display for Mirror {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Off => "off",
+            Self::Horizontal => "left to right",
+            Self::Vertical => "top to bottom",
+            Self::Both => "both ways",
+            Self::Rotate => "quarter turns",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn mirror_images() -> anyhow::Result<()> {
+        let size: Size = "5:4".parse()?;
+        assert_eq!(Mirror::Off.images(size, (1, 0)), []);
+        assert_eq!(Mirror::Horizontal.images(size, (1, 0)), [(3, 0)]);
+        assert_eq!(Mirror::Vertical.images(size, (1, 0)), [(1, 3)]);
+        assert_eq!(Mirror::Both.images(size, (1, 0)), [(1, 3), (3, 0), (3, 3)]);
+        // the middle column is its own image.
+        assert_eq!(Mirror::Both.images(size, (2, 1)), [(2, 2)]);
+        // only the half turn fits on a board which isn't square.
+        assert_eq!(Mirror::Rotate.images(size, (1, 0)), [(3, 3)]);
+        let square: Size = "4:4".parse()?;
+        assert_eq!(
+            Mirror::Rotate.images(square, (1, 0)),
+            [(0, 2), (2, 3), (3, 1)]
+        );
+        assert_eq!(Mirror::Rotate.next().next(), Mirror::Horizontal);
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use chrono::{DateTime, Local};
+use std::{
+    collections::VecDeque,
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+/// how long a notification stays bright in the log area.
+const SHOW_FOR: Duration = Duration::from_secs(4);
+/// how many of the last notifications the log area shows.
+const RECENT: usize = 3;
+/// how many notifications are kept for the scrollback.
+const CAPACITY: usize = 100;
+
+#[derive(Debug)]
+pub(crate) struct Notice {
+    at: DateTime<Local>,
+    posted: Instant,
+    message: String,
+    /// something failed, rather than just happened.
+    pub(crate) error: bool,
+}
+
+impl Notice {
+    /// posted less than `SHOW_FOR` ago.
+    pub(crate) fn fresh(&self) -> bool {
+        self.posted.elapsed() < SHOW_FOR
+    }
+}
+
+impl Display for Notice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "[{}] ", self.at.format("%H:%M:%S"))?;
+        if self.error {
+            write!(f, "error: ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Notifications {
+    log: VecDeque<Notice>,
+}
+
+impl Notifications {
+    pub(crate) fn push(&mut self, message: impl Into<String>) {
+        self.post(message.into(), false);
+    }
+
+    /// post a failure, highlighted in the log.
+    pub(crate) fn error(&mut self, error: impl Display) {
+        self.post(error.to_string(), true);
+    }
+
+    fn post(&mut self, message: String, error: bool) {
+        if self.log.len() == CAPACITY {
+            self.log.pop_front();
+        }
+        self.log.push_back(Notice {
+            at: Local::now(),
+            posted: Instant::now(),
+            message,
+            error,
+        });
+    }
+
+    /// the last few notifications, shown in the log area, oldest first.
+    pub(crate) fn recent(&self) -> impl Iterator<Item = &Notice> {
+        self.log.iter().skip(self.log.len().saturating_sub(RECENT))
+    }
+
+    /// time until the next recent notification stops being fresh.
+    pub(crate) fn next_expiry(&self) -> Option<Duration> {
+        self.recent()
+            .filter(|n| n.fresh())
+            .map(|n| SHOW_FOR.saturating_sub(n.posted.elapsed()))
+            .min()
+    }
+
+    /// every kept notification, oldest first.
+    pub(crate) fn history(&self) -> impl Iterator<Item = &Notice> {
+        self.log.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn keeps_recent_history() {
+        let mut notes = Notifications::default();
+        for i in 0..CAPACITY + 5 {
+            notes.push(format!("message {}", i));
+        }
+        assert_eq!(notes.history().count(), CAPACITY);
+        assert!(notes.history().next().unwrap().message.ends_with(" 5"));
+        assert_eq!(notes.recent().count(), RECENT);
+        assert!(notes.next_expiry().unwrap() <= SHOW_FOR);
+    }
+
+    #[test]
+    fn errors_are_marked() {
+        let mut notes = Notifications::default();
+        notes.push("saved");
+        notes.error(anyhow::anyhow!("no such file"));
+        let recent: Vec<_> = notes.recent().collect();
+        assert!(!recent[0].error && recent[1].error);
+        assert!(recent[1].to_string().ends_with("] error: no such file"));
+        assert!(recent[1].fresh());
+    }
+}
@@ -0,0 +1,177 @@
+//! the built-in patterns, loaded by name with `--pattern` and `:load`.
+
+use crate::Size;
+use anyhow::{anyhow, ensure, Error, Result};
+use std::str::FromStr;
+
+/// built-in pattern written in plaintext notation (`O`: alive, `.`: dead).
+#[derive(Debug, Clone, Copy)]
+pub struct Pattern {
+    /// what `--pattern` and `:load` call it.
+    pub name: &'static str,
+    rows: &'static [&'static str],
+}
+
+/// every built-in pattern.
+pub static PATTERNS: &[Pattern] = &[
+    Pattern {
+        name: "block",
+        rows: &["OO", "OO"],
+    },
+    Pattern {
+        name: "beehive",
+        rows: &[".OO.", "O..O", ".OO."],
+    },
+    Pattern {
+        name: "blinker",
+        rows: &["OOO"],
+    },
+    Pattern {
+        name: "toad",
+        rows: &[".OOO", "OOO."],
+    },
+    Pattern {
+        name: "beacon",
+        rows: &["OO..", "OO..", "..OO", "..OO"],
+    },
+    Pattern {
+        name: "pulsar",
+        rows: &[
+            "..OOO...OOO..",
+            ".............",
+            "O....O.O....O",
+            "O....O.O....O",
+            "O....O.O....O",
+            "..OOO...OOO..",
+            ".............",
+            "..OOO...OOO..",
+            "O....O.O....O",
+            "O....O.O....O",
+            "O....O.O....O",
+            ".............",
+            "..OOO...OOO..",
+        ],
+    },
+    Pattern {
+        name: "pentadecathlon",
+        rows: &["..O....O..", "OO.OOOO.OO", "..O....O.."],
+    },
+    Pattern {
+        name: "glider",
+        rows: &[".O.", "..O", "OOO"],
+    },
+    Pattern {
+        name: "lwss",
+        rows: &[".O..O", "O....", "O...O", "OOOO."],
+    },
+    Pattern {
+        name: "r-pentomino",
+        rows: &[".OO", "OO.", ".O."],
+    },
+    Pattern {
+        name: "acorn",
+        rows: &[".O.....", "...O...", "OO..OOO"],
+    },
+    Pattern {
+        name: "diehard",
+        rows: &["......O.", "OO......", ".O...OOO"],
+    },
+    Pattern {
+        name: "gosper-gun",
+        rows: &[
+            "........................O...........",
+            "......................O.O...........",
+            "............OO......OO............OO",
+            "...........O...O....OO............OO",
+            "OO........O.....O...OO..............",
+            "OO........O...O.OO....O.O...........",
+            "..........O.....O.......O...........",
+            "...........O...O....................",
+            "............OO......................",
+        ],
+    },
+];
+
+impl FromStr for Pattern {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        PATTERNS
+            .iter()
+            .find(|p| p.name == s)
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = PATTERNS.iter().map(|p| p.name).collect();
+                anyhow!("Unknown pattern!.note:: available: {}.", names.join(", "))
+            })
+    }
+}
+
+impl Pattern {
+    /// columns of its longest row.
+    pub fn width(&self) -> u16 {
+        self.rows.iter().map(|r| r.len()).max().unwrap_or(0) as u16
+    }
+
+    /// rows of the pattern.
+    pub fn height(&self) -> u16 {
+        self.rows.len() as u16
+    }
+
+    /// positions of alive cells, relative to the top-left corner.
+    pub fn cells(&self) -> impl Iterator<Item = (u16, u16)> + '_ {
+        self.rows.iter().enumerate().flat_map(|(y, row)| {
+            row.chars()
+                .enumerate()
+                .filter(|&(_, c)| c == 'O')
+                .map(move |(x, _)| (x as u16, y as u16))
+        })
+    }
+
+    /// board of `size` with the pattern placed on its center.
+    pub fn centered(&self, size: Size) -> Result<Vec<bool>> {
+        ensure!(
+            self.width() <= size.width && self.height() <= size.height,
+            "pattern {} needs {}x{} cells, but board is {}.",
+            self.name,
+            self.width(),
+            self.height(),
+            size
+        );
+        let (ox, oy) = (
+            (size.width - self.width()) / 2,
+            (size.height - self.height()) / 2,
+        );
+        let mut board = vec![false; size.width as usize * size.height as usize];
+        for (x, y) in self.cells() {
+            board[(oy + y) as usize * size.width as usize + (ox + x) as usize] = true;
+        }
+        Ok(board)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn centered_pattern() -> Result<()> {
+        let glider: Pattern = "glider".parse()?;
+        let board = glider.centered(Size {
+            width: 5,
+            height: 5,
+        })?;
+        let alive: Vec<usize> = (0..25).filter(|&i| board[i]).collect();
+        assert_eq!(alive, [7, 13, 16, 17, 18]);
+        assert!("no-such-pattern".parse::<Pattern>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn gosper_gun_is_rectangular() -> Result<()> {
+        let gun: Pattern = "gosper-gun".parse()?;
+        assert_eq!((gun.width(), gun.height()), (36, 9));
+        assert_eq!(gun.cells().count(), 36);
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+//! the `replay` subcommand, which plays a recording made with `--record` back.
+
+use crate::{
+    config::Config,
+    duration_arg,
+    frontend::Frontend,
+    screen::{self, read, Interrupted, RawMode, Screen},
+    ui::{view, LogArea, Snapshot, StatusBar},
+};
+use anyhow::{Context, Result};
+use clap::Args;
+use crossterm::{
+    cursor::{Hide, MoveTo},
+    event::{poll, Event, KeyCode, KeyEvent, KeyEventKind},
+    execute,
+    terminal::{Clear, ClearType, EnterAlternateScreen},
+};
+use life_game::{
+    replay::{Event as Recorded, Replay},
+    Game,
+};
+use std::{fs::read_to_string, path::PathBuf, slice::Iter, time::Duration};
+
+/// keys of the player, which can't be remapped.
+const HINTS: &str =
+    "<q>: quit. <space>: pause. <.>: next generation while paused. <+>/<->: faster/slower.";
+
+#[derive(Args, Debug)]
+pub(crate) struct ReplayArgs {
+    file: PathBuf,
+    #[arg(
+        short,
+        long,
+        default_value = "100ms",
+        value_parser = duration_arg,
+        help = "between generations. + and - halve and double it while playing."
+    )]
+    duration: Duration,
+    #[arg(
+        long,
+        value_name = "N",
+        help = "play up to generation N without drawing."
+    )]
+    from: Option<usize>,
+    #[arg(
+        long,
+        help = "play to the end without the tui and print the last board in the native format."
+    )]
+    print: bool,
+}
+
+/// the events of a recording, played a generation at a time.
+struct Player<'a> {
+    events: Iter<'a, Recorded>,
+    /// generations left of the current step.
+    steps: usize,
+}
+
+impl Player<'_> {
+    /// play the next generation and the edits before it. false at the end.
+    fn next(&mut self, game: &mut Game) -> Result<bool> {
+        loop {
+            if self.steps > 0 {
+                self.steps -= 1;
+                game.step()?;
+                return Ok(true);
+            }
+            match self.events.next() {
+                Some(Recorded::Step(n)) => self.steps = *n,
+                Some(event) => event.apply(game)?,
+                None => return Ok(false),
+            }
+        }
+    }
+}
+
+fn play(
+    screen: &mut Screen,
+    game: &mut Game,
+    frontend: &Frontend,
+    player: &mut Player,
+    args: &ReplayArgs,
+) -> Result<()> {
+    let (mut delay, mut paused, mut done) = (args.duration, false, false);
+    loop {
+        let status = format!(
+            "{}\n{}, {:?} a generation",
+            frontend.summary(game),
+            if done {
+                "end of the recording"
+            } else if paused {
+                "paused"
+            } else {
+                "playing"
+            },
+            delay
+        );
+        let snapshot = Snapshot::of(game, frontend);
+        screen.render(|frame| {
+            let hints = StatusBar {
+                status: &status,
+                hints: HINTS,
+            };
+            let area = frame.area();
+            view(
+                area,
+                frame.buffer_mut(),
+                snapshot.board(),
+                hints,
+                LogArea::new([].into_iter()),
+            );
+        })?;
+        // a paused player only waits for keys.
+        let wait = if paused || done {
+            Duration::from_secs(60)
+        } else {
+            delay
+        };
+        if poll(wait)? {
+            let Event::Key(KeyEvent {
+                code: KeyCode::Char(c),
+                kind: KeyEventKind::Press,
+                ..
+            }) = read()?
+            else {
+                continue;
+            };
+            match c {
+                'q' => return Ok(()),
+                ' ' => paused = !paused,
+                '.' if paused => done = !player.next(game)?,
+                '+' => delay /= 2,
+                '-' => delay = delay.saturating_mul(2),
+                _ => {}
+            }
+        } else if !paused && !done {
+            done = !player.next(game)?;
+        }
+    }
+}
+
+pub(crate) fn replay(args: &ReplayArgs, config: &Config) -> Result<()> {
+    let replay: Replay = read_to_string(&args.file)
+        .with_context(|| format!("{}", args.file.display()))?
+        .parse()
+        .with_context(|| format!("{}", args.file.display()))?;
+    let mut game = replay.start()?;
+    let frontend = Frontend {
+        glyphs: config.glyphs,
+        ..Frontend::default()
+    };
+    let mut player = Player {
+        events: replay.events.iter(),
+        steps: 0,
+    };
+    if args.print {
+        while player.next(&mut game)? {}
+        print!("{}", game.to_text());
+        return Ok(());
+    }
+    if let Some(from) = args.from {
+        while game.state.time < from && player.next(&mut game)? {}
+    }
+
+    screen::restore_on_exit()?;
+    let mut screen = Screen::new(screen::terminal(), &config.colors)?;
+    execute!(screen, Hide, EnterAlternateScreen)?;
+    let result = match RawMode::enable()
+        .and_then(|_raw| play(&mut screen, &mut game, &frontend, &mut player, args))
+    {
+        Err(e) if e.is::<Interrupted>() => Ok(()),
+        result => result,
+    };
+    execute!(screen, MoveTo(0, 0), Clear(ClearType::FromCursorDown))?;
+    screen::restore_terminal()?;
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn play_a_generation_at_a_time() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        let path = dir.join("run.txt");
+        let mut game = Game::blank("6:6".parse()?)?;
+        game.load("blinker")?;
+        game.record(&path)?;
+        game.skip(2)?;
+        game.set_pos((0, 0))?;
+        game.step()?;
+        game.finish_recording()?;
+
+        let replay: Replay = read_to_string(&path)?.parse()?;
+        let mut replayed = replay.start()?;
+        let mut player = Player {
+            events: replay.events.iter(),
+            steps: 0,
+        };
+        assert!(player.next(&mut replayed)? && player.next(&mut replayed)?);
+        assert_eq!(replayed.state.time, 2);
+        // the edit comes with the generation after it.
+        assert!(!replayed.game[0]);
+        assert!(player.next(&mut replayed)?);
+        assert!(!player.next(&mut replayed)?);
+        assert_eq!(replayed.to_native(), game.to_native());
+        Ok(())
+    }
+}
@@ -0,0 +1,133 @@
+//! pictures of the board, for when the terminal doesn't do it justice.
+
+#[cfg(feature = "png")]
+pub mod raster;
+pub mod vector;
+
+use crate::Game;
+use anyhow::{anyhow, bail, ensure, Error, Result};
+use serde::Deserialize;
+use std::{fmt::Display, fs::write, path::Path, str::FromStr};
+
+/// a colour written `#rrggbb`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Colour(pub [u8; 3]);
+
+impl FromStr for Colour {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let hex = s
+            .strip_prefix('#')
+            .filter(|hex| hex.len() == 6 && hex.is_ascii())
+            .ok_or_else(|| anyhow!("colour {} isn't written #rrggbb.", s))?;
+        let mut rgb = [0; 3];
+        for (i, channel) in rgb.iter_mut().enumerate() {
+            *channel = u8::from_str_radix(&hex[2 * i..][..2], 16)
+                .map_err(|_| anyhow!("colour {} isn't written #rrggbb.", s))?;
+        }
+        Ok(Self(rgb))
+    }
+}
+
+impl TryFrom<String> for Colour {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl Display for Colour {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [r, g, b] = self.0;
+        write!(f, "#{:02x}{:02x}{:02x}", r, g, b)
+    }
+}
+
+/// how pictures of the board look. `dying` is every dying state, like the dying glyph.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Style {
+    /// pixels on a side of a cell.
+    pub scale: u32,
+    /// live cells.
+    pub alive: Colour,
+    /// dead cells, the background.
+    pub dead: Colour,
+    /// every dying state.
+    pub dying: Colour,
+    /// lines between the cells of svg pictures, if any.
+    pub grid: Option<Colour>,
+}
+
+impl Style {
+    /// check the style can draw anything.
+    pub fn check(&self) -> Result<()> {
+        ensure!(self.scale > 0, "cells need at least 1 pixel.");
+        Ok(())
+    }
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            scale: 1,
+            alive: Colour([255, 255, 255]),
+            dead: Colour([0, 0, 0]),
+            dying: Colour([128, 128, 128]),
+            grid: None,
+        }
+    }
+}
+
+/// write the board to `path` as a png or an svg, by its extension, drawn with the style of the
+/// game.
+pub fn save(game: &Game, path: &Path) -> Result<String> {
+    let extension = path.extension().and_then(|e| e.to_str());
+    match extension.map(str::to_ascii_lowercase).as_deref() {
+        #[cfg(feature = "png")]
+        Some("png") => raster::image(game, &game.state.picture)?
+            .save_with_format(path, image::ImageFormat::Png)?,
+        Some("svg") => write(path, vector::svg(game, &game.state.picture)?)?,
+        #[cfg(feature = "png")]
+        _ => bail!("{} doesn't end with .png or .svg.", path.display()),
+        #[cfg(not(feature = "png"))]
+        _ => bail!("{} doesn't end with .svg.", path.display()),
+    }
+    Ok(format!("success save picture to {}", path.display()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_colours() -> Result<()> {
+        assert_eq!("#20ff0a".parse::<Colour>()?, Colour([32, 255, 10]));
+        assert_eq!(Colour([32, 255, 10]).to_string(), "#20ff0a");
+        for bad in ["20ff0a", "#20ff0", "#20ff0g", "#20ffé"] {
+            assert!(bad.parse::<Colour>().is_err(), "{}", bad);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn save_pictures() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        let mut game = Game::blank("3:2".parse()?)?;
+        game.set_pos((1, 0))?;
+        save(&game, &dir.join("board.svg"))?;
+        assert!(std::fs::read_to_string(dir.join("board.svg"))?.starts_with("<svg"));
+        #[cfg(feature = "png")]
+        {
+            save(&game, &dir.join("board.PNG"))?;
+            let loaded = image::open(dir.join("board.PNG"))?.to_rgb8();
+            assert_eq!(loaded.dimensions(), (3, 2));
+        }
+        assert!(save(&game, &dir.join("board.jpg")).is_err());
+        Ok(())
+    }
+}
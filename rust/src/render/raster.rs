@@ -0,0 +1,132 @@
+//! png snapshots and animated pngs of the board.
+
+use super::Style;
+use crate::Game;
+use anyhow::{bail, ensure, Result};
+use image::{Rgb, RgbImage};
+use png::{BitDepth, ColorType, Encoder};
+use std::{fs::File, io::BufWriter, path::Path, time::Duration};
+
+/// the board drawn with `style`, `style.scale` pixels square per cell.
+pub fn image(game: &Game, style: &Style) -> Result<RgbImage> {
+    style.check()?;
+    let size = game.state.size;
+    let (width, height) = (
+        u32::from(size.width).checked_mul(style.scale),
+        u32::from(size.height).checked_mul(style.scale),
+    );
+    let (Some(width), Some(height)) = (width, height) else {
+        bail!(
+            "a picture of {} at {} pixels a cell is too big.",
+            size,
+            style.scale
+        );
+    };
+    let states = game.states();
+    Ok(RgbImage::from_fn(width, height, |x, y| {
+        let (x, y) = (x / style.scale, y / style.scale);
+        let state = states[y as usize * usize::from(size.width) + x as usize];
+        let colour = match state {
+            0 => style.dead,
+            s if game.state.rule.alive(s) => style.alive,
+            _ => style.dying,
+        };
+        Rgb(colour.0)
+    }))
+}
+
+/// run `game` to generation `generations`, and write a frame every `every` generations from the
+/// current one as an animated png to `path`, shown `delay` apart. it plays in a loop. animated
+/// png is the only format, and `path` has to end with `.png`.
+pub fn animate(
+    game: &mut Game,
+    path: &Path,
+    generations: usize,
+    every: usize,
+    delay: Duration,
+) -> Result<String> {
+    ensure!(every > 0, "frames need at least 1 generation between them.");
+    ensure!(
+        path.extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("png")),
+        "{}: animations are written as animated png only, to a .png file.",
+        path.display()
+    );
+    let style = game.state.picture;
+    let start = game.state.time;
+    let frames = u32::try_from(generations.saturating_sub(start) / every + 1)?;
+    let first = image(game, &style)?;
+    let mut encoder = Encoder::new(
+        BufWriter::new(File::create(path)?),
+        first.width(),
+        first.height(),
+    );
+    encoder.set_color(ColorType::Rgb);
+    encoder.set_depth(BitDepth::Eight);
+    encoder.set_animated(frames, 0)?;
+    // the delay is a fraction, in ms up to a minute.
+    encoder.set_frame_delay(delay.as_millis().min(u128::from(u16::MAX)) as u16, 1000)?;
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(first.as_raw())?;
+    for _ in 1..frames {
+        for _ in 0..every {
+            game.step()?;
+        }
+        writer.write_image_data(image(game, &style)?.as_raw())?;
+    }
+    writer.finish()?;
+    Ok(format!(
+        "success save {} frames to {}",
+        frames,
+        path.display()
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::render::Colour;
+
+    #[test]
+    fn draw_cells() -> Result<()> {
+        let mut game = Game::blank("3:2".parse()?)?;
+        game.set_pos((1, 0))?;
+        let style = Style {
+            scale: 2,
+            alive: Colour([255, 0, 0]),
+            ..Style::default()
+        };
+        let image = image(&game, &style)?;
+        assert_eq!(image.dimensions(), (6, 4));
+        let red = |x, y| image.get_pixel(x, y).0 == [255, 0, 0];
+        assert!(red(2, 0) && red(3, 1));
+        assert!(!red(1, 0) && !red(2, 2) && !red(4, 1));
+        assert!(super::image(&game, &Style { scale: 0, ..style }).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn animate_a_blinker() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        let mut game = Game::blank("5:5".parse()?)?;
+        game.load("blinker")?;
+        let path = dir.join("blinker.png");
+        let message = animate(&mut game, &path, 6, 2, Duration::from_millis(100))?;
+        assert!(message.starts_with("success save 4 frames"));
+        assert_eq!(game.state.time, 6);
+
+        let mut reader =
+            png::Decoder::new(std::io::BufReader::new(File::open(&path)?)).read_info()?;
+        let control = reader.info().animation_control.expect("an animated png");
+        assert_eq!((control.num_frames, control.num_plays), (4, 0));
+        let mut frame = vec![0; reader.output_buffer_size().unwrap_or(0)];
+        reader.next_frame(&mut frame)?;
+        // the blinker lies flat in even generations.
+        assert_eq!(&frame[(2 * 5 + 1) * 3..][..3], [255; 3]);
+        assert_eq!(&frame[(5 + 2) * 3..][..3], [0; 3]);
+        assert!(animate(&mut game, &path, 6, 0, Duration::ZERO).is_err());
+        assert!(animate(&mut game, &dir.join("blinker.gif"), 8, 2, Duration::ZERO).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+//! svg pictures of the board, which stay sharp at any size.
+
+use super::{Colour, Style};
+use crate::Game;
+use anyhow::Result;
+use std::fmt::Write as _;
+
+/// width of the grid lines, in cells.
+const GRID_WIDTH: f64 = 0.05;
+
+/// the board drawn with `style` as an svg, `style.scale` pixels square per cell. a row of cells in
+/// the same state is one rectangle.
+pub fn svg(game: &Game, style: &Style) -> Result<String> {
+    style.check()?;
+    let size = game.state.size;
+    let (width, height) = (u32::from(size.width), u32::from(size.height));
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" shape-rendering=\"crispEdges\">\n<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+        u64::from(width) * u64::from(style.scale),
+        u64::from(height) * u64::from(style.scale),
+        width,
+        height,
+        width,
+        height,
+        style.dead
+    );
+    let mut rect = |x: usize, y: usize, w: usize, colour: Colour| {
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"1\" fill=\"{}\"/>",
+            x, y, w, colour
+        );
+    };
+    let states = game.states();
+    for (y, row) in states.chunks(usize::from(size.width)).enumerate() {
+        let mut x = 0;
+        for run in row.chunk_by(|a, b| a == b) {
+            let colour = match run[0] {
+                0 => None,
+                s if game.state.rule.alive(s) => Some(style.alive),
+                _ => Some(style.dying),
+            };
+            if let Some(colour) = colour {
+                rect(x, y, run.len(), colour);
+            }
+            x += run.len();
+        }
+    }
+    if let Some(grid) = style.grid {
+        let mut path = String::new();
+        for x in 0..=width {
+            let _ = write!(path, "M{} 0V{}", x, height);
+        }
+        for y in 0..=height {
+            let _ = write!(path, "M0 {}H{}", y, width);
+        }
+        let _ = writeln!(
+            svg,
+            "<path d=\"{}\" stroke=\"{}\" stroke-width=\"{}\"/>",
+            path, grid, GRID_WIDTH
+        );
+    }
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn draw_rows() -> Result<()> {
+        let mut game = Game::blank("4:2".parse()?)?;
+        game.set_pos((1, 1))?;
+        game.set_pos((2, 1))?;
+        let style = Style {
+            scale: 10,
+            ..Style::default()
+        };
+        let svg = svg(&game, &style)?;
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"40\" height=\"20\" viewBox=\"0 0 4 2\""));
+        assert!(svg.contains("<rect width=\"4\" height=\"2\" fill=\"#000000\"/>"));
+        // the two live cells of the row are one rectangle.
+        assert!(svg.contains("<rect x=\"1\" y=\"1\" width=\"2\" height=\"1\" fill=\"#ffffff\"/>"));
+        assert_eq!(svg.matches("<rect").count(), 2);
+        assert!(!svg.contains("<path"));
+        let grid = Style {
+            grid: Some(Colour([64, 64, 64])),
+            ..style
+        };
+        assert!(super::svg(&game, &grid)?.contains("M0 0V2M1 0V2"));
+        assert!(svg.ends_with("</svg>\n"));
+        Ok(())
+    }
+}
@@ -0,0 +1,371 @@
+//! recordings of a run: the board it started from, then every step and edit in order, to play it
+//! back exactly, e.g. to see what went wrong at some generation.
+//!
+//! a recording is text. after the first line come the settings of the start and its board in rle,
+//! then one line per event:
+//!
+//! ```text
+//! #life-game replay
+//! generation 0
+//! rule B3/S23
+//! board
+//! x = 3, y = 3, rule = B3/S23
+//! bo$2bo$3o!
+//! step 120
+//! cell 1:2 0
+//! ```
+//!
+//! edits aren't recorded as they are made, but found when the board is stepped: whatever changed
+//! since the last step is written as `cell` lines, or as a whole `board` when much did.
+
+use crate::{engine, point_from_str, rle, rule::Rule, Game, Noise, Size};
+use anyhow::{anyhow, bail, ensure, Context, Error, Result};
+use std::{
+    fmt::{self, Display},
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    str::FromStr,
+};
+
+/// first line of a recording.
+const MAGIC: &str = "#life-game replay";
+
+/// something that happened to the board.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// this many generations were stepped.
+    Step(usize),
+    /// the cell at the position was put into the state.
+    Cell((u16, u16), u8),
+    /// the rule changed. the cells it changed follow.
+    Rule(Rule),
+    /// the whole board was replaced, in the states of its cells.
+    Board(Size, Vec<u8>),
+}
+
+impl Event {
+    /// do it to `game`.
+    pub fn apply(&self, game: &mut Game) -> Result<()> {
+        match self {
+            Self::Step(n) => {
+                for _ in 0..*n {
+                    game.step()?;
+                }
+            }
+            Self::Cell(pos, state) => {
+                let idx = game.index(*pos)?;
+                let (alive, dying) = engine::split(&[*state], &game.state.rule);
+                game.game[idx] = alive[0];
+                game.dying[idx] = dying[0];
+                game.recount();
+            }
+            Self::Rule(rule) => game.state.rule = *rule,
+            Self::Board(size, states) => {
+                (game.game, game.dying) = engine::split(states, &game.state.rule);
+                game.state.size = *size;
+                game.state.len = states.len();
+                game.replaced();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Event {
+    type Err = Error;
+
+    /// a one line event: `step N`, `cell X:Y STATE` or `rule RULE`. a board takes more lines.
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (key, value) = s.split_once(' ').unwrap_or((s, ""));
+        Ok(match key {
+            "step" => Self::Step(value.parse()?),
+            "cell" => {
+                let (pos, state) = value
+                    .split_once(' ')
+                    .ok_or_else(|| anyhow!("a cell needs a position and a state."))?;
+                Self::Cell(point_from_str(pos)?, state.parse()?)
+            }
+            "rule" => Self::Rule(value.parse()?),
+            _ => bail!("unknown event: {}", key),
+        })
+    }
+}
+
+/// a recorded run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    /// generation the run was recorded from.
+    pub generation: usize,
+    /// seed of the random board it started from, if it was one.
+    pub seed: Option<u64>,
+    /// probability and seed of the noise, if the run had any.
+    pub noise: Option<(f64, u64)>,
+    /// the rule it started under.
+    pub rule: Rule,
+    /// size of the board it started from.
+    pub size: Size,
+    /// the board it started from, in the states of its cells.
+    pub states: Vec<u8>,
+    /// what happened from then on, in order.
+    pub events: Vec<Event>,
+}
+
+impl Replay {
+    /// a recording of `game` as it is, without events yet.
+    pub fn of(game: &Game) -> Self {
+        let state = &game.state;
+        Self {
+            generation: state.time,
+            seed: state.seed,
+            noise: state.noise.as_ref().map(|n| (n.probability, n.seed)),
+            rule: state.rule,
+            size: state.size,
+            states: game.states(),
+            events: Vec::new(),
+        }
+    }
+
+    /// the game the run started from.
+    pub fn start(&self) -> Result<Game> {
+        let mut game = Game::blank(self.size)?;
+        game.state.rule = self.rule;
+        Event::Board(self.size, self.states.clone()).apply(&mut game)?;
+        game.state.time = self.generation;
+        game.state.seed = self.seed;
+        game.state.noise = self.noise.map(|(p, seed)| Noise::new(p, seed));
+        game.record_population(0, 0);
+        Ok(game)
+    }
+}
+
+impl Display for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}\ngeneration {}", MAGIC, self.generation)?;
+        if let Some(seed) = self.seed {
+            writeln!(f, "seed {}", seed)?;
+        }
+        if let Some((p, seed)) = self.noise {
+            writeln!(f, "noise {} {}", p, seed)?;
+        }
+        writeln!(f, "rule {}", self.rule)?;
+        write!(
+            f,
+            "board\n{}",
+            rle::encode(self.size, &self.states, &self.rule)
+        )?;
+        let mut rule = self.rule;
+        for event in &self.events {
+            match event {
+                Event::Step(n) => writeln!(f, "step {}", n)?,
+                Event::Cell((x, y), state) => writeln!(f, "cell {}:{} {}", x, y, state)?,
+                Event::Rule(new) => {
+                    rule = *new;
+                    writeln!(f, "rule {}", rule)?
+                }
+                Event::Board(size, states) => {
+                    write!(f, "board\n{}", rle::encode(*size, states, &rule))?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// take the lines of an rle board, up to the one ending it.
+fn board<'a>(lines: &mut impl Iterator<Item = &'a str>) -> Result<(Size, Vec<u8>)> {
+    let mut text = String::new();
+    for line in lines.by_ref() {
+        text.push_str(line);
+        text.push('\n');
+        if line.contains('!') {
+            let (size, states, _) = rle::decode(&text)?;
+            return Ok((size, states));
+        }
+    }
+    bail!("a board has no end.")
+}
+
+impl FromStr for Replay {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut lines = s.lines();
+        ensure!(
+            lines.next().map(str::trim) == Some(MAGIC),
+            "not a recording: it doesn't start with {}.",
+            MAGIC
+        );
+        let (mut generation, mut seed, mut noise, mut rule) = (0, None, None, Rule::default());
+        let (mut start, mut events) = (None, Vec::new());
+        while let Some(line) = lines.next() {
+            let context = || format!("bad line: {}", line);
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match (key, &start) {
+                ("generation", None) => generation = value.parse().with_context(context)?,
+                ("seed", None) => seed = Some(value.parse().with_context(context)?),
+                ("noise", None) => {
+                    let (p, seed) = value.split_once(' ').ok_or_else(|| anyhow!(context()))?;
+                    noise = Some((
+                        p.parse().with_context(context)?,
+                        seed.parse().with_context(context)?,
+                    ));
+                }
+                ("rule", None) => rule = value.parse().with_context(context)?,
+                ("board", None) => start = Some(board(&mut lines)?),
+                ("step" | "cell" | "rule", Some(_)) => {
+                    events.push(line.parse().with_context(context)?)
+                }
+                ("board", Some(_)) => {
+                    let (size, states) = board(&mut lines)?;
+                    events.push(Event::Board(size, states));
+                }
+                ("", _) => {}
+                _ => bail!("{}", context()),
+            }
+        }
+        let (size, states) = start.ok_or_else(|| anyhow!("the recording has no board."))?;
+        Ok(Self {
+            generation,
+            seed,
+            noise,
+            rule,
+            size,
+            states,
+            events,
+        })
+    }
+}
+
+/// writes a recording of a game while it runs, kept in its state.
+#[derive(Debug)]
+pub struct Recorder {
+    out: BufWriter<File>,
+    /// the board as the recording has it so far.
+    size: Size,
+    rule: Rule,
+    states: Vec<u8>,
+    /// steps not written yet, as a run of them is one line.
+    steps: usize,
+}
+
+impl Recorder {
+    /// start recording `game` to `path`. a rule script can't be recorded.
+    pub fn create(game: &Game, path: &Path) -> Result<Self> {
+        ensure!(
+            game.state.script.is_none(),
+            "a run with a rule script can't be recorded."
+        );
+        let mut out =
+            BufWriter::new(File::create(path).with_context(|| format!("{}", path.display()))?);
+        let start = Replay::of(game);
+        write!(out, "{}", start)?;
+        Ok(Self {
+            out,
+            size: start.size,
+            rule: start.rule,
+            states: start.states,
+            steps: 0,
+        })
+    }
+
+    fn write_steps(&mut self) -> Result<()> {
+        if self.steps > 0 {
+            writeln!(self.out, "step {}", self.steps)?;
+            self.steps = 0;
+        }
+        Ok(())
+    }
+
+    /// write what changed since the last event, the edits made since then.
+    pub fn catch_up(&mut self, size: Size, rule: Rule, states: Vec<u8>) -> Result<()> {
+        if rule != self.rule {
+            self.write_steps()?;
+            writeln!(self.out, "rule {}", rule)?;
+            self.rule = rule;
+        }
+        let changed: Vec<usize> = if size == self.size {
+            (0..states.len())
+                .filter(|&i| states[i] != self.states[i])
+                .collect()
+        } else {
+            Vec::new()
+        };
+        if size != self.size || changed.len() > states.len() / 8 {
+            self.write_steps()?;
+            write!(self.out, "board\n{}", rle::encode(size, &states, &rule))?;
+        } else if !changed.is_empty() {
+            self.write_steps()?;
+            let width = usize::from(size.width);
+            for i in changed {
+                writeln!(self.out, "cell {}:{} {}", i % width, i / width, states[i])?;
+            }
+        }
+        (self.size, self.states) = (size, states);
+        Ok(())
+    }
+
+    /// note a step, which gave the board `states`.
+    pub fn stepped(&mut self, states: Vec<u8>) {
+        self.steps += 1;
+        self.states = states;
+    }
+
+    /// write the edits since the last step, and everything else still pending.
+    pub fn finish(mut self, game: &Game) -> Result<()> {
+        self.catch_up(game.state.size, game.state.rule, game.states())?;
+        self.write_steps()?;
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn record_and_replay() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        let path = dir.join("run.txt");
+        let mut game = Game::blank("8:8".parse()?)?;
+        game.load("glider")?;
+        game.state.noise = Some(Noise::new(0.05, 7));
+        game.record(&path)?;
+        game.skip(5)?;
+        game.set_pos((0, 0))?;
+        game.set_pos((7, 7))?;
+        game.skip(3)?;
+        game.set_rule("B36/S23".parse()?);
+        game.skip(2)?;
+        game.resize("10:6".parse()?, crate::Anchor::Center)?;
+        game.step()?;
+        game.set_pos((1, 1))?;
+        game.finish_recording()?;
+
+        let text = std::fs::read_to_string(&path)?;
+        assert!(
+            text.starts_with("#life-game replay\ngeneration 0\nnoise 0.05 7\nrule B3/S23\nboard\n")
+        );
+        // the noise had brought 0:0 to life.
+        assert!(text.contains("step 5\ncell 0:0 0\ncell 7:7 1\nstep 3\nrule B36/S23\n"));
+        assert!(text.contains("\nboard\nx = 10, y = 6, rule = B36/S23\n"));
+        let replay: Replay = text.parse()?;
+        let mut replayed = replay.start()?;
+        for event in &replay.events {
+            event.apply(&mut replayed)?;
+        }
+        assert_eq!(replayed.state.time, 11);
+        assert_eq!(replayed.state.rule, game.state.rule);
+        assert_eq!(replayed.to_native(), game.to_native());
+        assert_eq!(replay.to_string().parse::<Replay>()?, replay);
+
+        assert_eq!("cell 2:3 1".parse::<Event>()?, Event::Cell((2, 3), 1));
+        assert!("cell 2:3".parse::<Event>().is_err());
+        assert!("life".parse::<Replay>().is_err());
+        assert!("#life-game replay\nstep 1\n".parse::<Replay>().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,242 @@
+//! the run length encoded format golly and catagolue use.
+
+use crate::{meta::Metadata, rule::Rule, Size};
+use anyhow::{anyhow, bail, ensure, Context, Result};
+
+const LINE_LENGTH: usize = 70;
+
+/// encode a board, the state of every cell, into the RLE format (`b`: dead, `o`: alive, `$`: end of
+/// row). boards with dying cells use the multi-state letters: `.` dead, `A` alive, `B` and up dying.
+pub fn encode(size: Size, states: &[u8], rule: &Rule) -> String {
+    let multi = rule.states() > 2 || states.iter().any(|&s| s > 1);
+    let letter = |state: u8| match (multi, state) {
+        (false, 0) => 'b',
+        (false, _) => 'o',
+        (true, 0) => '.',
+        (true, s) => char::from(b'A' + s - 1),
+    };
+    let mut runs: Vec<(usize, char)> = Vec::new();
+    let mut push = |n: usize, c: char| match runs.last_mut() {
+        Some((m, last)) if *last == c => *m += n,
+        _ => runs.push((n, c)),
+    };
+    for row in states.chunks(size.width as usize) {
+        // dead cells at the end of a row are implied.
+        let end = row.iter().rposition(|&s| s > 0).map_or(0, |i| i + 1);
+        for &s in &row[..end] {
+            push(1, letter(s));
+        }
+        push(1, '$');
+    }
+    while matches!(runs.last(), Some((_, '$'))) {
+        runs.pop();
+    }
+
+    let mut data = format!("x = {}, y = {}, rule = {}\n", size.width, size.height, rule);
+    let mut line = String::new();
+    for (n, c) in runs {
+        let token = if n == 1 {
+            c.to_string()
+        } else {
+            format!("{}{}", n, c)
+        };
+        if line.len() + token.len() > LINE_LENGTH {
+            data.push_str(&line);
+            data.push('\n');
+            line.clear();
+        }
+        line.push_str(&token);
+    }
+    data.push_str(&line);
+    data.push_str("!\n");
+    data
+}
+
+/// decode the RLE format into the state of every cell. `#N` gives the name, `#C` and `#O` comments,
+/// and the header the size and the rule.
+/// `A` to `X` are the states 1 to 24, and other letters but `b` count as alive.
+pub fn decode(text: &str) -> Result<(Size, Vec<u8>, Metadata)> {
+    let mut meta = Metadata::default();
+    let mut size = None;
+    let mut data = String::new();
+    for line in text.lines().map(str::trim) {
+        if let Some(comment) = line.strip_prefix('#') {
+            match comment.split_at_checked(1) {
+                Some(("N", name)) => meta.name = Some(name.trim().to_string()),
+                Some(("C" | "c" | "O", text)) => meta.comments.push(text.trim().to_string()),
+                _ => {}
+            }
+        } else if size.is_none() {
+            let (s, rule) = header(line)?;
+            size = Some(s);
+            meta.rule = rule;
+        } else {
+            data.push_str(line);
+            if line.contains('!') {
+                break;
+            }
+        }
+    }
+    let size: Size = size.ok_or_else(|| anyhow!("no `x = .., y = ..` header."))?;
+    let (width, height) = (size.width as usize, size.height as usize);
+    let mut cells = vec![0; width * height];
+    let (mut x, mut y) = (0, 0);
+    let mut count = String::new();
+    for c in data.chars() {
+        if c.is_ascii_digit() {
+            count.push(c);
+            continue;
+        }
+        let n = if count.is_empty() { 1 } else { count.parse()? };
+        count.clear();
+        match c {
+            '!' => break,
+            '$' => (x, y) = (0, y + n),
+            'b' | '.' => x += n,
+            c if c.is_ascii_alphabetic() => {
+                ensure!(x + n <= width && y < height, "cells outside of {}.", size);
+                let state = match c {
+                    'A'..='X' => c as u8 - b'A' + 1,
+                    _ => 1,
+                };
+                cells[y * width + x..][..n].fill(state);
+                x += n;
+            }
+            c if c.is_whitespace() => {}
+            c => bail!("unexpected {:?} in rle data.", c),
+        }
+    }
+    Ok((size, cells, meta))
+}
+
+/// `x = 3, y = 3, rule = B3/S23`. the rule may also be written as `S/B`, e.g. `23/3`, or
+/// `S/B/C`. it comes last, as a Larger than Life rule has commas of its own.
+fn header(line: &str) -> Result<(Size, Option<Rule>)> {
+    let (mut width, mut height, mut rule) = (None, None, None);
+    let fields = match line.find("rule") {
+        Some(i) => [line[..i].trim_end().trim_end_matches(','), &line[i..]],
+        None => [line, ""],
+    };
+    for field in fields[0]
+        .split(',')
+        .chain(Some(fields[1]).filter(|f| !f.is_empty()))
+    {
+        let (key, value) = field
+            .split_once('=')
+            .ok_or_else(|| anyhow!("bad rle header: {}", line))?;
+        let value = value.trim();
+        match key.trim() {
+            "x" => width = Some(value.parse()?),
+            "y" => height = Some(value.parse()?),
+            "rule" => {
+                rule = Some(
+                    match value.split_once('/') {
+                        Some((s, b))
+                            if s.bytes().all(|c| c.is_ascii_digit()) && !b.contains('/') =>
+                        {
+                            format!("B{}/S{}", b, s).parse()
+                        }
+                        _ => value.parse(),
+                    }
+                    .with_context(|| format!("rule {}", value))?,
+                )
+            }
+            _ => {}
+        }
+    }
+    match (width, height) {
+        (Some(width), Some(height)) => Ok((Size { width, height }, rule)),
+        _ => bail!("bad rle header: {}", line),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encode_glider() {
+        let board = [
+            0, 1, 0, 0, //
+            0, 0, 1, 0, //
+            1, 1, 1, 0, //
+            0, 0, 0, 0,
+        ];
+        let size = Size {
+            width: 4,
+            height: 4,
+        };
+        assert_eq!(
+            encode(size, &board, &Rule::default()),
+            "x = 4, y = 4, rule = B3/S23\nbo$2bo$3o!\n"
+        );
+    }
+
+    #[test]
+    fn encode_wraps_long_lines() {
+        let board: Vec<u8> = (0..200).map(|i: usize| (i % 2) as u8).collect();
+        let size = Size {
+            width: 200,
+            height: 1,
+        };
+        let data = encode(size, &board, &Rule::default());
+        assert!(data.lines().all(|l| l.len() <= LINE_LENGTH));
+        assert!(data.ends_with("!\n"));
+    }
+
+    #[test]
+    fn decode_glider() -> Result<()> {
+        let (size, cells, meta) =
+            decode("#N Glider\n#C a comment\nx = 3, y = 3, rule = 23/36\nbo$2bo$\n3o!\n")?;
+        assert_eq!((size.width, size.height), (3, 3));
+        assert_eq!(cells, [0, 1, 0, 0, 0, 1, 1, 1, 1]);
+        assert_eq!(meta.name.as_deref(), Some("Glider"));
+        assert_eq!(meta.comments, ["a comment"]);
+        assert_eq!(meta.rule.map(|r| r.to_string()).as_deref(), Some("B36/S23"));
+        // decoding what was encoded gives the board back.
+        let board: Vec<u8> = (0..40)
+            .map(|i: usize| u8::from(i.is_multiple_of(3)))
+            .collect();
+        let size = Size {
+            width: 8,
+            height: 5,
+        };
+        let (_, cells, _) = decode(&encode(size, &board, &Rule::default()))?;
+        assert_eq!(cells, board);
+        assert!(decode("x = 2, y = 1\n3o!").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn multi_state() -> Result<()> {
+        let rule: Rule = "345/2/4".parse()?;
+        let size = Size {
+            width: 4,
+            height: 2,
+        };
+        let board = [0, 1, 2, 3, 3, 0, 0, 0];
+        let data = encode(size, &board, &rule);
+        assert_eq!(data, "x = 4, y = 2, rule = B2/S345/C4\n.ABC$C!\n");
+        let (_, cells, meta) = decode(&data)?;
+        assert_eq!(cells, board);
+        assert_eq!(meta.rule, Some(rule));
+        assert_eq!(decode("x = 1, y = 1, rule = 345/2/4\nB!")?.1, [2]);
+        Ok(())
+    }
+
+    #[test]
+    fn larger_than_life_header() -> Result<()> {
+        let rule: Rule = "R5,C0,M1,S34..58,B34..45,NM".parse()?;
+        let size = Size {
+            width: 2,
+            height: 1,
+        };
+        let data = encode(size, &[1, 1], &rule);
+        assert_eq!(
+            data,
+            "x = 2, y = 1, rule = R5,C0,M1,S34..58,B34..45,NM\n2o!\n"
+        );
+        assert_eq!(decode(&data)?.2.rule, Some(rule));
+        Ok(())
+    }
+}
@@ -0,0 +1,280 @@
+//! the control socket of `--control`, which takes json-rpc 2.0 calls on a unix socket, a call
+//! per line and an answer per line, to drive a running game from other programs and tests:
+//!
+//! - `step {"n": N}`: step N generations, 1 if not given. the result is the generation and the
+//!   population.
+//! - `set_cell {"x": X, "y": Y, "alive": true}`: make the cell alive, or dead with false.
+//! - `load {"pattern": NAME}`: replace the board with a built-in pattern or a file, like `:load`.
+//! - `get_population`: the number of live cells.
+//!
+//! ```text
+//! {"jsonrpc": "2.0", "id": 1, "method": "step", "params": {"n": 10}}
+//! {"id":1,"jsonrpc":"2.0","result":{"generation":10,"population":42}}
+//! ```
+//!
+//! calls are answered by the main loop between keys like the requests of `--http`. calls
+//! without an id are notifications, and aren't answered. without the `rpc` feature, or
+//! off unix, `--control` is an error.
+
+#[cfg(not(all(unix, feature = "rpc")))]
+use anyhow::bail;
+use anyhow::Result;
+use life_game::Game;
+use std::path::Path;
+
+#[cfg(all(unix, feature = "rpc"))]
+use serde_json::{json, Value};
+#[cfg(all(unix, feature = "rpc"))]
+use std::{
+    fs::remove_file,
+    io::{BufRead, BufReader, Write},
+    os::unix::{
+        fs::FileTypeExt,
+        net::{UnixListener, UnixStream},
+    },
+    path::PathBuf,
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+};
+
+/// a line sent to the socket, waiting for its answer.
+#[cfg(all(unix, feature = "rpc"))]
+pub(crate) struct Call {
+    line: String,
+    reply: Sender<Option<String>>,
+}
+#[cfg(not(all(unix, feature = "rpc")))]
+pub(crate) enum Call {}
+
+#[cfg(all(unix, feature = "rpc"))]
+pub(crate) struct Socket {
+    path: PathBuf,
+    calls: Receiver<Call>,
+}
+#[cfg(not(all(unix, feature = "rpc")))]
+pub(crate) enum Socket {}
+
+#[cfg(all(unix, feature = "rpc"))]
+impl Socket {
+    /// listen at `path`. a socket left there by an earlier run is replaced, any other file isn't.
+    pub(crate) fn bind(path: &Path) -> Result<Self> {
+        if path
+            .symlink_metadata()
+            .is_ok_and(|meta| meta.file_type().is_socket())
+        {
+            remove_file(path)?;
+        }
+        let listener =
+            UnixListener::bind(path).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+        let (send, calls) = mpsc::channel();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let send = send.clone();
+                thread::spawn(move || serve(stream, send));
+            }
+        });
+        Ok(Self {
+            path: path.to_path_buf(),
+            calls,
+        })
+    }
+
+    /// the next call, if one came.
+    pub(crate) fn try_recv(&self) -> Option<Call> {
+        self.calls.try_recv().ok()
+    }
+}
+
+#[cfg(all(unix, feature = "rpc"))]
+impl Drop for Socket {
+    fn drop(&mut self) {
+        let _ = remove_file(&self.path);
+    }
+}
+
+#[cfg(not(all(unix, feature = "rpc")))]
+impl Socket {
+    pub(crate) fn bind(_: &Path) -> Result<Self> {
+        bail!("--control needs a build for unix with the rpc feature.")
+    }
+
+    pub(crate) fn try_recv(&self) -> Option<Call> {
+        match *self {}
+    }
+}
+
+/// pass the calls of a client to the main loop, and their answers back, until it hangs up.
+#[cfg(all(unix, feature = "rpc"))]
+fn serve(stream: UnixStream, calls: Sender<Call>) -> Result<()> {
+    let mut out = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let (reply, answer) = mpsc::channel();
+        calls.send(Call { line: line?, reply })?;
+        if let Some(answer) = answer.recv()? {
+            writeln!(out, "{}", answer)?;
+        }
+    }
+    Ok(())
+}
+
+/// what a call asks of `game`, or its error code and message.
+#[cfg(all(unix, feature = "rpc"))]
+fn run(game: &mut Game, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+    let invalid = |what: &str| (-32602, format!("invalid params: {}", what));
+    let failed = |e: anyhow::Error| (-32000, format!("{:#}", e));
+    let coordinate = |key: &str| {
+        params[key]
+            .as_u64()
+            .and_then(|v| u16::try_from(v).ok())
+            .ok_or_else(|| invalid(&format!("{} is not a coordinate", key)))
+    };
+    Ok(match method {
+        "step" => {
+            let n = match &params["n"] {
+                Value::Null => 1,
+                n => n.as_u64().ok_or_else(|| invalid("n is not a count"))?,
+            };
+            for _ in 0..n {
+                game.step().map_err(failed)?;
+            }
+            json!({"generation": game.state.time, "population": game.state.population})
+        }
+        "set_cell" => {
+            let pos = (coordinate("x")?, coordinate("y")?);
+            let alive = match &params["alive"] {
+                Value::Null => true,
+                alive => alive
+                    .as_bool()
+                    .ok_or_else(|| invalid("alive is not a bool"))?,
+            };
+            game.set_cell(pos, alive).map_err(failed)?;
+            json!({"population": game.state.population})
+        }
+        "load" => {
+            let pattern = params["pattern"]
+                .as_str()
+                .ok_or_else(|| invalid("pattern is not a string"))?;
+            game.load(pattern).map_err(failed)?;
+            json!({"population": game.state.population})
+        }
+        "get_population" => json!(game.state.population),
+        _ => return Err((-32601, format!("method not found: {}", method))),
+    })
+}
+
+/// the answer of `game` to `line`, none for a notification.
+#[cfg(all(unix, feature = "rpc"))]
+fn answer(game: &mut Game, line: &str) -> Option<String> {
+    let (id, result) = match serde_json::from_str::<Value>(line) {
+        Ok(call) => {
+            let id = call.get("id").cloned();
+            let result = match call["method"].as_str() {
+                Some(method) => run(game, method, &call["params"]),
+                None => Err((-32600, "invalid request: no method".to_string())),
+            };
+            // a notification is answered not even with its error.
+            (id?, result)
+        }
+        Err(e) => (Value::Null, Err((-32700, format!("parse error: {}", e)))),
+    };
+    Some(
+        match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err((code, message)) => {
+                json!({"jsonrpc": "2.0", "id": id, "error": {"code": code, "message": message}})
+            }
+        }
+        .to_string(),
+    )
+}
+
+/// answer `call` from `game`. a client which left before its answer is no error.
+#[cfg(all(unix, feature = "rpc"))]
+pub(crate) fn respond(game: &mut Game, call: Call) -> Result<()> {
+    let _ = call.reply.send(answer(game, &call.line));
+    Ok(())
+}
+
+#[cfg(not(all(unix, feature = "rpc")))]
+pub(crate) fn respond(_: &mut Game, call: Call) -> Result<()> {
+    match call {}
+}
+
+#[cfg(all(test, unix, feature = "rpc"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn answer_calls() -> Result<()> {
+        let mut game = Game::blank("6:6".parse()?)?;
+        let mut call = |line: &str| answer(&mut game, line);
+        assert_eq!(
+            call(r#"{"jsonrpc":"2.0","id":1,"method":"load","params":{"pattern":"blinker"}}"#)
+                .unwrap(),
+            r#"{"id":1,"jsonrpc":"2.0","result":{"population":3}}"#
+        );
+        assert!(call(r#"{"id":2,"method":"step","params":{"n":2}}"#)
+            .unwrap()
+            .contains(r#""result":{"generation":2,"population":3}"#));
+        assert!(
+            call(r#"{"id":3,"method":"set_cell","params":{"x":0,"y":0}}"#)
+                .unwrap()
+                .contains(r#""result":{"population":4}"#)
+        );
+        assert!(call(r#"{"id":4,"method":"get_population"}"#)
+            .unwrap()
+            .contains(r#""result":4"#));
+        assert!(
+            call(r#"{"id":5,"method":"set_cell","params":{"x":9,"y":0}}"#)
+                .unwrap()
+                .contains(r#""code":-32000"#)
+        );
+        assert!(call(r#"{"id":6,"method":"set_cell","params":{"y":0}}"#)
+            .unwrap()
+            .contains(r#""code":-32602"#));
+        assert!(call(r#"{"id":7,"method":"fly"}"#)
+            .unwrap()
+            .contains(r#""code":-32601"#));
+        assert!(call("{").unwrap().contains(r#""id":null"#));
+        // notifications are followed, but not answered.
+        assert_eq!(call(r#"{"method":"step"}"#), None);
+        assert_eq!(game.state.time, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn drive_over_the_socket() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let dir = tmp.path();
+        let path = dir.join("control.sock");
+        let socket = Socket::bind(&path)?;
+        let mut game = Game::blank("6:6".parse()?)?;
+        let client = thread::spawn({
+            let path = path.clone();
+            move || -> Result<String> {
+                let mut stream = UnixStream::connect(path)?;
+                writeln!(
+                    stream,
+                    r#"{{"id":1,"method":"set_cell","params":{{"x":1,"y":2}}}}"#
+                )?;
+                let mut line = String::new();
+                BufReader::new(stream).read_line(&mut line)?;
+                Ok(line)
+            }
+        });
+        while !client.is_finished() {
+            if let Some(call) = socket.try_recv() {
+                respond(&mut game, call)?;
+            }
+            thread::sleep(std::time::Duration::from_millis(5));
+        }
+        assert!(client
+            .join()
+            .unwrap()?
+            .contains(r#""result":{"population":1}"#));
+        assert!(game.game[2 * 6 + 1]);
+        drop(socket);
+        assert!(!path.exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,597 @@
+//! rules cells live and die by, and the neighbourhoods they count.
+
+use alloc::string::{String, ToString};
+use anyhow::{anyhow, ensure, Error, Result};
+use core::{fmt::Display, str::FromStr};
+
+/// life-like rule in B/S notation, e.g. `B3/S23`, or a Generations rule with more states, e.g.
+/// `B2/S345/C4` or `345/2/4` (S/B/C). a live cell which doesn't survive goes through the dying
+/// states 2, 3, .. before it is dead, and dying cells are neither alive nor can be born.
+///
+/// Larger than Life rules count the neighbours in a bigger square and take ranges of counts,
+/// e.g. Bosco's rule `R5,C0,M1,S34..58,B34..45,NM`.
+///
+/// life-like and Generations rules end with `V` on the von Neumann neighbourhood and `H` on the
+/// hexagonal one, e.g. `B2/S34H`.
+///
+/// `WireWorld` is Wireworld: empty cells (0), electron heads (1, alive), electron tails (2, dying)
+/// and conductors ([`CONDUCTOR`]). heads become tails, tails become conductors, and conductors
+/// become heads next to one or two heads.
+///
+/// `Immigration` and `QuadLife` are conway's life with 2 and 4 colours of live cells, the states 1
+/// and up. a cell is born with the colour most of its parents have, and under QuadLife three
+/// parents of different colours give the fourth.
+///
+/// `W110`, or just `110`, is an elementary cellular automaton with Wolfram's rule number. the
+/// bottom row of the board is the current generation, and the rows above are the ones before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "std",
+    derive(serde::Deserialize),
+    serde(try_from = "String")
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(into = "String"))]
+pub struct Rule {
+    /// dead cells with this many live neighbours are born.
+    birth: [bool; 9],
+    /// live cells with this many live neighbours survive.
+    survival: [bool; 9],
+    /// number of states, dead and alive included. 2 for life-like rules.
+    states: u8,
+    /// counts and ranges of a Larger than Life rule, which replace `birth` and `survival`.
+    larger: Option<LargerThanLife>,
+    neighbourhood: Neighbourhood,
+    wireworld: bool,
+    /// rule number of an elementary automaton: bit `n` is the next state of a cell whose left
+    /// neighbour, itself and right neighbour spell `n` in binary.
+    wolfram: Option<u8>,
+    /// number of colours of live cells, 0 if they have none.
+    colours: u8,
+}
+
+/// which cells around a cell are its neighbours.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(clap::ValueEnum))]
+pub enum Neighbourhood {
+    /// the 8 cells around it.
+    #[default]
+    Moore,
+    /// the 4 cells above, below, left and right of it.
+    VonNeumann,
+    /// the 6 cells around it on a hexagonal grid, whose odd rows are half a cell to the right.
+    Hex,
+}
+
+impl Neighbourhood {
+    /// number of neighbours.
+    pub fn neighbours(self) -> usize {
+        match self {
+            Self::Moore => 8,
+            Self::VonNeumann => 4,
+            Self::Hex => 6,
+        }
+    }
+
+    /// the letter which ends a rule on this neighbourhood.
+    fn suffix(self) -> &'static str {
+        match self {
+            Self::Moore => "",
+            Self::VonNeumann => "V",
+            Self::Hex => "H",
+        }
+    }
+}
+
+impl Display for Neighbourhood {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        f.write_str(match self {
+            Self::Moore => "Moore",
+            Self::VonNeumann => "von Neumann",
+            Self::Hex => "hexagonal",
+        })
+    }
+}
+
+/// the neighbourhood and ranges of a Larger than Life rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LargerThanLife {
+    /// cells up to this far away, across and along, are neighbours.
+    pub radius: u8,
+    /// the cell itself counts as its neighbour.
+    pub middle: bool,
+    /// dead cells with this many live neighbours, inclusive, are born.
+    pub birth: (u16, u16),
+    /// live cells with this many live neighbours, inclusive, survive.
+    pub survival: (u16, u16),
+}
+
+/// biggest radius of a Larger than Life rule.
+pub const MAX_RADIUS: u8 = 50;
+
+/// state of a conductor under Wireworld.
+pub const CONDUCTOR: u8 = 3;
+
+/// most states a rule may have: one letter each in rle, one base 36 digit in native saves.
+pub const MAX_STATES: u8 = 25;
+
+impl Default for Rule {
+    /// conway's life.
+    fn default() -> Self {
+        Self {
+            birth: counts("3"),
+            survival: counts("23"),
+            states: 2,
+            larger: None,
+            neighbourhood: Neighbourhood::Moore,
+            wireworld: false,
+            wolfram: None,
+            colours: 0,
+        }
+    }
+}
+
+fn counts(digits: &str) -> [bool; 9] {
+    let mut counts = [false; 9];
+    for d in digits.bytes() {
+        counts[(d - b'0') as usize] = true;
+    }
+    counts
+}
+
+impl FromStr for Rule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.starts_with(['R', 'r']) && s.contains(',') {
+            return larger_than_life(s);
+        }
+        if s.eq_ignore_ascii_case("wireworld") {
+            return Ok(Self::wireworld());
+        }
+        if s.eq_ignore_ascii_case("immigration") {
+            return Ok(Self::immigration());
+        }
+        if s.eq_ignore_ascii_case("quadlife") {
+            return Ok(Self::quadlife());
+        }
+        let number = s.strip_prefix(['W', 'w']).unwrap_or(s);
+        if !number.is_empty() && number.bytes().all(|c| c.is_ascii_digit()) {
+            let code = number
+                .parse()
+                .map_err(|_| anyhow!("elementary rules are 0 to 255: {}", s))?;
+            return Ok(Self::elementary(code));
+        }
+        let (s, neighbourhood) = if let Some(s) = s.strip_suffix(['V', 'v']) {
+            (s, Neighbourhood::VonNeumann)
+        } else if let Some(s) = s.strip_suffix(['H', 'h']) {
+            (s, Neighbourhood::Hex)
+        } else {
+            (s, Neighbourhood::Moore)
+        };
+        life_like(s)?.with_neighbourhood(neighbourhood)
+    }
+}
+
+/// `B3/S23`, `S23/B3`, `B2/S345/C4` or `345/2/4`, on the Moore neighbourhood.
+fn life_like(s: &str) -> Result<Rule> {
+    let parts: alloc::vec::Vec<&str> = s.split('/').collect();
+    let (s, states) = match parts[..] {
+        // S/B/C
+        [survival, birth, states] if survival.bytes().all(|c| c.is_ascii_digit()) => {
+            let rule: Rule = alloc::format!("B{}/S{}", birth, survival).parse()?;
+            return rule.with_states(states);
+        }
+        [_, _, states] => (&s[..s.len() - states.len() - 1], Some(states)),
+        _ => (s, None),
+    };
+    let (birth, survival) = s
+        .split_once('/')
+        .map(|(a, b)| {
+            if a.starts_with(['S', 's']) {
+                (b, a)
+            } else {
+                (a, b)
+            }
+        })
+        .ok_or_else(|| {
+            anyhow!(r#"Invalid Rule!.note:: you must use a "B<digits>/S<digits>" format."#)
+        })?;
+    let digits = |part: &str, prefix: [char; 2]| -> Result<[bool; 9]> {
+        let digits = part
+            .strip_prefix(prefix)
+            .ok_or_else(|| anyhow!("{} must start with {}.", part, prefix[0]))?;
+        ensure!(
+            digits.bytes().all(|d| (b'0'..=b'8').contains(&d)),
+            "neighbour counts must be 0 to 8: {}",
+            part
+        );
+        Ok(counts(digits))
+    };
+    let rule = Rule {
+        birth: digits(birth, ['B', 'b'])?,
+        survival: digits(survival, ['S', 's'])?,
+        states: 2,
+        larger: None,
+        neighbourhood: Neighbourhood::Moore,
+        wireworld: false,
+        wolfram: None,
+        colours: 0,
+    };
+    match states {
+        Some(states) => rule.with_states(
+            states
+                .strip_prefix(['C', 'c'])
+                .ok_or_else(|| anyhow!("{} must start with C.", states))?,
+        ),
+        None => Ok(rule),
+    }
+}
+
+/// `R5,C0,M1,S34..58,B34..45,NM`: radius, states (0 for 2), middle, survival and birth ranges,
+/// and the Moore neighbourhood.
+fn larger_than_life(s: &str) -> Result<Rule> {
+    let mut rule = Rule {
+        birth: [false; 9],
+        survival: [false; 9],
+        states: 2,
+        larger: None,
+        neighbourhood: Neighbourhood::Moore,
+        wireworld: false,
+        wolfram: None,
+        colours: 0,
+    };
+    let (mut radius, mut middle, mut birth, mut survival) = (None, false, None, None);
+    let range = |value: &str| -> Result<(u16, u16)> {
+        let (lo, hi) = value
+            .split_once("..")
+            .ok_or_else(|| anyhow!("expected a range like 34..58: {}", value))?;
+        let bound = |n: &str| n.parse().map_err(|_| anyhow!("not a count: {}", n));
+        let (lo, hi): (u16, u16) = (bound(lo)?, bound(hi)?);
+        ensure!(lo <= hi, "empty range: {}", value);
+        Ok((lo, hi))
+    };
+    for field in s.split(',') {
+        let Some((key, value)) = field.trim().split_at_checked(1) else {
+            continue;
+        };
+        match key {
+            "R" | "r" => {
+                radius = value.parse().ok().filter(|r| (1..=MAX_RADIUS).contains(r));
+                ensure!(
+                    radius.is_some(),
+                    "radius must be 1 to {}: {}",
+                    MAX_RADIUS,
+                    value
+                );
+            }
+            "C" | "c" if matches!(value, "0" | "1") => {}
+            "C" | "c" => rule = rule.with_states(value)?,
+            "M" | "m" => middle = value == "1",
+            "S" | "s" => survival = Some(range(value)?),
+            "B" | "b" => birth = Some(range(value)?),
+            "N" | "n" => ensure!(
+                matches!(value, "M" | "m"),
+                "only the Moore neighbourhood NM is supported: N{}",
+                value
+            ),
+            _ => return Err(anyhow!("unknown field {} in {}", field, s)),
+        }
+    }
+    rule.larger = Some(LargerThanLife {
+        radius: radius.ok_or_else(|| anyhow!("no radius R in {}", s))?,
+        middle,
+        birth: birth.ok_or_else(|| anyhow!("no birth range B in {}", s))?,
+        survival: survival.ok_or_else(|| anyhow!("no survival range S in {}", s))?,
+    });
+    Ok(rule)
+}
+
+impl TryFrom<String> for Rule {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<Rule> for String {
+    fn from(rule: Rule) -> Self {
+        rule.to_string()
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        if self.wireworld {
+            return f.write_str("WireWorld");
+        }
+        if let Some(code) = self.wolfram {
+            return write!(f, "W{}", code);
+        }
+        match self.colours {
+            2 => return f.write_str("Immigration"),
+            4 => return f.write_str("QuadLife"),
+            _ => {}
+        }
+        if let Some(larger) = &self.larger {
+            return write!(
+                f,
+                "R{},C{},M{},S{}..{},B{}..{},NM",
+                larger.radius,
+                if self.states > 2 { self.states } else { 0 },
+                u8::from(larger.middle),
+                larger.survival.0,
+                larger.survival.1,
+                larger.birth.0,
+                larger.birth.1
+            );
+        }
+        let digits = |counts: &[bool; 9]| -> String {
+            (0..9)
+                .filter(|&n| counts[n])
+                .map(|n| char::from(b'0' + n as u8))
+                .collect()
+        };
+        write!(f, "B{}/S{}", digits(&self.birth), digits(&self.survival))?;
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        f.write_str(self.neighbourhood.suffix())
+    }
+}
+
+impl Rule {
+    /// Wireworld, see [`Rule`].
+    pub fn wireworld() -> Self {
+        Self {
+            birth: [false; 9],
+            survival: [false; 9],
+            states: CONDUCTOR + 1,
+            larger: None,
+            neighbourhood: Neighbourhood::Moore,
+            wireworld: true,
+            wolfram: None,
+            colours: 0,
+        }
+    }
+
+    /// Brian's Brain, `B2/S/C3`: cells which are on always start dying, and off cells with two
+    /// neighbours which are on turn on.
+    pub fn brians_brain() -> Self {
+        Self {
+            birth: counts("2"),
+            survival: [false; 9],
+            states: 3,
+            ..Self::default()
+        }
+    }
+
+    /// the elementary automaton with Wolfram's rule number `code`, e.g. 30 or 110.
+    pub fn elementary(code: u8) -> Self {
+        Self {
+            birth: [false; 9],
+            survival: [false; 9],
+            wolfram: Some(code),
+            ..Self::default()
+        }
+    }
+
+    /// Immigration, see [`Rule`].
+    pub fn immigration() -> Self {
+        Self::coloured(2)
+    }
+
+    /// QuadLife, see [`Rule`].
+    pub fn quadlife() -> Self {
+        Self::coloured(4)
+    }
+
+    /// conway's life with `colours` colours of live cells.
+    fn coloured(colours: u8) -> Self {
+        Self {
+            states: colours + 1,
+            colours,
+            ..Self::default()
+        }
+    }
+
+    /// number of colours of live cells, 0 if they have none.
+    pub fn colours(&self) -> u8 {
+        self.colours
+    }
+
+    /// whether cells in `state` are alive: 1, and every colour under a coloured rule.
+    pub fn alive(&self, state: u8) -> bool {
+        state == 1 || (state > 0 && state <= self.colours)
+    }
+
+    /// whether it is Wireworld, which has its own transitions.
+    pub fn is_wireworld(&self) -> bool {
+        self.wireworld
+    }
+
+    /// rule number of an elementary automaton.
+    pub fn wolfram(&self) -> Option<u8> {
+        self.wolfram
+    }
+
+    fn with_states(mut self, states: &str) -> Result<Self> {
+        self.states = states
+            .parse()
+            .ok()
+            .filter(|n| (2..=MAX_STATES).contains(n))
+            .ok_or_else(|| anyhow!("states must be 2 to {}: {}", MAX_STATES, states))?;
+        Ok(self)
+    }
+
+    /// number of states, dead and alive included.
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// the same rule on another neighbourhood. fails if it counts more neighbours than there are,
+    /// and for Larger than Life rules, Wireworld, elementary automata and coloured rules, which have
+    /// their own.
+    pub fn with_neighbourhood(mut self, neighbourhood: Neighbourhood) -> Result<Self> {
+        if neighbourhood != Neighbourhood::Moore {
+            ensure!(
+                self.larger.is_none()
+                    && !self.wireworld
+                    && self.wolfram.is_none()
+                    && self.colours == 0,
+                "{} has no other neighbourhoods.",
+                self
+            );
+            let max = neighbourhood.neighbours();
+            ensure!(
+                !self.birth[max + 1..].contains(&true) && !self.survival[max + 1..].contains(&true),
+                "neighbour counts must be 0 to {} on the {} neighbourhood: {}",
+                max,
+                neighbourhood,
+                self
+            );
+        }
+        self.neighbourhood = neighbourhood;
+        Ok(self)
+    }
+
+    /// which cells count as neighbours.
+    pub fn neighbourhood(&self) -> Neighbourhood {
+        self.neighbourhood
+    }
+
+    /// the neighbourhood and ranges, if this is a Larger than Life rule.
+    pub fn larger(&self) -> Option<&LargerThanLife> {
+        self.larger.as_ref()
+    }
+
+    /// state after `state`, a dying state of 2 or more, or 0 once it is dead. under Wireworld a tail
+    /// becomes a conductor, which only changes when its neighbours do.
+    pub fn decay(&self, state: u8) -> u8 {
+        if state + 1 < self.states && self.colours == 0 {
+            state + 1
+        } else {
+            0
+        }
+    }
+
+    /// whether a cell is alive in the next generation.
+    pub fn next(&self, alive: bool, neighbours: usize) -> bool {
+        if let Some(larger) = &self.larger {
+            let (lo, hi) = if alive { larger.survival } else { larger.birth };
+            return (usize::from(lo)..=usize::from(hi)).contains(&neighbours);
+        }
+        if alive {
+            self.survival[neighbours]
+        } else {
+            self.birth[neighbours]
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_rules() -> Result<()> {
+        assert_eq!("B3/S23".parse::<Rule>()?, Rule::default());
+        assert_eq!("s23/b3".parse::<Rule>()?, Rule::default());
+        let highlife: Rule = "B36/S23".parse()?;
+        assert_eq!(highlife.to_string(), "B36/S23");
+        assert!(highlife.next(false, 6) && !highlife.next(true, 6));
+        assert_eq!("B/S".parse::<Rule>()?.to_string(), "B/S");
+        assert!("B9/S23".parse::<Rule>().is_err());
+        assert!("23/3".parse::<Rule>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_generations() -> Result<()> {
+        let star_wars: Rule = "345/2/4".parse()?;
+        assert_eq!(star_wars, "B2/S345/C4".parse()?);
+        assert_eq!(star_wars.to_string(), "B2/S345/C4");
+        assert_eq!(star_wars.states(), 4);
+        assert_eq!((star_wars.decay(2), star_wars.decay(3)), (3, 0));
+        assert_eq!("B3/S23/C2".parse::<Rule>()?, Rule::default());
+        assert_eq!("/2/3".parse::<Rule>()?, Rule::brians_brain());
+        assert!("B2/S/C1".parse::<Rule>().is_err());
+        assert!("B2/S/C26".parse::<Rule>().is_err());
+        assert!("B2/S/4".parse::<Rule>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_neighbourhoods() -> Result<()> {
+        let rule: Rule = "B2/S34H".parse()?;
+        assert_eq!(rule.neighbourhood(), Neighbourhood::Hex);
+        assert_eq!(rule.to_string(), "B2/S34H");
+        assert_eq!("34/2/4v".parse::<Rule>()?.to_string(), "B2/S34/C4V");
+        let moore = Rule::default().with_neighbourhood(Neighbourhood::VonNeumann)?;
+        assert_eq!(moore.to_string(), "B3/S23V");
+        assert!("B5/SV".parse::<Rule>().is_err());
+        assert!("B7/S6H".parse::<Rule>().is_err());
+        let bosco: Rule = "R5,C0,M1,S34..58,B34..45,NM".parse()?;
+        assert!(bosco.with_neighbourhood(Neighbourhood::Hex).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_wireworld() -> Result<()> {
+        let rule: Rule = "wireworld".parse()?;
+        assert_eq!(rule, Rule::wireworld());
+        assert_eq!(rule.to_string(), "WireWorld");
+        assert_eq!(
+            (rule.states(), rule.decay(1), rule.decay(2)),
+            (4, 2, CONDUCTOR)
+        );
+        assert!(!rule.next(false, 1) && !rule.next(true, 1));
+        assert!(rule.with_neighbourhood(Neighbourhood::VonNeumann).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_coloured() -> Result<()> {
+        let quadlife: Rule = "quadlife".parse()?;
+        assert_eq!(quadlife.to_string(), "QuadLife");
+        assert_eq!((quadlife.colours(), quadlife.states()), (4, 5));
+        assert!(quadlife.alive(4) && !quadlife.alive(0));
+        assert_eq!(quadlife.decay(1), 0);
+        assert_eq!("Immigration".parse::<Rule>()?, Rule::immigration());
+        assert!(!Rule::default().alive(2));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_elementary() -> Result<()> {
+        let rule: Rule = "110".parse()?;
+        assert_eq!(rule, "w110".parse()?);
+        assert_eq!(
+            (rule.wolfram(), rule.to_string()),
+            (Some(110), "W110".to_string())
+        );
+        assert_eq!(rule.states(), 2);
+        assert!("256".parse::<Rule>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_larger_than_life() -> Result<()> {
+        let bosco: Rule = "R5,C0,M1,S34..58,B34..45,NM".parse()?;
+        assert_eq!(bosco.to_string(), "R5,C0,M1,S34..58,B34..45,NM");
+        let larger = bosco.larger().unwrap();
+        assert_eq!((larger.radius, larger.middle), (5, true));
+        assert!(bosco.next(true, 58) && !bosco.next(true, 59));
+        assert!(bosco.next(false, 34) && !bosco.next(false, 33));
+        assert_eq!(
+            "r2,c3,s4..6,b5..5".parse::<Rule>()?.to_string(),
+            "R2,C3,M0,S4..6,B5..5,NM"
+        );
+        assert!("R0,C0,M0,S1..2,B1..2,NM".parse::<Rule>().is_err());
+        assert!("R51,C0,M0,S1..2,B1..2,NM".parse::<Rule>().is_err());
+        assert!("R2,C0,M0,S3..2,B1..2,NM".parse::<Rule>().is_err());
+        assert!("R2,C0,M0,S1..2,NM".parse::<Rule>().is_err());
+        assert!("R2,C0,M0,S1..2,B1..2,NN".parse::<Rule>().is_err());
+        Ok(())
+    }
+}
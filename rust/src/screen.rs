@@ -0,0 +1,151 @@
+use crate::{config::Colors, ui};
+use anyhow::{bail, Result};
+use crossterm::{
+    cursor::{SetCursorStyle, Show},
+    event::{self, DisableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute,
+    style::ResetColor,
+    terminal::{disable_raw_mode, enable_raw_mode, LeaveAlternateScreen},
+};
+use ratatui::{backend::CrosstermBackend, style::Style, widgets::Block, Frame, Terminal};
+use std::{
+    fmt::Display,
+    fs::OpenOptions,
+    io::{stdout, IsTerminal, Write},
+    panic::{set_hook, take_hook},
+    process::exit,
+};
+
+/// the terminal, drawn with ratatui. it only rewrites what changed since the last frame.
+pub(crate) struct Screen {
+    terminal: Terminal<CrosstermBackend<Box<dyn Write>>>,
+    /// colors of the whole screen.
+    style: Style,
+}
+
+impl Screen {
+    pub(crate) fn new(out: Box<dyn Write>, colors: &Colors) -> Result<Self> {
+        let mut style = Style::new();
+        if let Some(color) = colors.foreground {
+            style = style.fg(color.into());
+        }
+        if let Some(color) = colors.background {
+            style = style.bg(color.into());
+        }
+        Ok(Self {
+            terminal: Terminal::new(CrosstermBackend::new(out))?,
+            style,
+        })
+    }
+
+    /// draw a frame with `render`, over the background of the whole screen.
+    pub(crate) fn render(&mut self, render: impl FnOnce(&mut Frame)) -> Result<()> {
+        let style = self.style;
+        self.terminal.draw(|frame| {
+            frame.render_widget(Block::new().style(style), frame.area());
+            render(frame);
+        })?;
+        Ok(())
+    }
+
+    /// draw `text` from the top left, replacing the last frame.
+    pub(crate) fn draw(&mut self, text: &str) -> Result<()> {
+        self.render(|frame| frame.render_widget(ui::text(text), frame.area()))
+    }
+}
+
+/// where the tui is drawn: stdout, or the terminal itself if stdout is piped, e.g. for the board
+/// of `--print-on-exit`.
+pub(crate) fn terminal() -> Box<dyn Write> {
+    let out = stdout();
+    if !out.is_terminal() {
+        if let Ok(tty) = OpenOptions::new().write(true).open("/dev/tty") {
+            return Box::new(tty);
+        }
+    }
+    Box::new(out)
+}
+
+/// keeps the terminal in raw mode while alive.
+#[derive(Debug)]
+pub(crate) struct RawMode;
+
+impl RawMode {
+    pub(crate) fn enable() -> Result<Self> {
+        enable_raw_mode()?;
+        Ok(Self)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+    }
+}
+
+/// Ctrl+C was pressed.
+#[derive(Debug)]
+pub(crate) struct Interrupted;
+
+impl Display for Interrupted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "interrupted")
+    }
+}
+
+impl std::error::Error for Interrupted {}
+
+/// next terminal event. Ctrl+C is raised as `Interrupted`, so every loop unwinds to the cleanup.
+pub(crate) fn read() -> Result<Event> {
+    let event = event::read()?;
+    if let Event::Key(KeyEvent {
+        code: KeyCode::Char('c'),
+        modifiers,
+        kind: KeyEventKind::Press,
+        ..
+    }) = event
+    {
+        if modifiers.contains(KeyModifiers::CONTROL) {
+            bail!(Interrupted);
+        }
+    }
+    Ok(event)
+}
+
+/// undo what main() set up for the tui, so the shell is usable again.
+pub(crate) fn restore_terminal() -> Result<()> {
+    let _ = disable_raw_mode();
+    execute!(
+        terminal(),
+        DisableMouseCapture,
+        Show,
+        SetCursorStyle::DefaultUserShape,
+        ResetColor,
+        LeaveAlternateScreen
+    )?;
+    Ok(())
+}
+
+/// restore the terminal before a panic message is printed, or when Ctrl+C kills the program.
+pub(crate) fn restore_on_exit() -> Result<()> {
+    let hook = take_hook();
+    set_hook(Box::new(move |info| {
+        let _ = restore_terminal();
+        hook(info);
+    }));
+    ctrlc::set_handler(|| {
+        let _ = restore_terminal();
+        exit(130);
+    })?;
+    Ok(())
+}
+
+impl Write for Screen {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.terminal.backend_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Write::flush(self.terminal.backend_mut())
+    }
+}
@@ -0,0 +1,257 @@
+//! rules written as a small expression, for automata no rule string describes.
+//!
+//! the expression gives the next state of a cell from `state`, its own state, and `count`, its
+//! live neighbours. it has integers, `+ - * / %`, comparisons, `&& || !`, `c ? a : b` and
+//! parentheses. true is 1 and false 0, and `#` starts a comment. e.g. life is
+//!
+//! ```text
+//! count == 3 || state == 1 && count == 2
+//! ```
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+use anyhow::{anyhow, bail, ensure, Error, Result};
+use core::{fmt::Display, str::FromStr};
+
+/// a parsed rule expression, which keeps its source to show it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Script {
+    source: String,
+    expr: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    Number(i64),
+    State,
+    Count,
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    Binary(Op, Box<Expr>, Box<Expr>),
+    If(Box<Expr>, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+/// operators of each precedence level, the loosest first.
+const LEVELS: [&[(&str, Op)]; 5] = [
+    &[("||", Op::Or)],
+    &[("&&", Op::And)],
+    &[
+        ("==", Op::Eq),
+        ("!=", Op::Ne),
+        ("<=", Op::Le),
+        (">=", Op::Ge),
+        ("<", Op::Lt),
+        (">", Op::Gt),
+    ],
+    &[("+", Op::Add), ("-", Op::Sub)],
+    &[("*", Op::Mul), ("/", Op::Div), ("%", Op::Rem)],
+];
+
+impl Script {
+    /// next state of a cell in `state` with `count` live neighbours. results out of
+    /// `0..states` are the nearest state.
+    pub fn next(&self, state: u8, count: usize, states: u8) -> u8 {
+        let next = self.expr.eval(state, count);
+        next.clamp(0, i64::from(states) - 1) as u8
+    }
+}
+
+impl Expr {
+    fn eval(&self, state: u8, count: usize) -> i64 {
+        let eval = |e: &Expr| e.eval(state, count);
+        match self {
+            Self::Number(n) => *n,
+            Self::State => state.into(),
+            Self::Count => count as i64,
+            Self::Not(e) => i64::from(eval(e) == 0),
+            Self::Neg(e) => eval(e).wrapping_neg(),
+            Self::If(c, a, b) => {
+                if eval(c) != 0 {
+                    eval(a)
+                } else {
+                    eval(b)
+                }
+            }
+            Self::Binary(Op::Or, a, b) => i64::from(eval(a) != 0 || eval(b) != 0),
+            Self::Binary(Op::And, a, b) => i64::from(eval(a) != 0 && eval(b) != 0),
+            Self::Binary(op, a, b) => {
+                let (a, b) = (eval(a), eval(b));
+                match op {
+                    Op::Eq => i64::from(a == b),
+                    Op::Ne => i64::from(a != b),
+                    Op::Lt => i64::from(a < b),
+                    Op::Le => i64::from(a <= b),
+                    Op::Gt => i64::from(a > b),
+                    Op::Ge => i64::from(a >= b),
+                    Op::Add => a.wrapping_add(b),
+                    Op::Sub => a.wrapping_sub(b),
+                    Op::Mul => a.wrapping_mul(b),
+                    // dividing by 0 gives 0, as the rule can't fail halfway through a step.
+                    Op::Div => a.checked_div(b).unwrap_or(0),
+                    Op::Rem => a.checked_rem(b).unwrap_or(0),
+                    Op::Or | Op::And => unreachable!(),
+                }
+            }
+        }
+    }
+}
+
+/// a recursive descent parser over the source without comments.
+struct Parser<'a> {
+    rest: &'a str,
+}
+
+impl Parser<'_> {
+    /// skip whitespace, then take `token` if it comes next.
+    fn eat(&mut self, token: &str) -> bool {
+        self.rest = self.rest.trim_start();
+        match self.rest.strip_prefix(token) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr> {
+        let cond = self.binary(0)?;
+        if !self.eat("?") {
+            return Ok(cond);
+        }
+        let a = self.expr()?;
+        ensure!(self.eat(":"), "expected `:` at `{}`", self.rest);
+        let b = self.expr()?;
+        Ok(Expr::If(Box::new(cond), Box::new(a), Box::new(b)))
+    }
+
+    /// operators of `LEVELS[level]` and tighter ones, which are left associative.
+    fn binary(&mut self, level: usize) -> Result<Expr> {
+        let Some(ops) = LEVELS.get(level) else {
+            return self.unary();
+        };
+        let mut expr = self.binary(level + 1)?;
+        'outer: loop {
+            for &(token, op) in *ops {
+                if self.eat(token) {
+                    let rhs = self.binary(level + 1)?;
+                    expr = Expr::Binary(op, Box::new(expr), Box::new(rhs));
+                    continue 'outer;
+                }
+            }
+            return Ok(expr);
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        if self.eat("!") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.eat("-") {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        if self.eat("(") {
+            let expr = self.expr()?;
+            ensure!(self.eat(")"), "expected `)` at `{}`", self.rest);
+            return Ok(expr);
+        }
+        let end = self
+            .rest
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .unwrap_or(self.rest.len());
+        let (word, rest) = self.rest.split_at(end);
+        let expr = match word {
+            "state" => Expr::State,
+            "count" => Expr::Count,
+            "" => bail!("expected a value at `{}`", self.rest),
+            _ => Expr::Number(
+                word.parse()
+                    .map_err(|_| anyhow!("unknown name `{}`. use state or count.", word))?,
+            ),
+        };
+        self.rest = rest;
+        Ok(expr)
+    }
+}
+
+impl FromStr for Script {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let code: Vec<&str> = s
+            .lines()
+            .map(|line| line.split_once('#').map_or(line, |(code, _)| code))
+            .collect();
+        let code = code.join("\n");
+        let mut parser = Parser { rest: &code };
+        let expr = parser.expr()?;
+        ensure!(
+            parser.rest.trim().is_empty(),
+            "unexpected `{}` after the rule.",
+            parser.rest.trim()
+        );
+        Ok(Self {
+            source: s.trim().into(),
+            expr,
+        })
+    }
+}
+
+impl Display for Script {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn evaluate() -> Result<()> {
+        let life: Script = "# life\ncount == 3 || state == 1 && count == 2".parse()?;
+        let next = |state, count| life.next(state, count, 2);
+        assert_eq!(
+            [next(0, 3), next(1, 2), next(0, 2), next(1, 4)],
+            [1, 1, 0, 0]
+        );
+        let brain: Script = "state == 1 ? 2 : state == 0 && count == 2".parse()?;
+        assert_eq!([0, 1, 2].map(|s| brain.next(s, 2, 3)), [1, 2, 0]);
+        let sums: Script = "-(1 + 2 * 3) % 4 + count / 0 + 9".parse()?;
+        assert_eq!(sums.next(0, 1, 255), 6);
+        // out of range results are clamped.
+        assert_eq!(sums.next(0, 1, 4), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_errors() {
+        for bad in [
+            "",
+            "count ==",
+            "alive",
+            "(state",
+            "state 1",
+            "1 ? 2",
+            "state = 1",
+        ] {
+            assert!(bad.parse::<Script>().is_err(), "{}", bad);
+        }
+    }
+}
@@ -0,0 +1,433 @@
+//! the `serve` subcommand, which runs the board without the tui and streams it to websocket
+//! clients, e.g. a viewer in the browser.
+//!
+//! a client gets the whole board when it connects, then what changed after every step or edit.
+//! in json these are
+//!
+//! ```text
+//! {"type":"board","generation":0,"population":5,"width":8,"height":8,"cells":[0,1,0,...]}
+//! {"type":"diff","generation":1,"population":5,"cells":[[2,0,0],[1,1,1]]}
+//! ```
+//!
+//! with `cells` the state of every cell row by row, or `[x, y, state]` of the changed ones. the
+//! binary format has the same messages in little endian: a byte 0 for a board, the generation
+//! and the population as u64, the width and the height as u16 and a byte per cell; or a byte 1
+//! for a diff, the generation and the population, the number of changed cells as u32 and for
+//! each x and y as u16 and its state as a byte.
+//!
+//! clients send text commands back: `pause`, `resume`, or a line of a recording made with
+//! `--record`, i.e. `step N`, `cell X:Y STATE` or `rule RULE`. a bad command is answered with an
+//! error, `{"type":"error","message":"..."}` in json and its message as text in binary.
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use life_game::{replay::Event, Game, Size};
+use serde::Serialize;
+use std::{
+    io::ErrorKind,
+    net::{SocketAddr, TcpListener, TcpStream},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+use tungstenite::{
+    handshake::{
+        server::{NoCallback, ServerHandshake},
+        HandshakeError, MidHandshake,
+    },
+    Error as WsError, Message as WsMessage, WebSocket,
+};
+
+/// longest wait between looking for clients and their commands.
+const POLL: Duration = Duration::from_millis(10);
+/// how long a client may take to finish its handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// a client half way through its handshake, when it started and where it is from.
+type Pending = (
+    MidHandshake<ServerHandshake<TcpStream, NoCallback>>,
+    Instant,
+    SocketAddr,
+);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum Format {
+    Json,
+    Binary,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct ServeArgs {
+    #[arg(long, default_value = "127.0.0.1:8080", help = "address to listen on.")]
+    listen: SocketAddr,
+    #[arg(
+        long,
+        value_enum,
+        default_value = "json",
+        help = "of the boards and diffs sent."
+    )]
+    format: Format,
+    #[arg(long, help = "start paused, until a client resumes or steps the run.")]
+    paused: bool,
+}
+
+/// what is sent to clients.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Message {
+    Board {
+        generation: usize,
+        population: usize,
+        width: u16,
+        height: u16,
+        cells: Vec<u8>,
+    },
+    Diff {
+        generation: usize,
+        population: usize,
+        cells: Vec<(u16, u16, u8)>,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl Message {
+    fn board(game: &Game) -> Self {
+        Self::Board {
+            generation: game.state.time,
+            population: game.state.population,
+            width: game.state.size.width,
+            height: game.state.size.height,
+            cells: game.states(),
+        }
+    }
+
+    fn encode(&self, format: Format) -> Result<WsMessage> {
+        if format == Format::Json {
+            return Ok(WsMessage::text(serde_json::to_string(self)?));
+        }
+        let mut out = Vec::new();
+        match self {
+            Self::Board {
+                generation,
+                population,
+                width,
+                height,
+                cells,
+            } => {
+                out.push(0);
+                out.extend((*generation as u64).to_le_bytes());
+                out.extend((*population as u64).to_le_bytes());
+                out.extend(width.to_le_bytes());
+                out.extend(height.to_le_bytes());
+                out.extend(cells);
+            }
+            Self::Diff {
+                generation,
+                population,
+                cells,
+            } => {
+                out.push(1);
+                out.extend((*generation as u64).to_le_bytes());
+                out.extend((*population as u64).to_le_bytes());
+                out.extend((cells.len() as u32).to_le_bytes());
+                for (x, y, state) in cells {
+                    out.extend(x.to_le_bytes());
+                    out.extend(y.to_le_bytes());
+                    out.push(*state);
+                }
+            }
+            Self::Error { message } => return Ok(WsMessage::text(message.as_str())),
+        }
+        Ok(WsMessage::binary(out))
+    }
+}
+
+/// the board as the clients have it, to send them what changed.
+struct Tracker {
+    generation: usize,
+    size: Size,
+    states: Vec<u8>,
+}
+
+impl Tracker {
+    fn of(game: &Game) -> Self {
+        Self {
+            generation: game.state.time,
+            size: game.state.size,
+            states: game.states(),
+        }
+    }
+
+    /// what changed since the last update, if anything did.
+    fn update(&mut self, game: &Game) -> Option<Message> {
+        let states = game.states();
+        let message = if game.state.size != self.size {
+            Message::board(game)
+        } else {
+            let width = usize::from(self.size.width);
+            let cells: Vec<_> = (0..states.len())
+                .filter(|&i| states[i] != self.states[i])
+                .map(|i| ((i % width) as u16, (i / width) as u16, states[i]))
+                .collect();
+            if cells.is_empty() && game.state.time == self.generation {
+                return None;
+            }
+            Message::Diff {
+                generation: game.state.time,
+                population: game.state.population,
+                cells,
+            }
+        };
+        (self.generation, self.size, self.states) = (game.state.time, game.state.size, states);
+        Some(message)
+    }
+}
+
+struct Server {
+    format: Format,
+    paused: bool,
+    clients: Vec<WebSocket<TcpStream>>,
+    /// clients still shaking hands, which is done a bit on every poll.
+    pending: Vec<Pending>,
+    tracker: Tracker,
+}
+
+impl Server {
+    /// take the clients waiting to connect, go on with their handshakes, and send the board to
+    /// those done with it.
+    fn accept(&mut self, listener: &TcpListener, game: &Game) -> Result<()> {
+        loop {
+            let (stream, addr) = match listener.accept() {
+                Ok(client) => client,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => return Err(e.into()),
+            };
+            // a client which never finishes its handshake mustn't stall the run, so it is only
+            // read from while it has sent something.
+            stream.set_nonblocking(true)?;
+            self.handshake(tungstenite::accept(stream), Instant::now(), addr, game)?;
+        }
+        for (handshake, start, addr) in std::mem::take(&mut self.pending) {
+            if start.elapsed() > HANDSHAKE_TIMEOUT {
+                eprintln!("{}: the handshake timed out", addr);
+                continue;
+            }
+            self.handshake(handshake.handshake(), start, addr, game)?;
+        }
+        Ok(())
+    }
+
+    /// keep a client on with its handshake, or send it the board once the handshake is done.
+    fn handshake(
+        &mut self,
+        result: Result<
+            WebSocket<TcpStream>,
+            HandshakeError<ServerHandshake<TcpStream, NoCallback>>,
+        >,
+        start: Instant,
+        addr: SocketAddr,
+        game: &Game,
+    ) -> Result<()> {
+        let mut client = match result {
+            Ok(client) => client,
+            Err(HandshakeError::Interrupted(handshake)) => {
+                self.pending.push((handshake, start, addr));
+                return Ok(());
+            }
+            Err(HandshakeError::Failure(e)) => {
+                eprintln!("{}: {}", addr, e);
+                return Ok(());
+            }
+        };
+        if client
+            .send(Message::board(game).encode(self.format)?)
+            .is_ok()
+        {
+            eprintln!("{} connected", addr);
+            self.clients.push(client);
+        }
+        Ok(())
+    }
+
+    /// follow the commands the clients sent, and drop those which left.
+    fn receive(&mut self, game: &mut Game) -> Result<()> {
+        let mut i = 0;
+        while i < self.clients.len() {
+            match self.clients[i].read() {
+                Ok(WsMessage::Text(text)) => {
+                    if let Err(e) = self.command(game, &text) {
+                        let message = format!("{:#}", e);
+                        let error = Message::Error { message }.encode(self.format)?;
+                        let _ = self.clients[i].send(error);
+                    }
+                }
+                Ok(_) => {}
+                Err(WsError::Io(e)) if e.kind() == ErrorKind::WouldBlock => i += 1,
+                Err(_) => drop(self.clients.remove(i)),
+            }
+        }
+        Ok(())
+    }
+
+    fn command(&mut self, game: &mut Game, command: &str) -> Result<()> {
+        match command.trim() {
+            "pause" => self.paused = true,
+            "resume" => self.paused = false,
+            event => event.parse::<Event>()?.apply(game)?,
+        }
+        Ok(())
+    }
+
+    /// send every client what changed, dropping those which can't take it.
+    fn broadcast(&mut self, game: &Game) -> Result<()> {
+        let Some(message) = self.tracker.update(game) else {
+            return Ok(());
+        };
+        let message = message.encode(self.format)?;
+        self.clients
+            .retain_mut(|client| match client.send(message.clone()) {
+                Ok(()) => true,
+                // queued until the client reads on.
+                Err(WsError::Io(e)) => e.kind() == ErrorKind::WouldBlock,
+                Err(_) => false,
+            });
+        Ok(())
+    }
+}
+
+/// run `game` for as long as the process lives, `--render-every` generations every
+/// `--duration`, streaming it to whoever connects.
+pub(crate) fn serve(args: &ServeArgs, mut game: Game) -> Result<()> {
+    let listener = TcpListener::bind(args.listen).with_context(|| args.listen.to_string())?;
+    listener.set_nonblocking(true)?;
+    eprintln!("serving on ws://{}", listener.local_addr()?);
+    let mut server = Server {
+        format: args.format,
+        paused: args.paused,
+        clients: Vec::new(),
+        pending: Vec::new(),
+        tracker: Tracker::of(&game),
+    };
+    let mut next = Instant::now();
+    loop {
+        server.accept(&listener, &game)?;
+        server.receive(&mut game)?;
+        let now = Instant::now();
+        if server.paused {
+            next = now;
+        } else if now >= next {
+            for _ in 0..game.state.render_every {
+                game.step()?;
+            }
+            next += game.state.duration;
+        }
+        server.broadcast(&game)?;
+        sleep(
+            next.saturating_duration_since(now)
+                .clamp(Duration::ZERO, POLL),
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn diff_and_encode() -> Result<()> {
+        let mut game = Game::blank("4:3".parse()?)?;
+        let mut tracker = Tracker::of(&game);
+        assert_eq!(tracker.update(&game), None);
+        game.set_pos((1, 2))?;
+        let diff = tracker.update(&game).unwrap();
+        assert_eq!(
+            diff,
+            Message::Diff {
+                generation: 0,
+                population: 1,
+                cells: vec![(1, 2, 1)],
+            }
+        );
+        assert_eq!(
+            diff.encode(Format::Json)?,
+            WsMessage::text(r#"{"type":"diff","generation":0,"population":1,"cells":[[1,2,1]]}"#)
+        );
+        let mut bytes = vec![1];
+        bytes.extend(0u64.to_le_bytes());
+        bytes.extend(1u64.to_le_bytes());
+        bytes.extend([1, 0, 0, 0, 1, 0, 2, 0, 1]);
+        assert_eq!(diff.encode(Format::Binary)?, WsMessage::binary(bytes));
+        // a generation passing is news even when nothing changed.
+        game.step()?;
+        assert!(matches!(
+            tracker.update(&game),
+            Some(Message::Diff { generation: 1, .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn idle_clients_dont_stall() -> Result<()> {
+        let game = Game::blank("4:4".parse()?)?;
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        listener.set_nonblocking(true)?;
+        let idle = TcpStream::connect(listener.local_addr()?)?;
+        let mut server = Server {
+            format: Format::Json,
+            paused: true,
+            clients: Vec::new(),
+            pending: Vec::new(),
+            tracker: Tracker::of(&game),
+        };
+        let start = Instant::now();
+        while server.pending.is_empty() {
+            server.accept(&listener, &game)?;
+        }
+        server.accept(&listener, &game)?;
+        assert!(start.elapsed() < Duration::from_millis(500));
+        assert!(server.clients.is_empty());
+        drop(idle);
+        Ok(())
+    }
+
+    #[test]
+    fn serve_a_client() -> Result<()> {
+        let mut game = Game::blank("6:6".parse()?)?;
+        game.load("blinker")?;
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        listener.set_nonblocking(true)?;
+        let url = format!("ws://{}", listener.local_addr()?);
+        let client = thread::spawn(move || -> Result<Vec<String>> {
+            let (mut ws, _) = tungstenite::connect(url)?;
+            let mut got = vec![ws.read()?.into_text()?.to_string()];
+            for command in ["cell 9:9 1", "cell 0:0 1"] {
+                ws.send(WsMessage::text(command))?;
+                got.push(ws.read()?.into_text()?.to_string());
+            }
+            Ok(got)
+        });
+        let mut server = Server {
+            format: Format::Json,
+            paused: true,
+            clients: Vec::new(),
+            pending: Vec::new(),
+            tracker: Tracker::of(&game),
+        };
+        while !client.is_finished() {
+            server.accept(&listener, &game)?;
+            server.receive(&mut game)?;
+            server.broadcast(&game)?;
+            sleep(POLL);
+        }
+        let got = client.join().unwrap()?;
+        assert!(got[0].starts_with(r#"{"type":"board","generation":0,"population":3,"width":6"#));
+        assert!(got[1].starts_with(r#"{"type":"error","message":"#));
+        assert_eq!(
+            got[2],
+            r#"{"type":"diff","generation":0,"population":4,"cells":[[0,0,1]]}"#
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,227 @@
+//! spectators: an instance run with `--host` sends every board it draws over tcp, and instances
+//! attached with the `spectate` subcommand draw the same board, e.g. on a projector while the
+//! host is edited on a laptop.
+//!
+//! a board is sent as a recording without events, as `--record` starts one. a spectator which
+//! falls behind gets the latest board only.
+
+use crate::{
+    config::Config,
+    frontend::Frontend,
+    screen::{self, read, Interrupted, RawMode, Screen},
+    ui::{view, LogArea, Snapshot, StatusBar},
+};
+use anyhow::{Context, Result};
+use clap::Args;
+use crossterm::{
+    cursor::{Hide, MoveTo},
+    event::{poll, Event, KeyCode, KeyEvent, KeyEventKind},
+    execute,
+    terminal::{Clear, ClearType, EnterAlternateScreen},
+};
+use life_game::{replay::Replay, Game};
+use std::{
+    io::{BufRead, BufReader, Lines, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, Receiver, Sender, TryRecvError},
+        Arc, Mutex, PoisonError,
+    },
+    thread,
+    time::Duration,
+};
+
+/// longest a spectator may take to take a board before it is dropped.
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+/// keys of the spectator, which can't be remapped.
+const HINTS: &str = "<q>: quit.";
+
+/// the latest board sent, and the spectators it was sent to.
+type Spectators = Arc<Mutex<(String, Vec<TcpStream>)>>;
+
+/// the host side: sends the boards it is given to every spectator.
+pub(crate) struct Host {
+    boards: Sender<String>,
+    /// the last board given, to send only boards which changed.
+    last: String,
+}
+
+impl Host {
+    pub(crate) fn start(addr: SocketAddr) -> Result<Self> {
+        let listener = TcpListener::bind(addr).with_context(|| addr.to_string())?;
+        let spectators: Spectators = Default::default();
+        thread::spawn({
+            let spectators = spectators.clone();
+            move || {
+                for mut stream in listener.incoming().flatten() {
+                    let mut spectators = spectators.lock().unwrap_or_else(PoisonError::into_inner);
+                    let _ = stream.set_write_timeout(Some(TIMEOUT));
+                    if stream.write_all(spectators.0.as_bytes()).is_ok() {
+                        spectators.1.push(stream);
+                    }
+                }
+            }
+        });
+        let (boards, sent) = mpsc::channel::<String>();
+        thread::spawn(move || {
+            while let Ok(board) = sent.recv() {
+                let board = sent.try_iter().last().unwrap_or(board);
+                let mut spectators = spectators.lock().unwrap_or_else(PoisonError::into_inner);
+                spectators
+                    .1
+                    .retain_mut(|stream| stream.write_all(board.as_bytes()).is_ok());
+                spectators.0 = board;
+            }
+        });
+        Ok(Self {
+            boards,
+            last: String::new(),
+        })
+    }
+
+    /// send `board` to the spectators, unless they have it already.
+    pub(crate) fn publish(&mut self, board: Replay) {
+        let board = board.to_string();
+        if board != self.last {
+            let _ = self.boards.send(board.clone());
+            self.last = board;
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct SpectateArgs {
+    #[arg(help = "host and port of an instance run with --host.")]
+    addr: String,
+}
+
+/// the next board of the host, none once it hung up.
+fn next_board(lines: &mut Lines<impl BufRead>) -> Result<Option<Replay>> {
+    let (mut text, mut in_board) = (String::new(), false);
+    for line in lines {
+        let line = line?;
+        text.push_str(&line);
+        text.push('\n');
+        if line == "board" {
+            in_board = true;
+        } else if in_board && line.contains('!') {
+            return Ok(Some(text.parse()?));
+        }
+    }
+    Ok(None)
+}
+
+/// pass the boards of the host on as games, until it or the spectator hangs up.
+fn receive(stream: TcpStream, games: Sender<Result<Game>>) {
+    let mut lines = BufReader::new(stream).lines();
+    loop {
+        let game = match next_board(&mut lines) {
+            Ok(Some(board)) => board.start(),
+            Ok(None) => return,
+            Err(e) => Err(e),
+        };
+        let failed = game.is_err();
+        if games.send(game).is_err() || failed {
+            return;
+        }
+    }
+}
+
+fn watch(
+    screen: &mut Screen,
+    games: Receiver<Result<Game>>,
+    args: &SpectateArgs,
+    config: &Config,
+) -> Result<()> {
+    let (mut game, mut gone) = (None::<Game>, None);
+    // glyphs are the spectator's own.
+    let frontend = Frontend {
+        glyphs: config.glyphs,
+        ..Frontend::default()
+    };
+    loop {
+        loop {
+            match games.try_recv() {
+                Ok(next) => game = Some(next?),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    gone = Some("the host is gone");
+                    break;
+                }
+            }
+        }
+        let status = match &game {
+            Some(game) => format!("{}\n", frontend.summary(game)),
+            None => "waiting for the first board\n".to_string(),
+        } + &format!("spectating {}. {}", args.addr, gone.unwrap_or("read only"));
+        let snapshot = game.as_ref().map(|game| Snapshot::of(game, &frontend));
+        screen.render(|frame| {
+            let hints = StatusBar {
+                status: &status,
+                hints: HINTS,
+            };
+            let area = frame.area();
+            if let Some(snapshot) = &snapshot {
+                let log = LogArea::new([].into_iter());
+                view(area, frame.buffer_mut(), snapshot.board(), hints, log);
+            } else {
+                frame.render_widget(hints, area);
+            }
+        })?;
+        if poll(Duration::from_millis(20))? {
+            if let Event::Key(KeyEvent {
+                code: KeyCode::Char('q'),
+                kind: KeyEventKind::Press,
+                ..
+            }) = read()?
+            {
+                return Ok(());
+            }
+        }
+    }
+}
+
+pub(crate) fn spectate(args: &SpectateArgs, config: &Config) -> Result<()> {
+    let stream = TcpStream::connect(&args.addr).with_context(|| args.addr.clone())?;
+    let (send, games) = mpsc::channel();
+    thread::spawn(move || receive(stream, send));
+
+    screen::restore_on_exit()?;
+    let mut screen = Screen::new(screen::terminal(), &config.colors)?;
+    execute!(screen, Hide, EnterAlternateScreen)?;
+    let result = match RawMode::enable().and_then(|_raw| watch(&mut screen, games, args, config)) {
+        Err(e) if e.is::<Interrupted>() => Ok(()),
+        result => result,
+    };
+    execute!(screen, MoveTo(0, 0), Clear(ClearType::FromCursorDown))?;
+    screen::restore_terminal()?;
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn host_and_spectate() -> Result<()> {
+        let mut game = Game::blank("6:5".parse()?)?;
+        game.load("glider")?;
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        drop(listener);
+        let mut host = Host::start(addr)?;
+        host.publish(Replay::of(&game));
+        let mut lines = BufReader::new(TcpStream::connect(addr)?).lines();
+        // the board sent before it came first.
+        let first = next_board(&mut lines)?.unwrap();
+        assert_eq!(first.start()?.to_native(), game.to_native());
+        host.publish(Replay::of(&game));
+        game.step()?;
+        host.publish(Replay::of(&game));
+        let next = next_board(&mut lines)?.unwrap();
+        assert_eq!(next.generation, 1);
+        assert_eq!(next.start()?.to_native(), game.to_native());
+        Ok(())
+    }
+}
@@ -0,0 +1,296 @@
+//! population history, cycle detection and the statistics of `--stats`.
+
+use crate::Size;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, VecDeque},
+    fmt::Display,
+    hash::{Hash, Hasher},
+};
+
+/// how many past generations are compared to detect stabilization.
+const WINDOW: usize = 64;
+
+/// population of one generation, and how it changed from the previous one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PopulationRecord {
+    /// the generation.
+    pub generation: usize,
+    /// live cells in it.
+    pub population: usize,
+    /// cells which came alive in it.
+    pub births: usize,
+    /// cells which died in it.
+    pub deaths: usize,
+}
+
+/// `history` as csv, a header and a line per generation.
+pub fn population_csv(history: &[PopulationRecord]) -> String {
+    let mut data = String::from("generation,population,births,deaths\n");
+    for r in history {
+        data.push_str(&format!(
+            "{},{},{},{}\n",
+            r.generation, r.population, r.births, r.deaths
+        ));
+    }
+    data
+}
+
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// population of the last `width` generations as a line of block characters.
+pub fn sparkline(history: &[PopulationRecord], width: usize) -> String {
+    let recent = &history[history.len().saturating_sub(width)..];
+    let min = recent.iter().map(|r| r.population).min().unwrap_or(0);
+    let max = recent.iter().map(|r| r.population).max().unwrap_or(0);
+    recent
+        .iter()
+        .map(|r| {
+            let level = if max == min {
+                0
+            } else {
+                (r.population - min) * (SPARKS.len() - 1) / (max - min)
+            };
+            SPARKS[level]
+        })
+        .collect()
+}
+
+/// remembers hashes of recent boards to notice when a board repeats.
+#[derive(Debug, Default)]
+pub struct CycleDetector {
+    recent: VecDeque<u64>,
+    seen: HashMap<u64, usize>,
+}
+
+impl CycleDetector {
+    /// no board was checked yet.
+    pub fn is_empty(&self) -> bool {
+        self.recent.is_empty()
+    }
+
+    /// record the board of `generation`. returns (generation the cycle started, period) if it
+    /// repeats one of the last boards.
+    pub fn check(&mut self, board: &[bool], generation: usize) -> Option<(usize, usize)> {
+        let mut hasher = DefaultHasher::new();
+        board.hash(&mut hasher);
+        let hash = hasher.finish();
+        if let Some(&at) = self.seen.get(&hash) {
+            return Some((at, generation - at));
+        }
+        if self.recent.len() == WINDOW {
+            if let Some(old) = self.recent.pop_front() {
+                self.seen.remove(&old);
+            }
+        }
+        self.recent.push_back(hash);
+        self.seen.insert(hash, generation);
+        None
+    }
+}
+
+/// methuselah-style statistics of a run, and lifespans of connected objects.
+#[derive(Debug)]
+pub struct RunStats {
+    size: Size,
+    generation: usize,
+    /// live cells of the last generation recorded.
+    pub population: usize,
+    /// (the highest population, generation it was reached at)
+    pub max_population: (usize, usize),
+    /// (generation the cycle started, period)
+    pub stabilized: Option<(usize, usize)>,
+    final_ash: Option<usize>,
+    cycles: CycleDetector,
+    /// object label of every cell, 0 is dead.
+    labels: Vec<usize>,
+    /// generation each living object was born, indexed by label - 1.
+    births: Vec<usize>,
+    lifespans: Vec<usize>,
+}
+
+impl Display for RunStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        writeln!(f, "generations: {}", self.generation)?;
+        writeln!(f, "population: {}", self.population)?;
+        writeln!(
+            f,
+            "max population: {} at generation {}",
+            self.max_population.0, self.max_population.1
+        )?;
+        match self.stabilized {
+            Some((at, period)) => {
+                writeln!(f, "stabilized at generation {} (period {})", at, period)?
+            }
+            None => writeln!(f, "not stabilized yet")?,
+        }
+        if let Some(ash) = self.final_ash {
+            writeln!(f, "final ash: {} cells", ash)?;
+        }
+        writeln!(f, "living objects: {}", self.births.len())?;
+        write!(f, "dead objects: {}", self.lifespans.len())?;
+        if let Some(average) = self.average_lifespan() {
+            write!(f, ", average lifespan: {:.1} generations", average)?;
+        }
+        Ok(())
+    }
+}
+
+impl RunStats {
+    /// statistics of a run starting from `board` at `generation`.
+    pub fn new(size: Size, board: &[bool], generation: usize) -> Self {
+        let mut stats = Self {
+            size,
+            generation,
+            population: 0,
+            max_population: (0, generation),
+            stabilized: None,
+            final_ash: None,
+            cycles: CycleDetector::default(),
+            labels: vec![0; board.len()],
+            births: Vec::new(),
+            lifespans: Vec::new(),
+        };
+        stats.record(board, generation);
+        stats
+    }
+
+    /// generations the objects which died lived on average, if any died.
+    pub fn average_lifespan(&self) -> Option<f64> {
+        (!self.lifespans.is_empty())
+            .then(|| self.lifespans.iter().sum::<usize>() as f64 / self.lifespans.len() as f64)
+    }
+
+    /// update the statistics with the board of `generation`.
+    pub fn record(&mut self, board: &[bool], generation: usize) {
+        self.generation = generation;
+        self.population = board.iter().filter(|&&v| v).count();
+        if self.population > self.max_population.0 {
+            self.max_population = (self.population, generation);
+        }
+        if self.stabilized.is_some() {
+            // objects just cycle from here.
+            return;
+        }
+        self.track_objects(board, generation);
+
+        if let Some(cycle) = self.cycles.check(board, generation) {
+            self.stabilized = Some(cycle);
+            self.final_ash = Some(self.population);
+        }
+    }
+
+    fn neighbours(&self, idx: usize) -> impl Iterator<Item = usize> {
+        let (width, height) = (self.size.width as usize, self.size.height as usize);
+        let (x, y) = (idx % width, idx / width);
+        [width - 1, 0, 1].into_iter().flat_map(move |dx| {
+            [height - 1, 0, 1]
+                .into_iter()
+                .map(move |dy| (y + dy) % height * width + (x + dx) % width)
+        })
+    }
+
+    /// label the connected objects of `board`, inheriting the birth of any overlapping object
+    /// of the previous generation. objects nothing inherits from are dead.
+    fn track_objects(&mut self, board: &[bool], generation: usize) {
+        let mut labels = vec![0; board.len()];
+        let mut births = Vec::new();
+        let mut continued = vec![false; self.births.len()];
+        for start in 0..board.len() {
+            if !board[start] || labels[start] != 0 {
+                continue;
+            }
+            let label = births.len() + 1;
+            let mut birth = generation;
+            labels[start] = label;
+            let mut stack = vec![start];
+            while let Some(idx) = stack.pop() {
+                for n in self.neighbours(idx) {
+                    if let Some(prev) = self.labels[n].checked_sub(1) {
+                        birth = birth.min(self.births[prev]);
+                        continued[prev] = true;
+                    }
+                    if board[n] && labels[n] == 0 {
+                        labels[n] = label;
+                        stack.push(n);
+                    }
+                }
+            }
+            births.push(birth);
+        }
+        for (prev, birth) in self.births.iter().enumerate() {
+            if !continued[prev] {
+                self.lifespans.push(generation - birth);
+            }
+        }
+        self.labels = labels;
+        self.births = births;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn board(rows: &[&str]) -> (Size, Vec<bool>) {
+        let size = Size {
+            width: rows[0].len() as u16,
+            height: rows.len() as u16,
+        };
+        (size, rows.concat().chars().map(|c| c == '@').collect())
+    }
+
+    #[test]
+    fn sparkline_scales_to_window() {
+        let history: Vec<PopulationRecord> = [5, 0, 7, 14, 3]
+            .into_iter()
+            .enumerate()
+            .map(|(generation, population)| PopulationRecord {
+                generation,
+                population,
+                births: 0,
+                deaths: 0,
+            })
+            .collect();
+        assert_eq!(sparkline(&history, 4), "▁▄█▂");
+        assert_eq!(sparkline(&history[..1], 60), "▁");
+    }
+
+    #[test]
+    fn detect_cycles() {
+        let mut cycles = CycleDetector::default();
+        assert_eq!(cycles.check(&[true, false], 0), None);
+        assert_eq!(cycles.check(&[false, true], 1), None);
+        assert_eq!(cycles.check(&[false, false], 2), None);
+        assert_eq!(cycles.check(&[false, true], 3), Some((1, 2)));
+    }
+
+    #[test]
+    fn blinker_stabilizes() {
+        let (size, horizontal) = board(&["-----", "-----", "-@@@-", "-----", "-----"]);
+        let (_, vertical) = board(&["-----", "--@--", "--@--", "--@--", "-----"]);
+        let mut stats = RunStats::new(size, &horizontal, 0);
+        stats.record(&vertical, 1);
+        assert_eq!(stats.stabilized, None);
+        stats.record(&horizontal, 2);
+        assert_eq!(stats.stabilized, Some((0, 2)));
+        assert_eq!(stats.final_ash, Some(3));
+        // the blinker is a single object that never died.
+        assert_eq!(stats.births, [0]);
+        assert_eq!(stats.average_lifespan(), None);
+    }
+
+    #[test]
+    fn lifespans_of_dead_objects() {
+        let (size, start) = board(&[
+            "--------", "-@------", "--------", "-----@@-", "-----@@-", "--------",
+        ]);
+        let (_, block) = board(&[
+            "--------", "--------", "--------", "-----@@-", "-----@@-", "--------",
+        ]);
+        let mut stats = RunStats::new(size, &start, 0);
+        stats.record(&block, 1);
+        assert_eq!(stats.lifespans, [1]);
+        assert_eq!(stats.births.len(), 1);
+        assert_eq!(stats.average_lifespan(), Some(1.0));
+    }
+}
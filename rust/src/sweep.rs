@@ -0,0 +1,222 @@
+use anyhow::{anyhow, ensure, Error, Result};
+use clap::Args;
+use life_game::{Game, Size};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    fmt::Write as _,
+    fs::write,
+    path::PathBuf,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread::{available_parallelism, scope},
+};
+
+/// inclusive range of values, written `<start>..<end>:<step>`, or a single value.
+#[derive(Debug, Clone)]
+pub(crate) struct Steps(Vec<f64>);
+
+impl FromStr for Steps {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let Some((range, step)) = s.split_once(':') else {
+            return Ok(Self(vec![s.parse()?]));
+        };
+        let (start, end) = range.split_once("..").ok_or_else(|| {
+            anyhow!(r#"Invalid Format!.note:: you must use a "<start>..<end>:<step>" format."#)
+        })?;
+        let (start, end, step): (f64, f64, f64) = (start.parse()?, end.parse()?, step.parse()?);
+        ensure!(step > 0.0 && start <= end, "empty range: {}", s);
+        // count the steps instead of accumulating, so rounding errors don't drop the end.
+        let count = ((end - start) / step + 1e-9).floor() as usize;
+        Ok(Self((0..=count).map(|i| start + step * i as f64).collect()))
+    }
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct SweepArgs {
+    #[arg(long, default_value = "160:32")]
+    size: Size,
+    #[arg(
+        long,
+        default_value = "0.5",
+        help = "initial density, or <start>..<end>:<step>."
+    )]
+    density: Steps,
+    #[arg(long, default_value = "10", help = "runs per density, seeded 0..N.")]
+    seeds: u64,
+    #[arg(long, default_value = "1000", help = "max generations of each run.")]
+    generations: usize,
+    #[arg(long, value_name = "FILE", help = "write every run as csv.")]
+    stats_out: Option<PathBuf>,
+    #[arg(long, help = "worker threads. default: available cores.")]
+    threads: Option<usize>,
+}
+
+#[derive(Debug)]
+struct Run {
+    density: f64,
+    seed: u64,
+    generations: usize,
+    population: usize,
+    max_population: (usize, usize),
+    stabilized: Option<(usize, usize)>,
+    average_lifespan: Option<f64>,
+}
+
+fn run_one(size: Size, density: f64, seed: u64, generations: usize) -> Result<Run> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let board = (0..size.width as usize * size.height as usize)
+        .map(|_| rng.gen_bool(density))
+        .collect();
+    let mut game = Game::from_board(size, board)?;
+    game.reset_stats();
+    for _ in 0..generations {
+        game.step()?;
+        if game.stats().is_some_and(|s| s.stabilized.is_some()) {
+            break;
+        }
+    }
+    let stats = game.stats().ok_or_else(|| anyhow!("statistics are off"))?;
+    Ok(Run {
+        density,
+        seed,
+        generations: game.state.time,
+        population: stats.population,
+        max_population: stats.max_population,
+        stabilized: stats.stabilized,
+        average_lifespan: stats.average_lifespan(),
+    })
+}
+
+fn csv(runs: &[Run]) -> String {
+    let mut data = String::from(
+        "density,seed,generations,population,max_population,max_population_generation,stabilized_at,period,average_lifespan\n",
+    );
+    let opt = |v: Option<String>| v.unwrap_or_default();
+    for run in runs {
+        let _ = writeln!(
+            data,
+            "{},{},{},{},{},{},{},{},{}",
+            run.density,
+            run.seed,
+            run.generations,
+            run.population,
+            run.max_population.0,
+            run.max_population.1,
+            opt(run.stabilized.map(|s| s.0.to_string())),
+            opt(run.stabilized.map(|s| s.1.to_string())),
+            opt(run.average_lifespan.map(|a| format!("{:.2}", a))),
+        );
+    }
+    data
+}
+
+fn summary(runs: &[Run], densities: &[f64]) -> String {
+    let mut data =
+        String::from("density\truns\tstabilized\tmean population\tmean max population\n");
+    for &density in densities {
+        let group: Vec<&Run> = runs.iter().filter(|r| r.density == density).collect();
+        let n = group.len().max(1) as f64;
+        let _ = writeln!(
+            data,
+            "{:.3}\t{}\t{}\t{:.1}\t{:.1}",
+            density,
+            group.len(),
+            group.iter().filter(|r| r.stabilized.is_some()).count(),
+            group.iter().map(|r| r.population).sum::<usize>() as f64 / n,
+            group.iter().map(|r| r.max_population.0).sum::<usize>() as f64 / n,
+        );
+    }
+    data
+}
+
+/// run every (density, seed) pair headlessly on worker threads.
+fn run_all(args: &SweepArgs) -> Result<Vec<Run>> {
+    let jobs: Vec<(f64, u64)> = args
+        .density
+        .0
+        .iter()
+        .flat_map(|&d| (0..args.seeds).map(move |s| (d, s)))
+        .collect();
+    let threads = args
+        .threads
+        .unwrap_or_else(|| available_parallelism().map_or(1, |n| n.get()))
+        .clamp(1, jobs.len().max(1));
+
+    let next = AtomicUsize::new(0);
+    let runs = Mutex::new(Vec::with_capacity(jobs.len()));
+    scope(|s| -> Result<()> {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                s.spawn(|| -> Result<()> {
+                    while let Some(&(density, seed)) =
+                        jobs.get(next.fetch_add(1, Ordering::Relaxed))
+                    {
+                        let run = run_one(args.size, density, seed, args.generations)?;
+                        runs.lock().map_err(|_| anyhow!("poisoned"))?.push(run);
+                    }
+                    Ok(())
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().map_err(|_| anyhow!("worker panicked"))??;
+        }
+        Ok(())
+    })?;
+
+    let mut runs = runs.into_inner().map_err(|_| anyhow!("poisoned"))?;
+    runs.sort_by(|a, b| a.density.total_cmp(&b.density).then(a.seed.cmp(&b.seed)));
+    Ok(runs)
+}
+
+pub(crate) fn sweep(args: &SweepArgs) -> Result<()> {
+    let runs = run_all(args)?;
+    if let Some(path) = &args.stats_out {
+        write(path, csv(&runs))?;
+    }
+    print!("{}", summary(&runs, &args.density.0));
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_steps() -> Result<()> {
+        let steps: Steps = "0.1..0.6:0.05".parse()?;
+        assert_eq!(steps.0.len(), 11);
+        assert!((steps.0[10] - 0.6).abs() < 1e-9);
+        assert_eq!("0.3".parse::<Steps>()?.0, [0.3]);
+        assert!("0.6..0.1:0.1".parse::<Steps>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn sweep_runs_every_pair() -> Result<()> {
+        let args = SweepArgs {
+            size: Size {
+                width: 8,
+                height: 8,
+            },
+            density: "0.2..0.4:0.2".parse()?,
+            seeds: 3,
+            generations: 30,
+            stats_out: None,
+            threads: Some(2),
+        };
+        let runs = run_all(&args)?;
+        assert_eq!(runs.len(), 6);
+        assert_eq!((runs[0].seed, runs[5].seed), (0, 2));
+        // seeded runs are reproducible.
+        let again = run_one(args.size, runs[0].density, 0, 30)?;
+        assert_eq!(again.population, runs[0].population);
+        assert_eq!(csv(&runs).lines().count(), 7);
+        Ok(())
+    }
+}
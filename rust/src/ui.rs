@@ -0,0 +1,623 @@
+//! the widgets the tui is drawn with.
+
+use crate::{frontend::Frontend, notify::Notice};
+use life_game::{
+    clip::Clip,
+    engine,
+    replay::Replay,
+    rule::{Rule, CONDUCTOR},
+    Bounds, Game, Glyphs, Size,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Text},
+    widgets::{Block, Paragraph, Widget, Wrap},
+};
+use std::ops::Range;
+
+const TAB_WIDTH: usize = 8;
+/// colours of live cells under Immigration and QuadLife, in order.
+const COLOURS: [Color; 4] = [Color::Red, Color::LightBlue, Color::Green, Color::Yellow];
+
+/// `text` with tabs expanded to the next multiple of 8 columns. ratatui doesn't expand them.
+fn expand_tabs(text: &str) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    let mut column = 0;
+    for c in text.chars() {
+        match c {
+            '\t' => {
+                let n = TAB_WIDTH - column % TAB_WIDTH;
+                expanded.extend(std::iter::repeat_n(' ', n));
+                column += n;
+            }
+            '\n' => {
+                expanded.push(c);
+                column = 0;
+            }
+            c => {
+                expanded.push(c);
+                column += 1;
+            }
+        }
+    }
+    expanded
+}
+
+/// plain text, wrapped at the edge of the area.
+pub(crate) fn text(text: &str) -> Paragraph<'static> {
+    Paragraph::new(expand_tabs(text)).wrap(Wrap { trim: false })
+}
+
+/// rows `text` takes up when wrapped at `width`.
+fn height(text: &str, width: u16) -> u16 {
+    if text.is_empty() {
+        return 0;
+    }
+    self::text(text).line_count(width) as u16
+}
+
+/// what the board widget needs of a game, copied so it can be drawn without the game.
+pub(crate) struct Snapshot {
+    pub(crate) cells: Vec<bool>,
+    pub(crate) dying: Vec<u8>,
+    /// width of the whole board.
+    pub(crate) width: u16,
+    pub(crate) visible: (Range<u16>, Range<u16>),
+    /// terminal columns of a cell, 2 on a hexagonal board.
+    pub(crate) columns: u16,
+    pub(crate) rule: Rule,
+    pub(crate) glyphs: Glyphs,
+    /// the lines under the board.
+    pub(crate) summary: String,
+    pub(crate) outline: Option<Bounds>,
+    pub(crate) seam: bool,
+    pub(crate) ghosts: u16,
+    pub(crate) rulers: (u16, u16),
+    pub(crate) time: usize,
+}
+
+impl Snapshot {
+    pub(crate) fn of(game: &Game, frontend: &Frontend) -> Self {
+        Self {
+            cells: game.game.clone(),
+            dying: game.dying.clone(),
+            width: game.state.size.width,
+            visible: frontend.visible(&game.state, 1),
+            columns: game.state.cell_columns(),
+            rule: game.state.rule,
+            glyphs: frontend.glyphs,
+            summary: frontend.summary(game),
+            outline: frontend.outline(game),
+            seam: frontend.seam.is_some(),
+            ghosts: frontend.ghosts(&game.state),
+            rulers: frontend.ruler_size(&game.state),
+            time: game.state.time,
+        }
+    }
+
+    /// the board as a recording without events, as spectators get it.
+    pub(crate) fn replay(&self) -> Replay {
+        Replay {
+            generation: self.time,
+            seed: None,
+            noise: None,
+            rule: self.rule,
+            size: Size {
+                width: self.width,
+                height: (self.cells.len() / usize::from(self.width.max(1))) as u16,
+            },
+            states: engine::states(&self.cells, &self.dying),
+            events: Vec::new(),
+        }
+    }
+
+    pub(crate) fn board(&self) -> BoardWidget<'_> {
+        BoardWidget {
+            cells: &self.cells,
+            dying: &self.dying,
+            width: self.width,
+            visible: self.visible.clone(),
+            columns: self.columns,
+            zoom: 1,
+            rule: self.rule,
+            glyphs: self.glyphs,
+            highlight: &|_| false,
+            outline: self.outline,
+            seam: self.seam,
+            ghosts: self.ghosts,
+            rulers: self.rulers,
+            preview: None,
+        }
+    }
+}
+
+/// the visible part of a board, each cell a `zoom`x`zoom` block. dying cells are blue, and dim
+/// after the first dying state, live cells of coloured rules have their colour, and Wireworld has
+/// the usual colors. cells of a hexagonal board have a gap after them, and odd rows
+/// are half a cell to the right.
+#[derive(Clone)]
+pub(crate) struct BoardWidget<'a> {
+    cells: &'a [bool],
+    dying: &'a [u8],
+    width: u16,
+    visible: (Range<u16>, Range<u16>),
+    columns: u16,
+    zoom: u16,
+    rule: Rule,
+    glyphs: Glyphs,
+    /// cells drawn with the selected glyphs.
+    highlight: &'a dyn Fn((u16, u16)) -> bool,
+    /// cells on the edge of this box are drawn with the selected glyphs too.
+    outline: Option<Bounds>,
+    /// cells on the edges of the board, where it wraps around, have a grey background.
+    seam: bool,
+    /// dim copies of this many cells of the opposite edges are drawn around the board.
+    ghosts: u16,
+    /// columns left of the board and rows above it numbered, or `(0, 0)` for no rulers.
+    rulers: (u16, u16),
+    /// live cells of a clip drawn over the board in green, with its top left at the position.
+    preview: Option<(&'a Clip, (u16, u16))>,
+}
+
+impl<'a> BoardWidget<'a> {
+    pub(crate) fn new(
+        game: &'a Game,
+        frontend: &Frontend,
+        zoom: u16,
+        highlight: &'a dyn Fn((u16, u16)) -> bool,
+    ) -> Self {
+        Self {
+            cells: &game.game,
+            dying: &game.dying,
+            width: game.state.size.width,
+            visible: frontend.visible(&game.state, zoom),
+            columns: game.state.cell_columns(),
+            zoom,
+            rule: game.state.rule,
+            glyphs: frontend.glyphs,
+            highlight,
+            outline: frontend.outline(game),
+            seam: frontend.seam.is_some(),
+            ghosts: frontend.ghosts(&game.state),
+            rulers: frontend.ruler_size(&game.state),
+            preview: None,
+        }
+    }
+
+    /// draw the live cells of a clip over the board, e.g. to show where it would be pasted.
+    pub(crate) fn preview(self, preview: Option<(&'a Clip, (u16, u16))>) -> Self {
+        Self { preview, ..self }
+    }
+
+    /// where the cell at `pos` is drawn when the board is rendered into `area`, kept inside it.
+    pub(crate) fn cell_at(&self, area: Rect, pos: (u16, u16)) -> (u16, u16) {
+        let (xs, ys) = &self.visible;
+        let area = self.cells_area(area);
+        let column = self.column_of(pos.0 - xs.start + self.ghosts, pos.1);
+        let row = self.row_of(pos.1 - ys.start + self.ghosts);
+        (
+            area.x + column.min(area.width.saturating_sub(1)),
+            area.y + row.min(area.height.saturating_sub(1)),
+        )
+    }
+
+    /// the part of `area` right of and under the rulers.
+    fn cells_area(&self, area: Rect) -> Rect {
+        let (rx, ry) = self.rulers;
+        Rect {
+            x: area.x + rx.min(area.width),
+            y: area.y + ry.min(area.height),
+            width: area.width.saturating_sub(rx),
+            height: area.height.saturating_sub(ry),
+        }
+    }
+
+    /// first terminal column of the `column`th visible cell of row `y`.
+    fn column_of(&self, column: u16, y: u16) -> u16 {
+        let offset = if self.columns > 1 && y % 2 == 1 {
+            self.zoom
+        } else {
+            0
+        };
+        column
+            .saturating_mul(self.zoom)
+            .saturating_mul(self.columns)
+            .saturating_add(offset)
+    }
+
+    /// first terminal row of the `row`th visible row.
+    fn row_of(&self, row: u16) -> u16 {
+        row.saturating_mul(self.zoom)
+    }
+
+    /// whether the preview has a live cell at `pos`. it wraps around the edges as pasting does.
+    fn on_preview(&self, (x, y): (u16, u16)) -> bool {
+        self.preview.is_some_and(|(clip, (px, py))| {
+            let height = self.cells.len() / usize::from(self.width);
+            let dx = (usize::from(x) + usize::from(self.width) - usize::from(px))
+                % usize::from(self.width);
+            let dy = (usize::from(y) + height - usize::from(py)) % height;
+            dx < usize::from(clip.width)
+                && dy < usize::from(clip.height)
+                && clip.cells[dy * usize::from(clip.width) + dx]
+        })
+    }
+
+    fn on_outline(&self, (x, y): (u16, u16)) -> bool {
+        self.outline.is_some_and(|((l, t), (r, b))| {
+            (x == l || x == r) && (t..=b).contains(&y) || (y == t || y == b) && (l..=r).contains(&x)
+        })
+    }
+
+    /// how a cell in `state` is drawn, on top of the screen's colors.
+    fn style(&self, state: u8) -> Style {
+        match state {
+            0 => Style::new(),
+            _ if self.rule.colours() > 0 => {
+                Style::new().fg(COLOURS[usize::from(state - 1) % COLOURS.len()])
+            }
+            _ if !self.rule.is_wireworld() => match state {
+                0 | 1 => Style::new(),
+                2 => Style::new().fg(Color::Blue),
+                _ => Style::new().fg(Color::Blue).add_modifier(Modifier::DIM),
+            },
+            1 => Style::new().fg(Color::LightBlue),
+            2 => Style::new().fg(Color::Red),
+            CONDUCTOR => Style::new().fg(Color::Yellow),
+            _ => Style::new(),
+        }
+    }
+}
+
+impl Widget for BoardWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (xs, ys) = self.visible.clone();
+        let area = area.intersection(buf.area);
+        let (width, height) = (
+            i32::from(self.width),
+            (self.cells.len() / usize::from(self.width)) as i32,
+        );
+        let ghosts = i32::from(self.ghosts);
+        let xs = i32::from(xs.start) - ghosts..i32::from(xs.end) + ghosts;
+        let ys = i32::from(ys.start) - ghosts..i32::from(ys.end) + ghosts;
+        let board = self.cells_area(area);
+        if self.rulers != (0, 0) {
+            // the last digit of every column, every tenth one brighter, and whole row numbers.
+            let dim = Style::new().fg(Color::DarkGray);
+            for (column, x) in xs.clone().enumerate() {
+                let x = x.rem_euclid(width);
+                let sx = self.column_of(column as u16, 0);
+                if sx >= board.width {
+                    break;
+                }
+                let digit = char::from(b'0' + (x % 10) as u8);
+                let style = if x % 10 == 0 { Style::new() } else { dim };
+                buf[(board.x + sx, area.y)].set_char(digit).set_style(style);
+            }
+            let label = usize::from(self.rulers.0 - 1);
+            for (row, y) in ys.clone().enumerate() {
+                let sy = self.row_of(row as u16);
+                if sy >= board.height {
+                    break;
+                }
+                let y = format!("{:>1$}", y.rem_euclid(height), label);
+                buf.set_stringn(area.x, board.y + sy, y, label.min(area.width.into()), dim);
+            }
+        }
+        let area = board;
+        for (row, y) in ys.enumerate() {
+            // rows and columns past the area aren't drawn, and counting on could overflow.
+            if self.row_of(row as u16) >= area.height {
+                break;
+            }
+            for (column, x) in xs.clone().enumerate() {
+                if self.column_of(column as u16, 0) >= area.width {
+                    break;
+                }
+                let ghost = !(0..width).contains(&x) || !(0..height).contains(&y);
+                let (x, y) = (x.rem_euclid(width) as u16, y.rem_euclid(height) as u16);
+                let idx = y as usize * self.width as usize + x as usize;
+                let state = if self.cells[idx] {
+                    self.dying[idx].max(1)
+                } else {
+                    self.dying[idx]
+                };
+                let selected = !ghost && ((self.highlight)((x, y)) || self.on_outline((x, y)));
+                let preview = !ghost && self.on_preview((x, y));
+                let glyph = match preview {
+                    true => self.glyphs.alive,
+                    false => self.glyphs.pick(&self.rule, state, selected),
+                };
+                let edge =
+                    x == 0 || y == 0 || i32::from(x) == width - 1 || i32::from(y) == height - 1;
+                let style = match self.style(state) {
+                    _ if preview => Style::new().fg(Color::Green),
+                    style if ghost => style.add_modifier(Modifier::DIM),
+                    style if self.seam && edge => style.bg(Color::DarkGray),
+                    style => style,
+                };
+                for dy in 0..self.zoom {
+                    for dx in 0..self.zoom {
+                        let (sx, sy) = (
+                            self.column_of(column as u16, y).saturating_add(dx),
+                            self.row_of(row as u16).saturating_add(dy),
+                        );
+                        if sx < area.width && sy < area.height {
+                            buf[(area.x + sx, area.y + sy)]
+                                .set_char(glyph)
+                                .set_style(style);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// lines about the board, and the keys of the current mode under them.
+pub(crate) struct StatusBar<'a> {
+    pub(crate) status: &'a str,
+    pub(crate) hints: &'a str,
+}
+
+impl Widget for StatusBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [status, hints] = Layout::vertical([
+            Constraint::Length(height(self.status, area.width)),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        text(self.status).render(status, buf);
+        text(self.hints)
+            .style(Style::new().add_modifier(Modifier::DIM))
+            .render(hints, buf);
+    }
+}
+
+/// notifications, oldest first. errors are red, and ones which are no longer fresh are dim.
+pub(crate) struct LogArea<'a> {
+    notes: Vec<&'a Notice>,
+}
+
+impl<'a> LogArea<'a> {
+    pub(crate) fn new(notes: impl Iterator<Item = &'a Notice>) -> Self {
+        Self {
+            notes: notes.collect(),
+        }
+    }
+
+    fn paragraph(&self) -> Paragraph<'static> {
+        let lines: Vec<Line> = self
+            .notes
+            .iter()
+            .map(|note| {
+                let mut style = Style::new();
+                if note.error {
+                    style = style.fg(Color::Red).add_modifier(Modifier::BOLD);
+                }
+                if !note.fresh() {
+                    style = style.add_modifier(Modifier::DIM);
+                }
+                Line::styled(expand_tabs(&note.to_string()), style)
+            })
+            .collect();
+        Paragraph::new(Text::from(lines)).wrap(Wrap { trim: false })
+    }
+
+    fn height(&self, width: u16) -> u16 {
+        if self.notes.is_empty() {
+            return 0;
+        }
+        self.paragraph().line_count(width) as u16
+    }
+
+    /// the log in a box titled `title`, for a full screen list.
+    pub(crate) fn boxed(&self, title: &'a str) -> Paragraph<'a> {
+        self.paragraph().block(Block::bordered().title(title))
+    }
+}
+
+impl Widget for LogArea<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        self.paragraph().render(area, buf);
+    }
+}
+
+/// a board with its status bar and the log under it, filling `area`. returns where the board is.
+pub(crate) fn view(
+    area: Rect,
+    buf: &mut Buffer,
+    board: BoardWidget,
+    status: StatusBar,
+    log: LogArea,
+) -> Rect {
+    let width = area.width;
+    let [board_area, status_area, log_area] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(height(status.status, width) + height(status.hints, width)),
+        Constraint::Length(log.height(width)),
+    ])
+    .areas(area);
+    board.render(board_area, buf);
+    status.render(status_area, buf);
+    log.render(log_area, buf);
+    board_area
+}
+
+/// full screen text in a box, e.g. the help.
+pub(crate) struct Panel<'a> {
+    pub(crate) title: &'a str,
+    pub(crate) body: &'a str,
+}
+
+impl Widget for Panel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        text(self.body)
+            .block(Block::bordered().title(self.title))
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::notify::Notifications;
+
+    #[test]
+    fn draw_a_zoomed_board() -> anyhow::Result<()> {
+        let mut game = Game::blank("3:2".parse()?)?;
+        game.set_pos((0, 0))?;
+        let frontend = Frontend::default();
+        let mut buf = Buffer::empty(Rect::new(0, 0, 5, 3));
+        let board = BoardWidget::new(&game, &frontend, 2, &|p| p == (1, 0));
+        assert_eq!(board.cell_at(buf.area, (1, 0)), (2, 0));
+        board.render(buf.area, &mut buf);
+        // the third column doesn't fit.
+        assert_eq!(buf, Buffer::with_lines(["@@++-", "@@++-", "-----"]));
+        Ok(())
+    }
+
+    #[test]
+    fn draw_a_board_zoomed_past_the_area() -> anyhow::Result<()> {
+        let game = Game::blank("100:100".parse()?)?;
+        let frontend = Frontend::default();
+        let mut buf = Buffer::empty(Rect::new(0, 0, 5, 3));
+        let board = BoardWidget::new(&game, &frontend, 1000, &|_| false);
+        // cells far off the area would be drawn past the last terminal column.
+        assert_eq!(board.cell_at(buf.area, (99, 99)), (4, 2));
+        board.render(buf.area, &mut buf);
+        assert_eq!(buf, Buffer::with_lines(["-----"; 3]));
+        Ok(())
+    }
+
+    #[test]
+    fn draw_dying_cells() -> anyhow::Result<()> {
+        let mut game = Game::blank("2:1".parse()?)?;
+        game.state.rule = Rule::brians_brain();
+        game.set_state((0, 0), 1)?;
+        game.set_state((1, 0), 2)?;
+        let frontend = Frontend::default();
+        let mut buf = Buffer::empty(Rect::new(0, 0, 2, 1));
+        BoardWidget::new(&game, &frontend, 1, &|_| false).render(buf.area, &mut buf);
+        assert_eq!((buf[(0, 0)].symbol(), buf[(1, 0)].symbol()), ("@", "o"));
+        assert_eq!(
+            (buf[(0, 0)].fg, buf[(1, 0)].fg),
+            (Color::Reset, Color::Blue)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn draw_the_outline() -> anyhow::Result<()> {
+        let mut game = Game::blank("4:4".parse()?)?;
+        game.set_pos((0, 0))?;
+        game.set_pos((2, 2))?;
+        let frontend = Frontend {
+            outline: true,
+            ..Frontend::default()
+        };
+        let mut buf = Buffer::empty(Rect::new(0, 0, 4, 4));
+        BoardWidget::new(&game, &frontend, 1, &|_| false).render(buf.area, &mut buf);
+        assert_eq!(buf, Buffer::with_lines(["#++-", "+-+-", "++#-", "----"]));
+        Ok(())
+    }
+
+    #[test]
+    fn draw_the_seam() -> anyhow::Result<()> {
+        let mut game = Game::blank("3:2".parse()?)?;
+        game.set_pos((0, 0))?;
+        let frontend = Frontend {
+            seam: Some(1),
+            ..Frontend::default()
+        };
+        let mut buf = Buffer::empty(Rect::new(0, 0, 5, 4));
+        let board = BoardWidget::new(&game, &frontend, 1, &|_| false);
+        assert_eq!(board.cell_at(buf.area, (0, 0)), (1, 1));
+        board.render(buf.area, &mut buf);
+        // the last row and column again above and left of the board, and the first ones below
+        // and right of it.
+        assert_eq!(
+            buf.content.iter().map(|c| c.symbol()).collect::<String>(),
+            "------@--@------@--@"
+        );
+        assert!(buf[(0, 1)].modifier.contains(Modifier::DIM));
+        assert_eq!(
+            (buf[(1, 1)].bg, buf[(2, 2)].bg),
+            (Color::DarkGray, Color::DarkGray)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn draw_a_preview() -> anyhow::Result<()> {
+        let game = Game::blank("4:2".parse()?)?;
+        let clip = Game::clip_of("blinker")?;
+        let frontend = Frontend::default();
+        let mut buf = Buffer::empty(Rect::new(0, 0, 4, 2));
+        // it wraps around the edges.
+        BoardWidget::new(&game, &frontend, 1, &|_| false)
+            .preview(Some((&clip, (2, 1))))
+            .render(buf.area, &mut buf);
+        let cells: String = buf.content.iter().map(|c| c.symbol()).collect();
+        assert_eq!(cells, "----@-@@");
+        assert_eq!(
+            (buf[(0, 1)].fg, buf[(1, 1)].fg),
+            (Color::Green, Color::Reset)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn draw_rulers() -> anyhow::Result<()> {
+        let mut game = Game::blank("12:11".parse()?)?;
+        game.set_pos((10, 10))?;
+        let frontend = Frontend {
+            rulers: true,
+            ..Frontend::default()
+        };
+        let mut buf = Buffer::empty(Rect::new(0, 0, 15, 12));
+        let board = BoardWidget::new(&game, &frontend, 1, &|_| false);
+        assert_eq!(board.cell_at(buf.area, (10, 10)), (13, 11));
+        board.render(buf.area, &mut buf);
+        let row = |y| (0..15).map(|x| buf[(x, y)].symbol()).collect::<String>();
+        assert_eq!(row(0), "   012345678901");
+        assert_eq!(row(1), " 0 ------------");
+        assert_eq!(row(11), "10 ----------@-");
+        assert_eq!(buf[(13, 0)].fg, Color::Reset);
+        assert_eq!(buf[(4, 0)].fg, Color::DarkGray);
+        Ok(())
+    }
+
+    #[test]
+    fn lay_out_the_view() -> anyhow::Result<()> {
+        let mut game = Game::blank("4:2".parse()?)?;
+        game.set_pos((1, 1))?;
+        let mut buf = Buffer::empty(Rect::new(0, 0, 20, 6));
+        let mut notes = Notifications::default();
+        notes.error("no such file");
+        let board = view(
+            buf.area,
+            &mut buf,
+            Snapshot::of(&game, &Frontend::default()).board(),
+            StatusBar {
+                status: "status",
+                hints: "a\tb",
+            },
+            LogArea::new(notes.recent()),
+        );
+        // the note wraps onto two rows.
+        assert_eq!(board, Rect::new(0, 0, 20, 2));
+        let rows: Vec<String> = (0..6)
+            .map(|y| (0..10).map(|x| buf[(x, y)].symbol()).collect())
+            .collect();
+        assert_eq!(
+            rows[..4],
+            ["----      ", "-@--      ", "status    ", "a       b "]
+        );
+        assert_eq!(rows[5], "such file ");
+        assert_eq!(buf[(0, 4)].fg, Color::Red);
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+//! two player games: the players take turns placing cells of their colour on an Immigration
+//! board, which then runs for a while. every round scores the live cells of each colour.
+
+use anyhow::{ensure, Result};
+use life_game::Game;
+
+/// colour names of the players' cells, as drawn under Immigration.
+const PLAYERS: [&str; 2] = ["red", "blue"];
+
+/// what placing a cell led to.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Turn {
+    /// the player has cells left to place.
+    Placing,
+    /// the other player places their cells.
+    Next,
+    /// both players placed their cells, so the board runs.
+    Run,
+}
+
+#[derive(Debug)]
+pub(crate) struct Versus {
+    /// the player placing cells, 1 or 2, which is also the colour of their cells.
+    player: u8,
+    /// cells the player can still place this round.
+    left: usize,
+    /// cells each player places per round.
+    cells: usize,
+    /// generations every round runs.
+    generations: usize,
+    /// the current round, from 1.
+    round: usize,
+    rounds: usize,
+    /// scores of the finished rounds, added up.
+    total: [usize; 2],
+}
+
+impl Versus {
+    pub(crate) fn new(cells: usize, generations: usize, rounds: usize) -> Self {
+        Self {
+            player: 1,
+            left: cells,
+            cells,
+            generations,
+            round: 1,
+            rounds,
+            total: [0; 2],
+        }
+    }
+
+    /// place a cell of the current player at `pos`, which must be dead.
+    pub(crate) fn place(&mut self, game: &mut Game, pos: (u16, u16)) -> Result<Turn> {
+        ensure!(
+            game.state_at(game.index(pos)?) == 0,
+            "{}:{} is taken.",
+            pos.0,
+            pos.1
+        );
+        game.set_state(pos, self.player)?;
+        self.left -= 1;
+        if self.left > 0 {
+            return Ok(Turn::Placing);
+        }
+        self.left = self.cells;
+        if self.player == 1 {
+            self.player = 2;
+            return Ok(Turn::Next);
+        }
+        self.player = 1;
+        game.state.limit = Some(game.state.time + self.generations);
+        Ok(Turn::Run)
+    }
+
+    /// live cells of each player.
+    pub(crate) fn score(game: &Game) -> [usize; 2] {
+        let mut score = [0; 2];
+        for state in game.states() {
+            if let 1 | 2 = state {
+                score[usize::from(state - 1)] += 1;
+            }
+        }
+        score
+    }
+
+    /// score the round which just ran. the message says how it went, and who won after the last
+    /// round, which also ends the game.
+    pub(crate) fn end_round(&mut self, game: &mut Game) -> (String, bool) {
+        game.state.limit = None;
+        let score = Self::score(game);
+        self.total[0] += score[0];
+        self.total[1] += score[1];
+        let mut message = format!(
+            "round {} of {}: {} {}, {} {}. total: {} {}, {} {}",
+            self.round,
+            self.rounds,
+            PLAYERS[0],
+            score[0],
+            PLAYERS[1],
+            score[1],
+            PLAYERS[0],
+            self.total[0],
+            PLAYERS[1],
+            self.total[1]
+        );
+        self.round += 1;
+        let over = self.round > self.rounds;
+        if over {
+            message.push_str(match self.total[0].cmp(&self.total[1]) {
+                std::cmp::Ordering::Greater => ". red wins!",
+                std::cmp::Ordering::Less => ". blue wins!",
+                std::cmp::Ordering::Equal => ". a draw.",
+            });
+        }
+        (message, over)
+    }
+
+    /// whose turn it is, for the status line.
+    pub(crate) fn status(&self) -> String {
+        format!(
+            "{} places {} more cells, round {} of {}",
+            PLAYERS[usize::from(self.player - 1)],
+            self.left,
+            self.round,
+            self.rounds
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use life_game::rule::Rule;
+
+    #[test]
+    fn take_turns_and_score() -> Result<()> {
+        let mut game = Game::blank("8:8".parse()?)?;
+        game.state.rule = Rule::immigration();
+        let mut versus = Versus::new(2, 5, 1);
+        assert_eq!(versus.place(&mut game, (1, 1))?, Turn::Placing);
+        assert!(versus.place(&mut game, (1, 1)).is_err());
+        assert_eq!(versus.place(&mut game, (2, 1))?, Turn::Next);
+        assert_eq!(versus.status(), "blue places 2 more cells, round 1 of 1");
+        versus.place(&mut game, (5, 5))?;
+        assert_eq!(versus.place(&mut game, (5, 6))?, Turn::Run);
+        assert_eq!(game.state.limit, Some(5));
+        assert_eq!(Versus::score(&game), [2, 2]);
+        // a red cell joins the blue pair, which grows into a block of mostly blue cells.
+        game.set_state((6, 5), 1)?;
+        game.skip(5)?;
+        let (message, over) = versus.end_round(&mut game);
+        assert!(over);
+        assert_eq!(
+            message,
+            "round 1 of 1: red 1, blue 3. total: red 1, blue 3. blue wins!"
+        );
+        assert_eq!(game.state.limit, None);
+        Ok(())
+    }
+}
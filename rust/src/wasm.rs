@@ -0,0 +1,111 @@
+//! javascript bindings. build them as a `cdylib`, which the no_std build can't be, and run
+//! `wasm-bindgen` on the output:
+//!
+//! ```sh
+//! cargo rustc --lib --release --features wasm --target wasm32-unknown-unknown --crate-type cdylib
+//! wasm-bindgen --target web target/wasm32-unknown-unknown/release/life_game.wasm --out-dir web
+//! ```
+//!
+//! ```js
+//! const life = new Life(80, 40);
+//! life.set(1, 0, true);
+//! life.step();
+//! const cells = life.cells(); // Uint8Array, one state per cell in rows of life.width
+//! ```
+
+use crate::{Game, Size};
+use wasm_bindgen::prelude::*;
+
+/// a game driven from javascript.
+#[wasm_bindgen]
+pub struct Life {
+    game: Game,
+}
+
+fn js(e: anyhow::Error) -> JsError {
+    JsError::new(&format!("{:#}", e))
+}
+
+#[wasm_bindgen]
+impl Life {
+    /// an empty board of `width` x `height`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: u16, height: u16) -> Result<Life, JsError> {
+        let game = Game::blank(Size { width, height }).map_err(js)?;
+        Ok(Self { game })
+    }
+
+    /// advance one generation.
+    pub fn step(&mut self) -> Result<(), JsError> {
+        self.game.step().map_err(js)
+    }
+
+    /// make the cell at `x`, `y` alive or dead.
+    pub fn set(&mut self, x: u16, y: u16, alive: bool) -> Result<(), JsError> {
+        self.game.set_cell((x, y), alive).map_err(js)
+    }
+
+    /// replace the rule, e.g. `"B36/S23"` or `"345/2/4"`.
+    #[wasm_bindgen(js_name = setRule)]
+    pub fn set_rule(&mut self, rule: &str) -> Result<(), JsError> {
+        self.game.state.rule = rule.parse().map_err(js)?;
+        Ok(())
+    }
+
+    /// a copy of the cells: 1 for alive, 0 for dead, 2 and up for dying, or the colour of a live
+    /// cell under a coloured rule, in rows of `width`.
+    pub fn cells(&self) -> Vec<u8> {
+        self.game.states()
+    }
+
+    /// where the cells are in wasm memory, to view them without a copy. one byte per cell, 1 for
+    /// alive and 0 for the rest, in rows of `width`. only valid until the next call which changes
+    /// the board.
+    #[wasm_bindgen(js_name = cellsPtr)]
+    pub fn cells_ptr(&self) -> *const bool {
+        self.game.game.as_ptr()
+    }
+
+    /// columns of the board.
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> u16 {
+        self.game.state.size.width
+    }
+
+    /// rows of the board.
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> u16 {
+        self.game.state.size.height
+    }
+
+    /// generation of the board.
+    #[wasm_bindgen(getter)]
+    pub fn generation(&self) -> usize {
+        self.game.state.time
+    }
+
+    /// live cells on the board.
+    #[wasm_bindgen(getter)]
+    pub fn population(&self) -> usize {
+        self.game.state.population
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // only the paths which don't create javascript values run outside of wasm.
+    #[test]
+    fn drive_blinker() {
+        let mut life = Life::new(5, 5).ok().unwrap();
+        for x in 1..4 {
+            life.set(x, 2, true).ok().unwrap();
+        }
+        life.step().ok().unwrap();
+        assert_eq!((life.generation(), life.population()), (1, 3));
+        let cells = life.cells();
+        assert_eq!(cells.len(), 25);
+        assert!([7, 12, 17].iter().all(|&i| cells[i] == 1));
+    }
+}